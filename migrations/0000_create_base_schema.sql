@@ -0,0 +1,84 @@
+-- mirrors the tables of the production database, so the app can run against a local postgis
+CREATE EXTENSION IF NOT EXISTS postgis;
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE TABLE IF NOT EXISTS users (
+    id BIGSERIAL PRIMARY KEY,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    email TEXT NOT NULL UNIQUE,
+    password TEXT,
+    username TEXT,
+    address TEXT,
+    location GEOGRAPHY(POINT, 4326),
+    confirmation_token TEXT
+);
+
+CREATE TABLE IF NOT EXISTS contacts (
+    id BIGSERIAL PRIMARY KEY,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    by_user_id BIGINT NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    to_user_id BIGINT NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    state TEXT NOT NULL,
+    UNIQUE (by_user_id, to_user_id)
+);
+
+CREATE TABLE IF NOT EXISTS products (
+    id TEXT PRIMARY KEY,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    name TEXT NOT NULL,
+    images TEXT[] NOT NULL DEFAULT '{}',
+    url TEXT NOT NULL,
+    market_id BIGINT NOT NULL,
+    price INTEGER,
+    currency TEXT,
+    full_text_search TSVECTOR GENERATED ALWAYS AS (to_tsvector('german', name)) STORED
+);
+
+CREATE INDEX IF NOT EXISTS products_name_trgm_idx ON products USING GIN (name gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS products_full_text_search_idx ON products USING GIN (full_text_search);
+
+CREATE TABLE IF NOT EXISTS prices (
+    id BIGSERIAL PRIMARY KEY,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    product_id TEXT NOT NULL REFERENCES products (id) ON DELETE CASCADE,
+    price INTEGER,
+    currency TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS prices_product_id_created_at_idx ON prices (product_id, created_at DESC);
+
+CREATE TABLE IF NOT EXISTS shopping_lists (
+    id BIGSERIAL PRIMARY KEY,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    name TEXT NOT NULL,
+    user_id BIGINT NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    emoji_presentation TEXT
+);
+
+CREATE TABLE IF NOT EXISTS shopping_lists_items (
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    product_id TEXT NOT NULL REFERENCES products (id) ON DELETE CASCADE,
+    shopping_list_id BIGINT NOT NULL REFERENCES shopping_lists (id) ON DELETE CASCADE,
+    amount INTEGER NOT NULL DEFAULT 1,
+    PRIMARY KEY (shopping_list_id, product_id)
+);
+
+CREATE TABLE IF NOT EXISTS user_selected_shopping_list (
+    user_id BIGINT PRIMARY KEY REFERENCES users (id) ON DELETE CASCADE,
+    id BIGINT NOT NULL REFERENCES shopping_lists (id) ON DELETE CASCADE
+);
+
+-- returns 1 if the product was added to the list, 0 if it was removed
+CREATE OR REPLACE FUNCTION toggle_shopping_list_item(p_product_id TEXT, p_shopping_list_id BIGINT)
+RETURNS SMALLINT AS $$
+BEGIN
+    DELETE FROM shopping_lists_items
+    WHERE product_id = p_product_id AND shopping_list_id = p_shopping_list_id;
+    IF FOUND THEN
+        RETURN 0;
+    END IF;
+
+    INSERT INTO shopping_lists_items (product_id, shopping_list_id) VALUES (p_product_id, p_shopping_list_id);
+    RETURN 1;
+END;
+$$ LANGUAGE plpgsql;
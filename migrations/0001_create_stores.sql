@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS stores (
+    id BIGSERIAL PRIMARY KEY,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    market_id BIGINT NOT NULL,
+    name TEXT NOT NULL,
+    address TEXT NOT NULL,
+    location GEOGRAPHY(POINT, 4326) NOT NULL,
+    -- [{"weekday": 0, "opens": "07:00", "closes": "21:00"}, ..], weekday 0 is monday
+    opening_hours JSONB NOT NULL DEFAULT '[]'::JSONB
+);
+
+CREATE INDEX IF NOT EXISTS stores_location_idx ON stores USING GIST (location);
+CREATE INDEX IF NOT EXISTS stores_market_id_idx ON stores (market_id);
@@ -0,0 +1,3 @@
+-- price per base unit (e.g. per kg or l) in cents, as printed on the shelf label
+ALTER TABLE products ADD COLUMN IF NOT EXISTS unit_price INTEGER;
+ALTER TABLE products ADD COLUMN IF NOT EXISTS unit TEXT;
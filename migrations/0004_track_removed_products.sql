@@ -0,0 +1,24 @@
+-- set by the link check once a product's page is gone from its market, cleared when an import sees it again
+ALTER TABLE products ADD COLUMN IF NOT EXISTS removed_at TIMESTAMPTZ;
+-- set by the link check for items whose product is removed, shown on the shopping list
+ALTER TABLE shopping_lists_items ADD COLUMN IF NOT EXISTS stale_at TIMESTAMPTZ;
+
+-- deleted products keep their name, so links to them can still suggest similar products
+CREATE TABLE IF NOT EXISTS deleted_products (
+    id TEXT PRIMARY KEY,
+    deleted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    name TEXT NOT NULL,
+    market_id BIGINT NOT NULL
+);
+
+CREATE OR REPLACE FUNCTION remember_deleted_product() RETURNS TRIGGER AS $$
+BEGIN
+    INSERT INTO deleted_products (id, name, market_id) VALUES (OLD.id, OLD.name, OLD.market_id)
+    ON CONFLICT (id) DO UPDATE SET deleted_at = NOW(), name = EXCLUDED.name, market_id = EXCLUDED.market_id;
+    RETURN OLD;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE TRIGGER products_remember_deleted
+    AFTER DELETE ON products
+    FOR EACH ROW EXECUTE FUNCTION remember_deleted_product();
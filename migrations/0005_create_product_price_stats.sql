@@ -0,0 +1,58 @@
+-- per product price stats for the deal badges, refreshed after every import by `REFRESH MATERIALIZED VIEW CONCURRENTLY`.
+-- the 90 day median is taken over the daily prices, the prices table only has a row per change.
+CREATE MATERIALIZED VIEW IF NOT EXISTS product_price_stats AS
+WITH daily_prices AS (
+    SELECT
+        products.id AS product_id,
+        prices_by_day.price
+    FROM
+        products
+        CROSS JOIN generate_series(
+            date_trunc('day', NOW()) - INTERVAL '89 days',
+            date_trunc('day', NOW()),
+            INTERVAL '1 day'
+        ) AS days (day)
+        JOIN LATERAL (
+            SELECT prices.price
+            FROM prices
+            WHERE prices.product_id = products.id AND prices.created_at < days.day + INTERVAL '1 day'
+            ORDER BY prices.created_at DESC
+            LIMIT 1
+        ) AS prices_by_day ON TRUE
+    WHERE
+        prices_by_day.price IS NOT NULL
+),
+medians AS (
+    SELECT
+        product_id,
+        ROUND(PERCENTILE_CONT(0.5) WITHIN GROUP (ORDER BY price))::INTEGER AS median_price
+    FROM daily_prices
+    GROUP BY product_id
+),
+extremes AS (
+    SELECT
+        product_id,
+        MIN(price) AS all_time_low,
+        MAX(price) AS all_time_high
+    FROM prices
+    WHERE price IS NOT NULL
+    GROUP BY product_id
+)
+SELECT
+    products.id AS product_id,
+    extremes.all_time_low,
+    extremes.all_time_high,
+    medians.median_price,
+    -- how much cheaper the current price is than the median, negative if it's more expensive
+    CASE
+        WHEN medians.median_price > 0 THEN ROUND((medians.median_price - products.price) * 100.0 / medians.median_price)::INTEGER
+    END AS discount_percent
+FROM
+    products
+    JOIN extremes ON extremes.product_id = products.id
+    LEFT JOIN medians ON medians.product_id = products.id
+WHERE
+    products.price IS NOT NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS product_price_stats_product_id_idx ON product_price_stats (product_id);
+CREATE INDEX IF NOT EXISTS product_price_stats_discount_percent_idx ON product_price_stats (discount_percent DESC);
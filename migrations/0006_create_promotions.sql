@@ -0,0 +1,91 @@
+CREATE TABLE IF NOT EXISTS promotions (
+    id BIGSERIAL PRIMARY KEY,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    product_id TEXT NOT NULL REFERENCES products (id) ON DELETE CASCADE,
+    starts_at TIMESTAMPTZ NOT NULL,
+    ends_at TIMESTAMPTZ NOT NULL,
+    -- in cents per item, for a multi buy per item of the bundle
+    price INTEGER NOT NULL,
+    currency TEXT NOT NULL,
+    -- "loyalty_card" or "multi_buy" with buy_quantity and pay_quantity, NULL for everybody
+    condition TEXT,
+    buy_quantity INTEGER,
+    pay_quantity INTEGER,
+    UNIQUE (product_id, starts_at),
+    CHECK (ends_at > starts_at)
+);
+
+CREATE INDEX IF NOT EXISTS promotions_product_id_ends_at_idx ON promotions (product_id, ends_at);
+
+-- prices recorded during a promotion at its price are promotion prices, the stats only take regular prices.
+-- inlined into both of its uses, so the daily lookup can walk the index on prices instead of scanning the whole CTE
+DROP MATERIALIZED VIEW IF EXISTS product_price_stats;
+CREATE MATERIALIZED VIEW product_price_stats AS
+WITH regular_prices AS NOT MATERIALIZED (
+    SELECT prices.*
+    FROM prices
+    WHERE NOT EXISTS (
+        SELECT 1
+        FROM promotions
+        WHERE
+            promotions.product_id = prices.product_id
+            AND prices.created_at >= promotions.starts_at
+            AND prices.created_at < promotions.ends_at
+            AND prices.price = promotions.price
+    )
+),
+daily_prices AS (
+    SELECT
+        products.id AS product_id,
+        prices_by_day.price
+    FROM
+        products
+        CROSS JOIN generate_series(
+            date_trunc('day', NOW()) - INTERVAL '89 days',
+            date_trunc('day', NOW()),
+            INTERVAL '1 day'
+        ) AS days (day)
+        JOIN LATERAL (
+            SELECT regular_prices.price
+            FROM regular_prices
+            WHERE regular_prices.product_id = products.id AND regular_prices.created_at < days.day + INTERVAL '1 day'
+            ORDER BY regular_prices.created_at DESC
+            LIMIT 1
+        ) AS prices_by_day ON TRUE
+    WHERE
+        prices_by_day.price IS NOT NULL
+),
+medians AS (
+    SELECT
+        product_id,
+        ROUND(PERCENTILE_CONT(0.5) WITHIN GROUP (ORDER BY price))::INTEGER AS median_price
+    FROM daily_prices
+    GROUP BY product_id
+),
+extremes AS (
+    SELECT
+        product_id,
+        MIN(price) AS all_time_low,
+        MAX(price) AS all_time_high
+    FROM regular_prices
+    WHERE price IS NOT NULL
+    GROUP BY product_id
+)
+SELECT
+    products.id AS product_id,
+    extremes.all_time_low,
+    extremes.all_time_high,
+    medians.median_price,
+    -- how much cheaper the current price is than the median, negative if it's more expensive
+    CASE
+        WHEN medians.median_price > 0 THEN ROUND((medians.median_price - products.price) * 100.0 / medians.median_price)::INTEGER
+    END AS discount_percent
+FROM
+    products
+    JOIN extremes ON extremes.product_id = products.id
+    LEFT JOIN medians ON medians.product_id = products.id
+WHERE
+    products.price IS NOT NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS product_price_stats_product_id_idx ON product_price_stats (product_id);
+CREATE INDEX IF NOT EXISTS product_price_stats_discount_percent_idx ON product_price_stats (discount_percent DESC);
@@ -0,0 +1,29 @@
+-- the unit of an item's amount, e.g. "g" for 500 g of minced meat, NULL means pieces
+ALTER TABLE shopping_lists_items ADD COLUMN IF NOT EXISTS amount_unit TEXT;
+
+ALTER TABLE shopping_lists_items DROP CONSTRAINT IF EXISTS shopping_lists_items_amount_check;
+ALTER TABLE shopping_lists_items ADD CONSTRAINT shopping_lists_items_amount_check CHECK (amount > 0);
+
+-- the amount is only used when the product is added
+DROP FUNCTION IF EXISTS toggle_shopping_list_item(TEXT, BIGINT);
+
+-- returns 1 if the product was added to the list, 0 if it was removed
+CREATE OR REPLACE FUNCTION toggle_shopping_list_item(
+    p_product_id TEXT,
+    p_shopping_list_id BIGINT,
+    p_amount INTEGER,
+    p_amount_unit TEXT
+)
+RETURNS SMALLINT AS $$
+BEGIN
+    DELETE FROM shopping_lists_items
+    WHERE product_id = p_product_id AND shopping_list_id = p_shopping_list_id;
+    IF FOUND THEN
+        RETURN 0;
+    END IF;
+
+    INSERT INTO shopping_lists_items (product_id, shopping_list_id, amount, amount_unit)
+    VALUES (p_product_id, p_shopping_list_id, p_amount, p_amount_unit);
+    RETURN 1;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,27 @@
+-- set while shopping when the item is in the cart, cleared again by unchecking it
+ALTER TABLE shopping_lists_items ADD COLUMN IF NOT EXISTS checked_at TIMESTAMPTZ;
+
+-- a finished trip to the shops, its checked items are recorded in shopping_trip_items and unchecked on the list
+CREATE TABLE IF NOT EXISTS shopping_trips (
+    id BIGSERIAL PRIMARY KEY,
+    shopping_list_id BIGINT REFERENCES shopping_lists (id) ON DELETE SET NULL,
+    user_id BIGINT NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    -- the first item checked off
+    started_at TIMESTAMPTZ NOT NULL,
+    finished_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS shopping_trips_user_id_idx ON shopping_trips (user_id, finished_at DESC);
+
+-- what was bought at which price, products may be deleted later so their name is kept
+CREATE TABLE IF NOT EXISTS shopping_trip_items (
+    shopping_trip_id BIGINT NOT NULL REFERENCES shopping_trips (id) ON DELETE CASCADE,
+    product_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    amount INTEGER NOT NULL,
+    amount_unit TEXT,
+    price INTEGER,
+    currency TEXT,
+    checked_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (shopping_trip_id, product_id)
+);
@@ -0,0 +1,24 @@
+-- items may be free text, e.g. "Brötchen vom Bäcker", until they are linked to a product
+ALTER TABLE shopping_lists_items ADD COLUMN IF NOT EXISTS id BIGSERIAL;
+ALTER TABLE shopping_lists_items ADD COLUMN IF NOT EXISTS name TEXT;
+ALTER TABLE shopping_lists_items ADD COLUMN IF NOT EXISTS note TEXT;
+
+ALTER TABLE shopping_lists_items DROP CONSTRAINT IF EXISTS shopping_lists_items_pkey;
+ALTER TABLE shopping_lists_items ADD PRIMARY KEY (id);
+-- NULLs are distinct, so a list may hold any number of free text items
+ALTER TABLE shopping_lists_items DROP CONSTRAINT IF EXISTS shopping_lists_items_shopping_list_id_product_id_key;
+ALTER TABLE shopping_lists_items ADD CONSTRAINT shopping_lists_items_shopping_list_id_product_id_key
+    UNIQUE (shopping_list_id, product_id);
+
+-- the amount of a free text item is optional, products always have one
+ALTER TABLE shopping_lists_items ALTER COLUMN product_id DROP NOT NULL;
+ALTER TABLE shopping_lists_items ALTER COLUMN amount DROP NOT NULL;
+ALTER TABLE shopping_lists_items DROP CONSTRAINT IF EXISTS shopping_lists_items_product_or_name_check;
+ALTER TABLE shopping_lists_items ADD CONSTRAINT shopping_lists_items_product_or_name_check
+    CHECK ((product_id IS NOT NULL AND amount IS NOT NULL) OR (product_id IS NULL AND name IS NOT NULL));
+
+-- free text items end up in trips as well
+ALTER TABLE shopping_trip_items DROP CONSTRAINT IF EXISTS shopping_trip_items_pkey;
+ALTER TABLE shopping_trip_items ALTER COLUMN product_id DROP NOT NULL;
+ALTER TABLE shopping_trip_items ALTER COLUMN amount DROP NOT NULL;
+CREATE INDEX IF NOT EXISTS shopping_trip_items_shopping_trip_id_idx ON shopping_trip_items (shopping_trip_id);
@@ -0,0 +1,43 @@
+-- confirmed contacts a list is shared with, viewers may only look at it, editors may change its items
+CREATE TABLE IF NOT EXISTS shopping_list_members (
+    shopping_list_id BIGINT NOT NULL REFERENCES shopping_lists (id) ON DELETE CASCADE,
+    user_id BIGINT NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    role TEXT NOT NULL CHECK (role IN ('viewer', 'editor')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (shopping_list_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS shopping_list_members_user_id_idx ON shopping_list_members (user_id);
+
+-- everybody with access to a list and their role, every shopping list query checks its permissions against it
+CREATE OR REPLACE VIEW shopping_lists_access AS
+SELECT
+    shopping_lists.id AS shopping_list_id,
+    shopping_lists.user_id,
+    'owner' AS role
+FROM
+    shopping_lists
+UNION ALL
+SELECT
+    shopping_list_members.shopping_list_id,
+    shopping_list_members.user_id,
+    shopping_list_members.role
+FROM
+    shopping_list_members;
+
+-- what the collaborators of a list changed, a row per recipient until they've seen it.
+-- the subject is what was changed, e.g. a product's name, repeated changes of it are folded into the unread row
+CREATE TABLE IF NOT EXISTS shopping_list_notifications (
+    id BIGSERIAL PRIMARY KEY,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    shopping_list_id BIGINT NOT NULL REFERENCES shopping_lists (id) ON DELETE CASCADE,
+    user_id BIGINT NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    by_user_id BIGINT NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    change TEXT NOT NULL,
+    subject TEXT NOT NULL DEFAULT '',
+    read_at TIMESTAMPTZ
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS shopping_list_notifications_unread_idx
+    ON shopping_list_notifications (user_id, shopping_list_id, by_user_id, change, subject)
+    WHERE read_at IS NULL;
@@ -0,0 +1,70 @@
+-- every change to a list is sent on the "shopping_list_changes" channel, so all server instances can push it to
+-- the browsers that have the list open, whichever instance made the change. the payload is a ShoppingListEvent.
+CREATE OR REPLACE FUNCTION notify_shopping_list_item_change()
+RETURNS TRIGGER AS $$
+DECLARE
+    v_item shopping_lists_items;
+    v_kind TEXT;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        v_item := OLD;
+        v_kind := 'item_removed';
+    ELSIF TG_OP = 'INSERT' THEN
+        v_item := NEW;
+        v_kind := 'item_added';
+    ELSE
+        v_item := NEW;
+        v_kind := CASE
+            -- a free text item turned into a product
+            WHEN OLD.product_id IS DISTINCT FROM NEW.product_id THEN 'item_linked'
+            WHEN OLD.checked_at IS DISTINCT FROM NEW.checked_at THEN 'item_checked'
+            ELSE 'item_updated'
+        END;
+    END IF;
+
+    PERFORM pg_notify('shopping_list_changes', json_build_object(
+        'shopping_list_id', v_item.shopping_list_id,
+        'kind', v_kind,
+        'product_id', v_item.product_id
+    )::TEXT);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS shopping_lists_items_notify_change ON shopping_lists_items;
+CREATE TRIGGER shopping_lists_items_notify_change
+    AFTER INSERT OR UPDATE OR DELETE ON shopping_lists_items
+    FOR EACH ROW EXECUTE FUNCTION notify_shopping_list_item_change();
+
+CREATE OR REPLACE FUNCTION notify_shopping_list_change()
+RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_notify('shopping_list_changes', json_build_object(
+        'shopping_list_id', COALESCE(NEW.id, OLD.id),
+        'kind', CASE WHEN TG_OP = 'DELETE' THEN 'list_deleted' ELSE 'list_renamed' END
+    )::TEXT);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS shopping_lists_notify_change ON shopping_lists;
+CREATE TRIGGER shopping_lists_notify_change
+    AFTER UPDATE OF name, emoji_presentation OR DELETE ON shopping_lists
+    FOR EACH ROW EXECUTE FUNCTION notify_shopping_list_change();
+
+-- a member that lost access is disconnected
+CREATE OR REPLACE FUNCTION notify_shopping_list_member_change()
+RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_notify('shopping_list_changes', json_build_object(
+        'shopping_list_id', COALESCE(NEW.shopping_list_id, OLD.shopping_list_id),
+        'kind', 'members_changed'
+    )::TEXT);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS shopping_list_members_notify_change ON shopping_list_members;
+CREATE TRIGGER shopping_list_members_notify_change
+    AFTER INSERT OR UPDATE OR DELETE ON shopping_list_members
+    FOR EACH ROW EXECUTE FUNCTION notify_shopping_list_member_change();
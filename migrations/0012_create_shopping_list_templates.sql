@@ -0,0 +1,59 @@
+-- a template isn't shopped with, it's copied into new lists, e.g. the weekly groceries
+ALTER TABLE shopping_lists ADD COLUMN IF NOT EXISTS is_template BOOLEAN NOT NULL DEFAULT FALSE;
+-- the template a list was created from
+ALTER TABLE shopping_lists ADD COLUMN IF NOT EXISTS template_id BIGINT REFERENCES shopping_lists (id) ON DELETE SET NULL;
+
+-- a template that's copied into a new list every week on this ISO weekday (1 = monday), see `preiskampf create-recurring-lists`
+ALTER TABLE shopping_lists ADD COLUMN IF NOT EXISTS recurring_weekday SMALLINT;
+-- the last day a list was created from the template, so running the command twice a day creates a single list
+ALTER TABLE shopping_lists ADD COLUMN IF NOT EXISTS recurred_on DATE;
+
+ALTER TABLE shopping_lists DROP CONSTRAINT IF EXISTS shopping_lists_recurring_weekday_check;
+ALTER TABLE shopping_lists ADD CONSTRAINT shopping_lists_recurring_weekday_check
+    CHECK (recurring_weekday IS NULL OR (is_template AND recurring_weekday BETWEEN 1 AND 7));
+
+CREATE INDEX IF NOT EXISTS shopping_lists_recurring_weekday_idx ON shopping_lists (recurring_weekday)
+    WHERE recurring_weekday IS NOT NULL;
+
+-- copies a list with all of its items into a new list of the user, unchecked. returns the id of the copy
+CREATE OR REPLACE FUNCTION copy_shopping_list(
+    p_shopping_list_id BIGINT,
+    p_user_id BIGINT,
+    p_name TEXT,
+    p_is_template BOOLEAN
+)
+RETURNS BIGINT AS $$
+DECLARE
+    v_id BIGINT;
+BEGIN
+    INSERT INTO shopping_lists (user_id, name, emoji_presentation, is_template, template_id)
+    SELECT
+        p_user_id,
+        p_name,
+        shopping_lists.emoji_presentation,
+        p_is_template,
+        CASE WHEN shopping_lists.is_template THEN shopping_lists.id END
+    FROM
+        shopping_lists
+    WHERE
+        shopping_lists.id = p_shopping_list_id
+    RETURNING id INTO v_id;
+
+    -- created_at is kept, it's the order of the list
+    INSERT INTO shopping_lists_items (created_at, shopping_list_id, product_id, amount, amount_unit, name, note)
+    SELECT
+        shopping_lists_items.created_at,
+        v_id,
+        shopping_lists_items.product_id,
+        shopping_lists_items.amount,
+        shopping_lists_items.amount_unit,
+        shopping_lists_items.name,
+        shopping_lists_items.note
+    FROM
+        shopping_lists_items
+    WHERE
+        shopping_lists_items.shopping_list_id = p_shopping_list_id;
+
+    RETURN v_id;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,127 @@
+-- the place of an item on its list, lower comes first. products and free text items are listed apart but share the
+-- numbers, moving items only swaps the positions among them
+ALTER TABLE shopping_lists_items ADD COLUMN IF NOT EXISTS position INTEGER;
+
+-- the newest item on top, as they were listed before
+UPDATE shopping_lists_items SET position = numbered.position
+FROM (
+    SELECT id, ROW_NUMBER() OVER (PARTITION BY shopping_list_id ORDER BY created_at DESC, id DESC) AS position
+    FROM shopping_lists_items
+) AS numbered
+WHERE shopping_lists_items.id = numbered.id AND shopping_lists_items.position IS NULL;
+
+-- a new item goes on top of its list, unless it brings its position along
+CREATE OR REPLACE FUNCTION set_shopping_list_item_position()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.position IS NULL THEN
+        SELECT COALESCE(MIN(position), 1) - 1 INTO NEW.position
+        FROM shopping_lists_items
+        WHERE shopping_list_id = NEW.shopping_list_id;
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS shopping_lists_items_set_position ON shopping_lists_items;
+CREATE TRIGGER shopping_lists_items_set_position
+    BEFORE INSERT ON shopping_lists_items
+    FOR EACH ROW EXECUTE FUNCTION set_shopping_list_item_position();
+
+ALTER TABLE shopping_lists_items ALTER COLUMN position SET NOT NULL;
+CREATE INDEX IF NOT EXISTS shopping_lists_items_position_idx ON shopping_lists_items (shopping_list_id, position);
+
+-- how the items are put under headings while shopping, "category" or "market". NULL keeps the order of the list
+ALTER TABLE shopping_lists ADD COLUMN IF NOT EXISTS item_grouping TEXT;
+ALTER TABLE shopping_lists DROP CONSTRAINT IF EXISTS shopping_lists_item_grouping_check;
+ALTER TABLE shopping_lists ADD CONSTRAINT shopping_lists_item_grouping_check
+    CHECK (item_grouping IS NULL OR item_grouping IN ('category', 'market'));
+-- the store the list is shopped at, its categories follow the aisles of the store
+ALTER TABLE shopping_lists ADD COLUMN IF NOT EXISTS store_id BIGINT REFERENCES stores (id) ON DELETE SET NULL;
+
+-- the order a user walks through the aisles of a store, by top level category. categories without one come after
+CREATE TABLE IF NOT EXISTS store_aisles (
+    user_id BIGINT NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    store_id BIGINT NOT NULL REFERENCES stores (id) ON DELETE CASCADE,
+    category_id BIGINT NOT NULL REFERENCES categories (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (user_id, store_id, category_id)
+);
+
+-- moving items is pushed as a change of its own, the product list of the detail page doesn't follow it
+CREATE OR REPLACE FUNCTION notify_shopping_list_item_change()
+RETURNS TRIGGER AS $$
+DECLARE
+    v_item shopping_lists_items;
+    v_kind TEXT;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        v_item := OLD;
+        v_kind := 'item_removed';
+    ELSIF TG_OP = 'INSERT' THEN
+        v_item := NEW;
+        v_kind := 'item_added';
+    ELSE
+        v_item := NEW;
+        v_kind := CASE
+            -- a free text item turned into a product
+            WHEN OLD.product_id IS DISTINCT FROM NEW.product_id THEN 'item_linked'
+            WHEN OLD.checked_at IS DISTINCT FROM NEW.checked_at THEN 'item_checked'
+            WHEN OLD.position IS DISTINCT FROM NEW.position THEN 'item_moved'
+            ELSE 'item_updated'
+        END;
+    END IF;
+
+    PERFORM pg_notify('shopping_list_changes', json_build_object(
+        'shopping_list_id', v_item.shopping_list_id,
+        'kind', v_kind,
+        'product_id', v_item.product_id
+    )::TEXT);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+-- the copy keeps the order and the grouping of the list
+CREATE OR REPLACE FUNCTION copy_shopping_list(
+    p_shopping_list_id BIGINT,
+    p_user_id BIGINT,
+    p_name TEXT,
+    p_is_template BOOLEAN
+)
+RETURNS BIGINT AS $$
+DECLARE
+    v_id BIGINT;
+BEGIN
+    INSERT INTO shopping_lists (user_id, name, emoji_presentation, is_template, template_id, item_grouping, store_id)
+    SELECT
+        p_user_id,
+        p_name,
+        shopping_lists.emoji_presentation,
+        p_is_template,
+        CASE WHEN shopping_lists.is_template THEN shopping_lists.id END,
+        shopping_lists.item_grouping,
+        shopping_lists.store_id
+    FROM
+        shopping_lists
+    WHERE
+        shopping_lists.id = p_shopping_list_id
+    RETURNING id INTO v_id;
+
+    INSERT INTO shopping_lists_items (created_at, shopping_list_id, product_id, amount, amount_unit, name, note, position)
+    SELECT
+        shopping_lists_items.created_at,
+        v_id,
+        shopping_lists_items.product_id,
+        shopping_lists_items.amount,
+        shopping_lists_items.amount_unit,
+        shopping_lists_items.name,
+        shopping_lists_items.note,
+        shopping_lists_items.position
+    FROM
+        shopping_lists_items
+    WHERE
+        shopping_lists_items.shopping_list_id = p_shopping_list_id;
+
+    RETURN v_id;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,8 @@
+-- when an import last saw the product, the price history only grows when the price changes
+ALTER TABLE products ADD COLUMN IF NOT EXISTS checked_at TIMESTAMPTZ;
+
+UPDATE products SET checked_at = COALESCE(
+    (SELECT MAX(prices.created_at) FROM prices WHERE prices.product_id = products.id),
+    products.created_at
+)
+WHERE products.checked_at IS NULL;
@@ -0,0 +1,40 @@
+import { HtmxEventListener } from "../lib/HtmxEventListener";
+
+// keeps the time left until the "datetime" of a <time> up to date, same format as Promotion::format_countdown
+class Countdown extends HtmxEventListener {
+    constructor(el, options) {
+        super(el, options);
+
+        this.target = new Date(this.el.getAttribute("datetime"));
+        this.intervalHandle = setInterval(() => this.update(), 60 * 1000);
+        this.update();
+    }
+
+    update() {
+        if (!this.el?.isConnected) {
+            clearInterval(this.intervalHandle);
+            return;
+        }
+
+        const totalMinutes = Math.max(0, Math.floor((this.target - Date.now()) / 60000));
+        const days = Math.floor(totalMinutes / (24 * 60));
+        const hours = Math.floor(totalMinutes / 60) % 24;
+        const minutes = totalMinutes % 60;
+
+        if (days === 0 && hours === 0) {
+            this.el.textContent = `${minutes} Min.`;
+        } else if (days === 0) {
+            this.el.textContent = `${hours} Std. ${minutes} Min.`;
+        } else if (days === 1) {
+            this.el.textContent = `1 Tag ${hours} Std.`;
+        } else {
+            this.el.textContent = `${days} Tage ${hours} Std.`;
+        }
+
+        if (totalMinutes === 0) {
+            clearInterval(this.intervalHandle);
+        }
+    }
+}
+
+export { Countdown };
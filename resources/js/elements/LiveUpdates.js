@@ -0,0 +1,83 @@
+import { HtmxEventListener } from "../lib/HtmxEventListener";
+
+// swaps the fragments of a shopping list's event stream in, like htmx's out of band swaps. a "reload" event reloads the page:
+// - an element without "hx-swap-oob" or with hx-swap-oob="true" replaces the element with the same id
+// - hx-swap-oob="delete" removes the element with the same id
+// - hx-swap-oob="<innerHTML|afterbegin|beforeend>[:<selector>]" inserts the element's children into the selected
+//   element, the one with the same id if there's no selector. elements with the ids of the inserted ones are removed first
+class LiveUpdates extends HtmxEventListener {
+    constructor(el, options) {
+        super(el, options);
+
+        this.source = new EventSource(this.el.getAttribute("xui-src"));
+        this.source.addEventListener("update", e => this.swap(e.data));
+        // changes were missed and the open page can't tell which, it's loaded again
+        this.source.addEventListener("reload", () => {
+            this.close();
+            window.location.reload();
+        });
+        this.source.addEventListener("close", e => {
+            this.swap(e.data);
+            this.close();
+        });
+
+        this.on("htmx:beforeCleanupElement", e => {
+            if (e.target === this.el) {
+                this.close();
+            }
+        });
+    }
+
+    swap(html) {
+        if (!this.el?.isConnected) {
+            this.close();
+            return;
+        }
+
+        const template = document.createElement("template");
+        template.innerHTML = html;
+        Array.from(template.content.children).forEach(fragment => {
+            const [swapStyle, selector] = (fragment.getAttribute("hx-swap-oob") || "outerHTML").split(/:(.*)/);
+            fragment.removeAttribute("hx-swap-oob");
+            const target = selector ? document.querySelector(selector) : document.getElementById(fragment.id);
+            if (!target) {
+                return;
+            }
+
+            switch (swapStyle) {
+                case "delete":
+                    target.remove();
+                    break;
+                case "true":
+                case "outerHTML":
+                    target.replaceWith(fragment);
+                    this.process(fragment);
+                    break;
+                default: {
+                    const children = Array.from(fragment.children);
+                    children.forEach(child => child.id && document.getElementById(child.id)?.remove());
+                    if (swapStyle === "innerHTML") {
+                        target.replaceChildren(...fragment.childNodes);
+                    } else if (swapStyle === "afterbegin") {
+                        target.prepend(...fragment.childNodes);
+                    } else {
+                        target.append(...fragment.childNodes);
+                    }
+                    children.forEach(child => this.process(child));
+                }
+            }
+        });
+    }
+
+    process(el) {
+        window.htmx.process(el);
+        window.htmx.trigger(el, "htmx:load");
+    }
+
+    close() {
+        this.source?.close();
+        delete this.source;
+    }
+}
+
+export { LiveUpdates };
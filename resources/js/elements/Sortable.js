@@ -0,0 +1,116 @@
+import { HtmxEventListener } from "../lib/HtmxEventListener";
+
+const KEYBOARD_STEPS = { ArrowUp: -1, ArrowDown: 1 };
+
+// lets the children of a list be dragged into another order by their "xui-sort-handle", or moved with the arrow keys
+// while the handle has the focus. after a move the "xui-sort-key" of every child is sent to "xui-src" as the
+// "xui-name" field, along with the fields of "xui-vals"
+class Sortable extends HtmxEventListener {
+    constructor(el, options) {
+        super(el, options);
+
+        this.dragged = null;
+        this.on("pointerdown", this.onPointerDown.bind(this));
+        this.on("pointermove", this.onPointerMove.bind(this));
+        this.on("pointerup", this.onPointerUp.bind(this));
+        this.on("pointercancel", this.onPointerUp.bind(this));
+        this.on("keydown", this.onKeyDown.bind(this));
+    }
+
+    // children pushed in by live updates are included, they are looked up every time
+    getItems() {
+        return Array.from(this.el.children).filter(child => child.hasAttribute("xui-sort-key"));
+    }
+
+    getKeys() {
+        return this.getItems().map(item => item.getAttribute("xui-sort-key"));
+    }
+
+    getItemOfHandle(target) {
+        const item = target.closest("[xui-sort-handle]")?.closest("[xui-sort-key]");
+        return item?.parentElement === this.el ? item : null;
+    }
+
+    onPointerDown(e) {
+        const item = this.getItemOfHandle(e.target);
+        if (!item) {
+            return;
+        }
+
+        e.preventDefault();
+        // the handle keeps receiving the moves when the pointer leaves it
+        e.target.closest("[xui-sort-handle]").setPointerCapture(e.pointerId);
+        this.dragged = item;
+        this.keysBeforeDrag = this.getKeys().join();
+        item.classList.add("opacity-50");
+    }
+
+    onPointerMove(e) {
+        if (!this.dragged) {
+            return;
+        }
+
+        const target = this.getItems().find(item => {
+            const rect = item.getBoundingClientRect();
+            return item !== this.dragged && rect.top <= e.clientY && e.clientY <= rect.bottom;
+        });
+        if (!target) {
+            return;
+        }
+
+        const rect = target.getBoundingClientRect();
+        if (e.clientY < rect.top + rect.height / 2) {
+            target.before(this.dragged);
+        } else {
+            target.after(this.dragged);
+        }
+    }
+
+    onPointerUp() {
+        if (!this.dragged) {
+            return;
+        }
+
+        this.dragged.classList.remove("opacity-50");
+        this.dragged = null;
+        if (this.getKeys().join() !== this.keysBeforeDrag) {
+            this.save();
+        }
+    }
+
+    onKeyDown(e) {
+        const step = KEYBOARD_STEPS[e.key];
+        const item = step ? this.getItemOfHandle(e.target) : null;
+        if (!item) {
+            return;
+        }
+
+        const items = this.getItems();
+        const sibling = items[items.indexOf(item) + step];
+        if (!sibling) {
+            return;
+        }
+
+        e.preventDefault();
+        if (step < 0) {
+            sibling.before(item);
+        } else {
+            sibling.after(item);
+        }
+        e.target.focus();
+        this.save();
+    }
+
+    save() {
+        let values = {};
+        try {
+            values = JSON.parse(this.el.getAttribute("xui-vals") || "{}");
+        } catch (e) {/*ignore*/}
+        values[this.el.getAttribute("xui-name")] = this.getKeys();
+
+        // errors come with a notification, the list stays as it was dropped
+        window.htmx.ajax("PUT", this.el.getAttribute("xui-src"), { source: this.el, swap: "none", values });
+    }
+}
+
+export { Sortable };
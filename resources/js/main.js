@@ -8,6 +8,9 @@ import { Navigation } from "./elements/Navigation";
 import { NavigationToggle } from "./elements/NavigationToggle";
 import { AjaxFormInput } from "./elements/AjaxFormInput";
 import { GeocodingInput } from "./elements/GeocodingInput";
+import { Countdown } from "./elements/Countdown";
+import { LiveUpdates } from "./elements/LiveUpdates";
+import { Sortable } from "./elements/Sortable";
 
 const Elements = {
     Notification,
@@ -17,6 +20,9 @@ const Elements = {
     NavigationToggle,
     AjaxFormInput,
     GeocodingInput,
+    Countdown,
+    LiveUpdates,
+    Sortable,
 };
 
 htmx.defineExtension("http-header-enriched-ui", { onEvent: HttpHeaderEnrichedUiOnEvent });
@@ -0,0 +1,181 @@
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+use chrono::{Local, Utc};
+
+use crate::{
+    db,
+    model::{import::{parse_feed, FeedFormat}, market::Market},
+    services::{
+        import::import_feed_records,
+        link_check::check_listed_product_links,
+        product::refresh_price_stats,
+        shopping_list::create_recurring_shopping_lists,
+        scraper::{get_source, get_source_urls, scrape_market, Fetcher},
+    },
+};
+
+const IMPORT_USAGE: &str = "usage: preiskampf import [--dry-run] [--format csv|json] <feed file>...";
+const SCRAPE_USAGE: &str = "usage: preiskampf scrape [--dry-run] [--record <dir> | --replay <dir>] [<market>...]";
+const CHECK_LINKS_USAGE: &str = "usage: preiskampf check-links [--dry-run]";
+const CREATE_RECURRING_LISTS_USAGE: &str = "usage: preiskampf create-recurring-lists [--dry-run]";
+
+// subcommands run instead of the webserver, e.g. `cargo run -- import feeds/lidl.csv`
+pub fn is_command(args: &[String]) -> bool {
+    matches!(args.get(1).map(|arg| arg.as_str()), Some("import" | "scrape" | "check-links" | "create-recurring-lists"))
+}
+
+pub async fn run(args: &[String]) -> Result<(), Box<dyn Error>> {
+    match args.get(1).map(|arg| arg.as_str()) {
+        Some("import") => run_import(&args[2..]).await,
+        Some("scrape") => run_scrape(&args[2..]).await,
+        Some("check-links") => run_check_links(&args[2..]).await,
+        Some("create-recurring-lists") => run_create_recurring_lists(&args[2..]).await,
+        _ => Err(format!("{IMPORT_USAGE}\n{SCRAPE_USAGE}\n{CHECK_LINKS_USAGE}\n{CREATE_RECURRING_LISTS_USAGE}").into()),
+    }
+}
+
+async fn run_import(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut dry_run = false;
+    let mut format = None;
+    let mut paths = vec![];
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--dry-run" => dry_run = true,
+            "--format" => {
+                format = match args.next().map(|format| format.as_str()) {
+                    Some("csv") => Some(FeedFormat::Csv),
+                    Some("json") => Some(FeedFormat::Json),
+                    _ => return Err(IMPORT_USAGE.into()),
+                };
+            },
+            "-h" | "--help" => {
+                println!("{IMPORT_USAGE}");
+                return Ok(());
+            },
+            path => paths.push(path),
+        }
+    }
+    if paths.is_empty() {
+        return Err(IMPORT_USAGE.into());
+    }
+
+    // every file is parsed before anything is written, a broken feed imports nothing
+    let imported_at = Utc::now();
+    let mut records = vec![];
+    for path in paths {
+        let format = format.or_else(|| FeedFormat::from_path(path))
+            .ok_or_else(|| format!("{path}: unknown feed format, use --format csv|json"))?;
+        let content = fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+        let feed_records = parse_feed(content.as_str(), format, imported_at).map_err(|e| format!("{path}: {e}"))?;
+        println!("{path}: {} records", feed_records.len());
+        records.extend(feed_records);
+    }
+
+    let db_pool = db::init().await?;
+    let report = import_feed_records(&db_pool, records, dry_run).await?;
+    if !dry_run {
+        refresh_price_stats(&db_pool).await?;
+    }
+    report.get_changes().for_each(|diff| println!("{diff}"));
+    println!("{}{}", report.format_summary(), if dry_run { " (dry run, nothing saved)" } else { "" });
+
+    Ok(())
+}
+
+// scrapes the pages in /config/scraper.json of every given market (all by default) and imports them like a feed,
+// e.g. `cargo run -- scrape --replay fixtures/scraper --dry-run` works offline
+async fn run_scrape(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut dry_run = false;
+    let mut fetcher = Fetcher::Live;
+    let mut markets = vec![];
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--dry-run" => dry_run = true,
+            "--record" | "--replay" => {
+                let dir = PathBuf::from(args.next().ok_or(SCRAPE_USAGE)?);
+                fetcher = if arg == "--record" { Fetcher::Record(dir) } else { Fetcher::Replay(dir) };
+            },
+            "-h" | "--help" => {
+                println!("{SCRAPE_USAGE}");
+                return Ok(());
+            },
+            market => markets.push(Market::from_str(market).ok_or_else(|| format!("unknown market \"{market}\"\n{SCRAPE_USAGE}"))?),
+        }
+    }
+    if markets.is_empty() {
+        markets = Market::ALL.to_vec();
+    }
+
+    let mut records = vec![];
+    let mut errors = vec![];
+    for market in markets {
+        let source = get_source(market);
+        let urls = get_source_urls(market)?;
+        let result = scrape_market(source.as_ref(), urls.as_slice(), &fetcher).await;
+        println!("{}: {} pages, {} records", market.get_slug(), urls.len(), result.records.len());
+        result.errors.iter().for_each(|e| eprintln!("{}: {e}", market.get_slug()));
+        records.extend(result.records);
+        errors.extend(result.errors);
+    }
+
+    // what could be scraped is still imported, a single broken page must not hold back the others
+    let db_pool = db::init().await?;
+    let report = import_feed_records(&db_pool, records, dry_run).await?;
+    if !dry_run {
+        refresh_price_stats(&db_pool).await?;
+    }
+    report.get_changes().for_each(|diff| println!("{diff}"));
+    println!("{}{}", report.format_summary(), if dry_run { " (dry run, nothing saved)" } else { "" });
+
+    if !errors.is_empty() {
+        return Err(format!("{} pages failed", errors.len()).into());
+    }
+    Ok(())
+}
+
+// meant to run nightly, e.g. from cron, after the imports
+async fn run_check_links(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut dry_run = false;
+    for arg in args {
+        match arg.as_str() {
+            "--dry-run" => dry_run = true,
+            "-h" | "--help" => {
+                println!("{CHECK_LINKS_USAGE}");
+                return Ok(());
+            },
+            _ => return Err(CHECK_LINKS_USAGE.into()),
+        }
+    }
+
+    let db_pool = db::init().await?;
+    let report = check_listed_product_links(&db_pool, dry_run).await?;
+    report.removed.iter().for_each(|product_id| println!("- {product_id}"));
+    println!("{}{}", report.format_summary(), if dry_run { " (dry run, nothing saved)" } else { "" });
+
+    Ok(())
+}
+
+// meant to run daily, e.g. from cron, it creates a list at most once per template and day
+async fn run_create_recurring_lists(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut dry_run = false;
+    for arg in args {
+        match arg.as_str() {
+            "--dry-run" => dry_run = true,
+            "-h" | "--help" => {
+                println!("{CREATE_RECURRING_LISTS_USAGE}");
+                return Ok(());
+            },
+            _ => return Err(CREATE_RECURRING_LISTS_USAGE.into()),
+        }
+    }
+
+    let db_pool = db::init().await?;
+    let created = create_recurring_shopping_lists(&db_pool, Local::now().date_naive(), dry_run).await?;
+    created.iter().for_each(|list| println!("+ {} ({}) from template {}", list.name, list.id, list.template_id));
+    println!("{} lists created{}", created.len(), if dry_run { " (dry run, nothing saved)" } else { "" });
+
+    Ok(())
+}
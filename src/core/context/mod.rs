@@ -1,6 +1,6 @@
 use axum::http::Request;
 use crate::core::path::DetailOperations;
-use crate::core::query_params::StateParams;
+use crate::core::query_params::{QueryPairs, StateParams};
 
 // TODO context holds form-errors
 // TODO context holds pagination
@@ -45,41 +45,46 @@ impl<'a> Context<'a> {
     }
 
     pub fn preserve_query_state(&self, page: &usize, with_pathname: bool) -> String {
-        let query = self.uri.query().unwrap_or_default();
+        let query_pairs = self.get_query_pairs()
+            .remove(&["page"])
+            .append("page", page.to_string().as_str());
 
-        let preserved_query = if query.len() == 0 {
-            format!("page={}", page)
+        if with_pathname {
+            query_pairs.to_href(self.uri.path())
         } else {
-            let updated_query = query.split("&").map(|v| {
-                if v.starts_with("page=") {
-                    format!("page={page}")
-                } else {
-                    v.to_string()
-                }
-            }).reduce(|acc, e| {
-                if acc.len() == 0 {
-                    format!("{acc}{e}")
-                } else {
-                    format!("{acc}&{e}")
-                }
-            });
+            query_pairs.to_query_string()
+        }
+    }
 
-            match updated_query {
-                Some(query) => {
-                    if !query.contains("page") {
-                        format!("{}&page={}", query, page)
-                    } else {                               
-                        query
-                    }
-                },
-                None => query.to_string(),
-            }                   
-        };
+    // sets or removes a single query param and starts over at the first page, used for filter chips
+    pub fn with_query_param(&self, name: &str, value: Option<&str>) -> String {
+        self.with_query_values(name, value.as_slice())
+    }
 
-        if with_pathname {
-            format!("{}?{}", self.uri.path(), preserved_query)
-        } else {
-            preserved_query
-        }     
+    // replaces every value of a repeated query param, e.g. the selected markets
+    pub fn with_query_values<T: AsRef<str>>(&self, name: &str, values: &[T]) -> String {
+        values.iter()
+            .fold(self.get_query_pairs().remove(&[name, "page", "after", "before"]), |query_pairs, value| {
+                query_pairs.append(name, value.as_ref())
+            })
+            .to_href(self.uri.path())
     }
-}
\ No newline at end of file
+
+    // replaces any page or cursor with the given cursor param ("after" or "before"), None links to the first page
+    pub fn with_cursor(&self, name: &str, cursor: Option<&str>) -> String {
+        let query_pairs = self.get_query_pairs().remove(&["page", "after", "before"]);
+        match cursor {
+            Some(cursor) => query_pairs.append(name, cursor),
+            None => query_pairs,
+        }.to_href(self.uri.path())
+    }
+
+    // the current uri with only the given query params left, e.g. to reset all filters but the search
+    pub fn retain_query_params(&self, names: &[&str]) -> String {
+        self.get_query_pairs().retain(names).to_href(self.uri.path())
+    }
+
+    fn get_query_pairs(&self) -> QueryPairs {
+        QueryPairs::parse(self.uri.query())
+    }
+}
@@ -4,50 +4,215 @@ use axum::http::{Request, Uri};
 
 use crate::{core::{query_params::StateParams, context::Context}, view::misc::PaginationTemplate};
 
-pub enum _PaginationType {
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PaginationType {
     ByOffset,
     ByCursor
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CursorDirection {
+    After,
+    Before,
+}
+
+// position of a row by its sort key and id, the id keeps rows with the same sort key apart
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cursor {
+    pub sort_key: String,
+    pub id: String,
+}
+
+// the default and the maximum number of rows per page of an endpoint
+#[derive(Debug, Clone, Copy)]
+pub struct PageLimits {
+    pub default: usize,
+    pub max: usize,
+}
+
+impl PageLimits {
+    pub const fn new(default: usize, max: usize) -> Self {
+        Self { default, max }
+    }
+}
+
+pub const DEFAULT_PAGE_LIMITS: PageLimits = PageLimits::new(10, 50);
+
+// how many pages around the current one are linked
+const PAGE_WINDOW_RADIUS: usize = 2;
+
+#[derive(Debug, PartialEq)]
+pub enum PaginationError {
+    InvalidLimit,
+    PageOutOfRange,
+}
+
+impl PaginationError {
+    pub fn get_message(&self) -> &'static str {
+        match self {
+            PaginationError::InvalidLimit => "Ungültige Anzahl an Einträgen pro Seite",
+            PaginationError::PageOutOfRange => "Ungültige Seite",
+        }
+    }
+}
+
+const CURSOR_SEPARATOR: char = '\u{1f}';
+
+impl Cursor {
+    pub fn new(sort_key: String, id: String) -> Self {
+        Self { sort_key, id }
+    }
+
+    // hex keeps the cursor opaque and url safe without another dependency
+    pub fn encode(&self) -> String {
+        format!("{}{}{}", self.sort_key, CURSOR_SEPARATOR, self.id)
+            .bytes()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    pub fn decode(encoded: &str) -> Option<Self> {
+        // from_str_radix would accept a sign
+        if !encoded.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return None;
+        }
+        let bytes = encoded.as_bytes()
+            .chunks(2)
+            .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok().filter(|_| pair.len() == 2))
+            .collect::<Option<Vec<u8>>>()?;
+        let decoded = String::from_utf8(bytes).ok()?;
+        let (sort_key, id) = decoded.split_once(CURSOR_SEPARATOR)?;
+
+        Some(Self::new(sort_key.to_string(), id.to_string()))
+    }
+}
+
 #[derive(Debug)]
 pub struct Pagination {
     pub q: Option<String>,
     pub page: usize,
     pub limit: usize,
+    // the requested limit exceeded the maximum of the endpoint
+    pub is_limit_clamped: bool,
     pub sort_by: Option<String>,
     pub sort_order: Option<String>,
     pub last_page: Option<usize>,
     pub uri: Option<Uri>,
     pub has_previous_page: bool,
     pub has_next_page: bool,
+    pub pagination_type: PaginationType,
+    // the cursor of the requested page, if any
+    pub cursor: Option<(CursorDirection, Cursor)>,
+    pub previous_cursor: Option<String>,
+    pub next_cursor: Option<String>,
 }
 
 impl Pagination {
-    pub fn _from_request<T>(request: &Request<T>) -> Self {
+    pub fn _from_request<T>(request: &Request<T>) -> Result<Self, PaginationError> {
         Pagination::_from_uri(request.uri())
     }
 
-    pub fn _from_uri(uri: &Uri) -> Self {
+    pub fn _from_uri(uri: &Uri) -> Result<Self, PaginationError> {
         Pagination::_from_query(uri.query())
     }
 
-    pub fn _from_query(query: Option<&str>) -> Self {
-        Pagination::from_query_params(&StateParams::from_query(query))
+    pub fn _from_query(query: Option<&str>) -> Result<Self, PaginationError> {
+        Pagination::from_query_params(&StateParams::from_query(query), &DEFAULT_PAGE_LIMITS)
     }
 
-    pub fn from_query_params(query_params: &StateParams) -> Self {
+    pub fn from_query_params(query_params: &StateParams, limits: &PageLimits) -> Result<Self, PaginationError> {
         let page = query_params.get_page().unwrap_or(0);
-        Self { 
+        let requested_limit = query_params.get_limit().unwrap_or(limits.default);
+        if requested_limit == 0 {
+            return Err(PaginationError::InvalidLimit);
+        }
+
+        let limit = requested_limit.min(limits.max);
+        // the offset is bound as BIGINT
+        let is_offset_in_range = page.checked_mul(limit).is_some_and(|offset| i64::try_from(offset).is_ok());
+        if !is_offset_in_range {
+            return Err(PaginationError::PageOutOfRange);
+        }
+
+        let cursor = match (query_params.get_after(), query_params.get_before()) {
+            (Some(after), _) => Cursor::decode(after.as_str()).map(|cursor| (CursorDirection::After, cursor)),
+            (None, Some(before)) => Cursor::decode(before.as_str()).map(|cursor| (CursorDirection::Before, cursor)),
+            (None, None) => None,
+        };
+        Ok(Self { 
             q: query_params.get_q(),
             page: page,
-            limit: query_params.get_limit().unwrap_or(10),
+            limit,
+            is_limit_clamped: requested_limit > limits.max,
             sort_by: query_params.get_sort_by(),
             sort_order: query_params.get_sort_order(),
             last_page: None,
             uri: None,
             has_previous_page: page != 0,
             has_next_page: false,
+            pagination_type: PaginationType::ByOffset,
+            cursor,
+            previous_cursor: None,
+            next_cursor: None,
+        })
+    }
+
+    pub fn by_cursor(mut self) -> Self {
+        self.pagination_type = PaginationType::ByCursor;
+        self.has_previous_page = self.cursor.is_some();
+        self
+    }
+
+    pub fn is_by_cursor(&self) -> bool {
+        self.pagination_type == PaginationType::ByCursor
+    }
+
+    // the cursor to query with, offset pagination ignores stale cursor params
+    pub fn get_cursor(&self) -> Option<&(CursorDirection, Cursor)> {
+        if self.is_by_cursor() {
+            self.cursor.as_ref()
+        } else {
+            None
+        }
+    }
+
+    pub fn get_offset(&self) -> usize {
+        if self.is_by_cursor() {
+            0
+        } else {
+            self.page * self.limit
+        }
+    }
+
+    // expects up to limit + 1 rows, in reverse order when paging backwards by cursor
+    pub fn with_rows<T>(mut self, mut rows: Vec<T>, cursor_of: impl Fn(&T) -> Cursor) -> (Self, Vec<T>) {
+        let has_more = rows.len() > self.limit;
+        rows.truncate(self.limit);
+
+        if !self.is_by_cursor() {
+            self.has_next_page = has_more;
+            return (self, rows);
         }
+
+        match self.get_cursor().map(|(direction, _)| *direction) {
+            Some(CursorDirection::Before) => {
+                rows.reverse();
+                self.has_previous_page = has_more;
+                self.has_next_page = true;
+            },
+            Some(CursorDirection::After) => {
+                self.has_previous_page = true;
+                self.has_next_page = has_more;
+            },
+            None => {
+                self.has_previous_page = false;
+                self.has_next_page = has_more;
+            },
+        }
+
+        self.previous_cursor = rows.first().filter(|_| self.has_previous_page).map(|row| cursor_of(row).encode());
+        self.next_cursor = rows.last().filter(|_| self.has_next_page).map(|row| cursor_of(row).encode());
+        (self, rows)
     }
 
     pub fn with_total(mut self, total: u64) -> Self {
@@ -60,18 +225,141 @@ impl Pagination {
         self
     }
 
-    pub fn with_count(mut self, count: usize) -> Self {
-        self.has_next_page = count > self.limit;
-        self
-    }
-
     pub fn with_uri(mut self, uri: Uri) -> Self {
         self.uri = Some(uri);
         self
     }
 
+    // first, last and the pages around the current one, None stands for skipped pages
+    pub fn get_page_window(&self) -> Vec<Option<usize>> {
+        let last_page = match self.last_page {
+            Some(last_page) => last_page.max(self.page),
+            None if self.has_next_page => self.page + 1,
+            None => self.page,
+        };
+        let start = self.page.saturating_sub(PAGE_WINDOW_RADIUS);
+        let end = (self.page + PAGE_WINDOW_RADIUS).min(last_page);
+
+        let mut pages = vec![];
+        if start > 0 {
+            pages.push(Some(0));
+            if start > 1 {
+                pages.push(None);
+            }
+        }
+        pages.extend((start..=end).map(Some));
+        if end < last_page {
+            if end + 1 < last_page {
+                pages.push(None);
+            }
+            pages.push(Some(last_page));
+        }
+
+        pages
+    }
+
+    pub fn is_current_page(&self, page: &usize) -> bool {
+        *page == self.page
+    }
+
     pub fn render_with_context(&self, context: &Context) -> Result<String, askama::Error> {
         // TODO this should be rendered from context/partials?
         PaginationTemplate { pagination: self, context }.render()
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_round_trips_through_its_encoding() {
+        for cursor in [
+            Cursor::new("2026-10-19 06:00:00.123456+00".to_string(), "lidl-1".to_string()),
+            Cursor::new("-1".to_string(), "aldisued-000000000000412345".to_string()),
+            Cursor::new("Käse & Brot, 3,5 %".to_string(), "id with spaces/and?query=1".to_string()),
+            Cursor::new("".to_string(), "".to_string()),
+        ] {
+            let encoded = cursor.encode();
+            assert!(encoded.chars().all(|char| char.is_ascii_hexdigit()), "{}", encoded);
+            assert_eq!(Cursor::decode(encoded.as_str()), Some(cursor));
+        }
+    }
+
+    #[test]
+    fn cursor_rejects_garbage() {
+        let valid = Cursor::new("109".to_string(), "lidl-1".to_string()).encode();
+
+        // odd length, not hex, not utf-8, no separator
+        assert_eq!(Cursor::decode(&valid[1..]), None);
+        assert_eq!(Cursor::decode("zz"), None);
+        assert_eq!(Cursor::decode("+11f31"), None);
+        assert_eq!(Cursor::decode("ff1f00"), None);
+        assert_eq!(Cursor::decode("313039"), None);
+        assert_eq!(Cursor::decode(""), None);
+        assert_eq!(Cursor::decode("ä1"), None);
+    }
+
+    fn pagination(query: &str) -> Result<Pagination, PaginationError> {
+        Pagination::from_query_params(&StateParams::from_query(Some(query)), &PageLimits::new(10, 50))
+    }
+
+    #[test]
+    fn limit_defaults_and_is_clamped_to_the_maximum() {
+        let default = pagination("").unwrap();
+        assert_eq!((default.page, default.limit, default.is_limit_clamped), (0, 10, false));
+
+        let maximum = pagination("limit=50").unwrap();
+        assert_eq!((maximum.limit, maximum.is_limit_clamped), (50, false));
+
+        let clamped = pagination("limit=51&page=2").unwrap();
+        assert_eq!((clamped.page, clamped.limit, clamped.is_limit_clamped), (2, 50, true));
+        assert_eq!(clamped.get_offset(), 100);
+
+        assert_eq!(pagination("limit=0").unwrap_err(), PaginationError::InvalidLimit);
+        assert_eq!(pagination(&format!("page={}", usize::MAX)).unwrap_err(), PaginationError::PageOutOfRange);
+    }
+
+    #[test]
+    fn page_window_links_first_last_and_the_pages_around_the_current_one() {
+        let window = |query: &str, total: u64| pagination(query).unwrap().with_total(total).get_page_window();
+
+        // 20 pages of 10
+        assert_eq!(window("", 200), vec![Some(0), Some(1), Some(2), None, Some(19)]);
+        assert_eq!(window("page=19", 200), vec![Some(0), None, Some(17), Some(18), Some(19)]);
+        assert_eq!(window("page=10", 200), vec![Some(0), None, Some(8), Some(9), Some(10), Some(11), Some(12), None, Some(19)]);
+        // the first page is adjacent to the window
+        assert_eq!(window("page=3", 200), vec![Some(0), Some(1), Some(2), Some(3), Some(4), Some(5), None, Some(19)]);
+        assert_eq!(window("page=4", 60), vec![Some(0), None, Some(2), Some(3), Some(4), Some(5)]);
+    }
+
+    #[test]
+    fn page_window_of_empty_and_single_row_results() {
+        let empty = pagination("").unwrap().with_total(0);
+        assert_eq!(empty.last_page, Some(0));
+        assert_eq!(empty.get_page_window(), vec![Some(0)]);
+
+        let single = pagination("").unwrap().with_total(1);
+        assert_eq!(single.last_page, Some(0));
+        assert_eq!(single.get_page_window(), vec![Some(0)]);
+
+        let exactly_one_page = pagination("").unwrap().with_total(10);
+        assert_eq!(exactly_one_page.get_page_window(), vec![Some(0)]);
+        assert_eq!(pagination("").unwrap().with_total(11).get_page_window(), vec![Some(0), Some(1)]);
+
+        // a page past the end still shows itself
+        assert_eq!(pagination("page=2").unwrap().with_total(1).get_page_window(), vec![Some(0), Some(1), Some(2)]);
+    }
+
+    #[test]
+    fn page_window_without_a_total_reaches_one_page_ahead() {
+        let cursor_of = |id: &i32| Cursor::new(id.to_string(), id.to_string());
+
+        let (more, rows) = pagination("page=1").unwrap().with_rows((0..11).collect(), cursor_of);
+        assert_eq!(rows.len(), 10);
+        assert_eq!(more.get_page_window(), vec![Some(0), Some(1), Some(2)]);
+
+        let (last, rows) = pagination("page=1").unwrap().with_rows((0..3).collect(), cursor_of);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(last.get_page_window(), vec![Some(0), Some(1)]);
+    }
+}
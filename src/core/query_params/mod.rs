@@ -1,5 +1,12 @@
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{request::Parts, StatusCode},
+    response::Html,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use crate::model::product::{search::SearchLanguage, Price};
 use crate::routes::{render_success_notification, render_error_notification};
 
 #[derive(Deserialize, Debug)]
@@ -16,6 +23,13 @@ impl SortOrder {
         }
     }
 
+    pub fn reverse(&self) -> Self {
+        match self {
+            SortOrder::Asc => SortOrder::Desc,
+            SortOrder::Desc => SortOrder::Asc,
+        }
+    }
+
     pub fn from_str(str: &str) -> Self {
         match str {
             "ASC" => SortOrder::Asc,
@@ -51,45 +65,89 @@ pub struct StateParams {
     sort_by: Option<String>,
     sort_order: Option<String>,
     is: Option<String>,
+    market: Vec<String>,
+    category: Option<String>,
+    min_price: Option<String>,
+    max_price: Option<String>,
+    min_unit_price: Option<String>,
+    max_unit_price: Option<String>,
+    on_sale: Option<String>,
+    in_list: Option<String>,
+    lang: Option<String>,
+    after: Option<String>,
+    before: Option<String>,
 }
 
 impl StateParams {
+    // unparseable values are dropped, see try_from_query to reject them
     pub fn from_query(query: Option<&str>) -> Self {
+        Self::parse(query).0
+    }
+
+    pub fn try_from_query(query: Option<&str>) -> Result<Self, String> {
+        let (state_params, invalid_names) = Self::parse(query);
+        match invalid_names.first() {
+            Some(name) => Err(format!("Ungültiger Wert für \"{}\"", name)),
+            None => Ok(state_params),
+        }
+    }
+
+    // the decoded state and the names of params with invalid values
+    fn parse(query: Option<&str>) -> (Self, Vec<String>) {
         let mut state_params = Self {
             q: None,
-            page: Some(0),
-            limit: Some(10),
+            page: None,
+            limit: None,
             sort_by: None,
             sort_order: None,
             is: None,
+            market: vec![],
+            category: None,
+            min_price: None,
+            max_price: None,
+            min_unit_price: None,
+            max_unit_price: None,
+            on_sale: None,
+            in_list: None,
+            lang: None,
+            after: None,
+            before: None,
         };
+        let mut invalid_names = vec![];
 
-        if query.is_none() {
-            return state_params;
-        }
-
-        let parts = query.unwrap().split("&");
-        for part in parts {
-            let mut splitted_param = part.splitn(2, |b| b == '=');
-            let name = splitted_param.next().unwrap();
-            let value = splitted_param.next();
-
-            if value.is_none() {
-                continue;
-            }
-
+        for (name, value) in QueryPairs::parse(query).iter() {
+            let value = value.to_string();
             match name {
-                "q" => state_params.q = value.unwrap().parse().ok(),
-                "page" => state_params.page = value.unwrap().parse().ok(),
-                "limit" => state_params.limit = value.unwrap().parse().ok(),
-                "sort_by" => state_params.sort_by = value.unwrap().parse().ok(),
-                "sort_order" => state_params.sort_order = value.unwrap().parse().ok(),
-                "is" => state_params.is = value.unwrap().parse().ok(),
-                _ => println!("no StateParam for {:?} implemented", name),
+                "q" => state_params.q = Some(value),
+                "page" => match value.parse() {
+                    Ok(page) => state_params.page = Some(page),
+                    Err(_) => invalid_names.push(name.to_string()),
+                },
+                "limit" => match value.parse() {
+                    Ok(limit) => state_params.limit = Some(limit),
+                    Err(_) => invalid_names.push(name.to_string()),
+                },
+                "sort_by" => state_params.sort_by = Some(value),
+                "sort_order" => state_params.sort_order = Some(value),
+                "is" => state_params.is = Some(value),
+                // multi-select, "market=1&market=3"
+                "market" => state_params.market.push(value),
+                "category" => state_params.category = Some(value),
+                "min_price" => state_params.min_price = Some(value),
+                "max_price" => state_params.max_price = Some(value),
+                "min_unit_price" => state_params.min_unit_price = Some(value),
+                "max_unit_price" => state_params.max_unit_price = Some(value),
+                "on_sale" => state_params.on_sale = Some(value),
+                "in_list" => state_params.in_list = Some(value),
+                "lang" => state_params.lang = Some(value),
+                "after" => state_params.after = Some(value),
+                "before" => state_params.before = Some(value),
+                // e.g. radius and shopping_list_id, read by the StoreSearchParams and TourParams extractors
+                _ => {}
             }
         }
 
-        state_params
+        (state_params, invalid_names)
     }
 
     pub fn success_state_notify(&self) -> String {
@@ -143,9 +201,202 @@ impl StateParams {
     pub fn get_page(self: &Self) -> Option<usize> {
         self.page
     }
+
+    // repeated, e.g. "market=1&market=3", comma separated values are still understood
+    pub fn get_market_ids(&self) -> Vec<i64> {
+        self.market.iter()
+            .flat_map(|market| market.split(','))
+            .filter_map(|id| id.trim().parse().ok())
+            .collect()
+    }
+
+    pub fn get_category(&self) -> Option<String> {
+        self.category.to_owned().filter(|category| !category.is_empty())
+    }
+
+    pub fn get_min_price(&self) -> Option<i32> {
+        parse_price_in_cents(self.min_price.as_deref())
+    }
+
+    pub fn get_max_price(&self) -> Option<i32> {
+        parse_price_in_cents(self.max_price.as_deref())
+    }
+
+    pub fn get_min_unit_price(&self) -> Option<i32> {
+        parse_price_in_cents(self.min_unit_price.as_deref())
+    }
+
+    pub fn get_max_unit_price(&self) -> Option<i32> {
+        parse_price_in_cents(self.max_unit_price.as_deref())
+    }
+
+    pub fn is_on_sale(&self) -> bool {
+        parse_flag(self.on_sale.as_deref())
+    }
+
+    pub fn is_in_list(&self) -> bool {
+        parse_flag(self.in_list.as_deref())
+    }
+
+    // opaque cursors of core::pagination::Cursor
+    pub fn get_after(&self) -> Option<String> {
+        self.after.to_owned().filter(|after| !after.is_empty())
+    }
+
+    pub fn get_before(&self) -> Option<String> {
+        self.before.to_owned().filter(|before| !before.is_empty())
+    }
+
+    pub fn get_search_language(&self) -> SearchLanguage {
+        self.lang.as_deref().map(SearchLanguage::from_str).unwrap_or_default()
+    }
+}
+
+fn parse_price_in_cents(value: Option<&str>) -> Option<i32> {
+    Price::parse_cents(value?)
+}
+
+fn parse_flag(value: Option<&str>) -> bool {
+    matches!(value, Some("true") | Some("1") | Some("on"))
+}
+
+// rejects invalid values with a 400 instead of silently falling back, unlike Context::query_params
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for StateParams {
+    type Rejection = (StatusCode, Html<String>);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        StateParams::try_from_query(parts.uri.query()).map_err(|message| (StatusCode::BAD_REQUEST, Html(message)))
+    }
+}
+
+// the form-urlencoded pairs of a query string in their original order, names may repeat
+#[derive(Debug, Clone, Default)]
+pub struct QueryPairs {
+    pairs: Vec<(String, String)>,
+}
+
+impl QueryPairs {
+    pub fn parse(query: Option<&str>) -> Self {
+        Self {
+            pairs: form_urlencoded::parse(query.unwrap_or_default().as_bytes()).into_owned().collect(),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.pairs.iter().map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    pub fn remove(mut self, names: &[&str]) -> Self {
+        self.pairs.retain(|(name, _)| !names.contains(&name.as_str()));
+        self
+    }
+
+    pub fn retain(mut self, names: &[&str]) -> Self {
+        self.pairs.retain(|(name, _)| names.contains(&name.as_str()));
+        self
+    }
+
+    pub fn append(mut self, name: &str, value: &str) -> Self {
+        self.pairs.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn to_query_string(&self) -> String {
+        form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(self.pairs.iter())
+            .finish()
+    }
+
+    // the path with the encoded query, without a dangling "?"
+    pub fn to_href(&self, path: &str) -> String {
+        if self.pairs.is_empty() {
+            path.to_string()
+        } else {
+            format!("{}?{}", path, self.to_query_string())
+        }
+    }
 }
 
 #[derive(Deserialize)]
 pub struct ActivationParams {
     pub token: Option<String>,
-}
\ No newline at end of file
+}
+
+#[derive(Deserialize)]
+pub struct StoreSearchParams {
+    pub radius: Option<u32>,
+}
+
+#[derive(Deserialize)]
+pub struct TourParams {
+    pub shopping_list_id: Option<i64>,
+    pub radius: Option<u32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::Request;
+    use super::*;
+
+    #[test]
+    fn repeated_markets_are_all_kept() {
+        let state_params = StateParams::from_query(Some("market=1&market=3&market=2,4&market=aldi"));
+        assert_eq!(state_params.get_market_ids(), vec![1, 3, 2, 4]);
+        assert!(StateParams::from_query(None).get_market_ids().is_empty());
+    }
+
+    #[test]
+    fn values_are_percent_and_plus_decoded() {
+        let state_params = StateParams::from_query(Some("q=K%C3%A4se+%26+Brot&category=Obst%20%2B%20Gem%C3%BCse&unknown=1"));
+        assert_eq!(state_params.get_q(), Some("Käse & Brot".to_string()));
+        assert_eq!(state_params.get_category(), Some("Obst + Gemüse".to_string()));
+    }
+
+    #[test]
+    fn invalid_page_or_limit_are_dropped_or_rejected() {
+        let state_params = StateParams::from_query(Some("page=-1&limit=zehn&q=milch"));
+        assert_eq!((state_params.get_page(), state_params.get_limit()), (None, None));
+        assert_eq!(state_params.get_q(), Some("milch".to_string()));
+
+        assert!(StateParams::try_from_query(Some("page=2&limit=20")).is_ok());
+        assert_eq!(StateParams::try_from_query(Some("page=-1")).unwrap_err(), "Ungültiger Wert für \"page\"");
+        assert_eq!(StateParams::try_from_query(Some("limit=1.5")).unwrap_err(), "Ungültiger Wert für \"limit\"");
+    }
+
+    #[tokio::test]
+    async fn the_extractor_rejects_invalid_page_and_limit_with_a_bad_request() {
+        for (uri, status) in [
+            ("/produkte?page=1&limit=20", None),
+            ("/produkte?page=eins", Some(StatusCode::BAD_REQUEST)),
+            ("/produkte?limit=-5", Some(StatusCode::BAD_REQUEST)),
+        ] {
+            let (mut parts, _) = Request::builder().uri(uri).body(()).unwrap().into_parts();
+            let result = StateParams::from_request_parts(&mut parts, &()).await;
+            assert_eq!(result.err().map(|(status, _)| status), status, "{}", uri);
+        }
+    }
+
+    #[test]
+    fn query_pairs_round_trip_through_the_query_string() {
+        let query = "q=K%C3%A4se+%26+Brot&market=1&market=3&after=ab1f";
+        let pairs = QueryPairs::parse(Some(query));
+        assert_eq!(pairs.to_query_string(), query);
+        assert_eq!(
+            pairs.iter().collect::<Vec<_>>(),
+            vec![("q", "Käse & Brot"), ("market", "1"), ("market", "3"), ("after", "ab1f")],
+        );
+
+        let reparsed = QueryPairs::parse(Some(pairs.to_query_string().as_str()));
+        assert_eq!(reparsed.iter().collect::<Vec<_>>(), pairs.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn query_pairs_build_hrefs() {
+        let pairs = QueryPairs::parse(Some("q=milch&page=3&market=1&market=2"));
+        assert_eq!(pairs.clone().remove(&["page"]).to_href("/produkte"), "/produkte?q=milch&market=1&market=2");
+        assert_eq!(pairs.clone().retain(&["market"]).append("page", "0").to_href("/produkte"), "/produkte?market=1&market=2&page=0");
+        assert_eq!(pairs.retain(&["sort_by"]).to_href("/produkte"), "/produkte");
+        assert_eq!(QueryPairs::parse(None).append("q", "a&b=c").to_href("/"), "/?q=a%26b%3Dc");
+    }
+}
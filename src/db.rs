@@ -4,8 +4,24 @@ use dotenv;
 
 const DB_USER_KEY: &str = "DB_USER";
 const DB_PASSWORD_KEY: &str = "DB_PASSWORD";
+const DATABASE_URL_KEY: &str = "DATABASE_URL";
 
 pub async fn init() -> Result<Pool<Postgres>, Error> {
+    // a full connection string, e.g. to a local postgis from the docker-compose.yml, takes precedence over supabase
+    let connection_string = match dotenv::var(DATABASE_URL_KEY).or_else(|_| std::env::var(DATABASE_URL_KEY)) {
+        Ok(url) => url,
+        Err(_) => supabase_connection_string(),
+    };
+
+    PgPoolOptions::new()
+        .min_connections(5)
+        .max_connections(50)
+        .acquire_timeout(Duration::from_secs(3))
+        .connect(connection_string.as_str())
+        .await
+}
+
+fn supabase_connection_string() -> String {
     let db_user = dotenv::var(DB_USER_KEY)
         .unwrap_or_else(|_| std::env::var(DB_USER_KEY).expect(format!("{DB_USER_KEY} must be set in env").as_str())
     );
@@ -15,12 +31,5 @@ pub async fn init() -> Result<Pool<Postgres>, Error> {
     );
 
     // user 'postgres.{}' on db 'postgres'
-    let connection_string = format!("postgres://postgres.{}:{}@aws-0-eu-central-1.pooler.supabase.com:5432/postgres", db_user, db_password);
-
-    PgPoolOptions::new()
-        .min_connections(5)
-        .max_connections(50)
-        .acquire_timeout(Duration::from_secs(3))
-        .connect(connection_string.as_str())
-        .await
-}
\ No newline at end of file
+    format!("postgres://postgres.{}:{}@aws-0-eu-central-1.pooler.supabase.com:5432/postgres", db_user, db_password)
+}
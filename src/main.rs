@@ -6,8 +6,10 @@ use axum::{middleware, Router};
 use tower_http::services::ServeDir;
 use serde_json::Value;
 use sqlx::{Pool, Postgres};
+use tokio::sync::broadcast;
 use dotenv;
 
+mod cli;
 mod db;
 mod services;
 mod model;
@@ -17,21 +19,34 @@ mod core;
 use routes::{
     default_middleware,
     handle_not_found,
+    api,
     auth,
     controller,
 };
+use model::shopping_list::event::ShoppingListEvent;
+
+// events a slow event stream may fall behind before it has to catch up
+const SHOPPING_LIST_EVENT_CAPACITY: usize = 256;
 
 #[derive(Clone)]
 pub struct AppState {
     db_pool: Pool<Postgres>,
     navigation: Value,
+    // the changes to all lists, every open event stream subscribes to it
+    shopping_list_events: broadcast::Sender<ShoppingListEvent>,
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
-    println!("starting..");
     dotenv::dotenv().ok();
 
+    let args = std::env::args().collect::<Vec<String>>();
+    if cli::is_command(&args) {
+        return cli::run(&args).await;
+    }
+
+    println!("starting..");
+
     let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
         .expect("env \'CARGO_MANIFEST_DIR\' must be set to the projects root directory, if not run by the cargo command.");
     println!("manifest_dir: {:?}", manifest_dir);
@@ -50,13 +65,25 @@ async fn main() -> Result<(), Box<dyn Error>> {
     let db_pool = db::init().await?;
     println!("started db!");
 
-    let app_state = AppState { db_pool, navigation };
+    let (shopping_list_events, _) = broadcast::channel(SHOPPING_LIST_EVENT_CAPACITY);
+    tokio::spawn(services::shopping_list::events::listen_to_shopping_list_events(
+        db_pool.clone(),
+        shopping_list_events.clone(),
+    ));
+
+    let app_state = AppState { db_pool, navigation, shopping_list_events };
     let app = Router::new()
+        .merge(controller::category::routes())
+        .merge(controller::image::routes())
         .merge(controller::product::routes())
+        .merge(controller::search::routes())
         .merge(controller::shopping_list::routes())
         .merge(controller::social_timeline::routes())
         .merge(controller::static_page::routes())
+        .merge(controller::store::routes())
+        .merge(controller::tour::routes())
         .merge(controller::user::routes())
+        .merge(api::routes())
         .merge(auth::routes())
         .fallback(handle_not_found)
         .layer(middleware::from_fn(auth::validate))
@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct Category {
+    pub id: i64,
+    pub parent_id: Option<i64>,
+    pub name: String,
+    pub slug: String,
+    #[sqlx(default)]
+    pub depth: i32,
+    #[sqlx(default)]
+    pub product_count: i64,
+}
+
+impl Category {
+    pub fn get_href(&self) -> String {
+        format!("/stoebern/{}", self.slug)
+    }
+}
+
+// all categories in depth-first order, so it can be rendered as an indented list
+#[derive(Debug)]
+pub struct CategoryTree {
+    pub categories: Vec<Category>,
+}
+
+impl CategoryTree {
+    pub fn find_by_slug(&self, slug: &str) -> Option<&Category> {
+        self.categories.iter().find(|category| category.slug == slug)
+    }
+
+    pub fn get_children(&self, category_id: &i64) -> Vec<&Category> {
+        self.categories.iter().filter(|category| category.parent_id == Some(*category_id)).collect()
+    }
+
+    // root first, the category itself last
+    pub fn get_path(&self, category_id: &i64) -> Vec<&Category> {
+        let mut path = vec![];
+        let mut current = self.categories.iter().find(|category| category.id == *category_id);
+        while let Some(category) = current {
+            path.push(category);
+            current = category.parent_id.and_then(|parent_id| {
+                self.categories.iter().find(|category| category.id == parent_id)
+            });
+        }
+        path.reverse();
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn category(id: i64, parent_id: Option<i64>, slug: &str) -> Category {
+        Category { id, parent_id, name: slug.to_string(), slug: slug.to_string(), depth: 0, product_count: 0 }
+    }
+
+    // lebensmittel > molkerei > milch, lebensmittel > obst, drogerie
+    fn tree() -> CategoryTree {
+        CategoryTree {
+            categories: vec![
+                category(1, None, "lebensmittel"),
+                category(2, Some(1), "molkerei"),
+                category(3, Some(2), "milch"),
+                category(4, Some(1), "obst"),
+                category(5, None, "drogerie"),
+            ],
+        }
+    }
+
+    fn slugs(categories: Vec<&Category>) -> Vec<&str> {
+        categories.into_iter().map(|category| category.slug.as_str()).collect()
+    }
+
+    #[test]
+    fn paths_run_from_the_root_to_the_category() {
+        let tree = tree();
+        assert_eq!(slugs(tree.get_path(&3)), vec!["lebensmittel", "molkerei", "milch"]);
+        assert_eq!(slugs(tree.get_path(&4)), vec!["lebensmittel", "obst"]);
+        assert_eq!(slugs(tree.get_path(&5)), vec!["drogerie"]);
+        assert!(tree.get_path(&42).is_empty());
+    }
+
+    #[test]
+    fn children_are_the_direct_ones_only() {
+        let tree = tree();
+        assert_eq!(slugs(tree.get_children(&1)), vec!["molkerei", "obst"]);
+        assert_eq!(slugs(tree.get_children(&2)), vec!["milch"]);
+        assert!(tree.get_children(&3).is_empty());
+    }
+
+    #[test]
+    fn categories_are_found_by_slug() {
+        let tree = tree();
+        assert_eq!(tree.find_by_slug("milch").map(|category| category.id), Some(3));
+        assert!(tree.find_by_slug("Milch").is_none());
+        assert_eq!(tree.categories[2].get_href(), "/stoebern/milch");
+    }
+}
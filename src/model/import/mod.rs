@@ -0,0 +1,400 @@
+use std::collections::HashMap;
+use std::fmt;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use crate::model::{market::Market, product::{promotion::PromotionCondition, Price}};
+
+const DEFAULT_CURRENCY: &str = "EUR";
+// images are a single csv column
+const IMAGE_SEPARATOR: char = '|';
+
+// the fields of a record by column name, with the line it starts on
+pub(crate) type FeedRow = (usize, HashMap<String, String>);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FeedFormat {
+    Csv,
+    Json,
+}
+
+impl FeedFormat {
+    pub fn from_path(path: &str) -> Option<Self> {
+        match path.rsplit('.').next()?.to_lowercase().as_str() {
+            "csv" => Some(FeedFormat::Csv),
+            "json" => Some(FeedFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct FeedError {
+    // 1-based, the header of a csv is line 1
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for FeedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+// one product with its price at a point in time, as delivered by a market's price feed
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedRecord {
+    pub product_id: String,
+    pub name: String,
+    pub market_id: i64,
+    pub images: Vec<String>,
+    pub url: String,
+    // in cents
+    pub price: Option<i32>,
+    pub currency: String,
+    pub timestamp: DateTime<Utc>,
+    pub promotion: Option<FeedPromotion>,
+}
+
+// a temporary offer next to the regular price, from the promo_* fields
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedPromotion {
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    // in cents
+    pub price: i32,
+    pub condition: Option<PromotionCondition>,
+}
+
+impl FeedRecord {
+    // `imported_at` is used for records without a timestamp
+    fn from_fields(fields: &HashMap<String, String>, line: usize, imported_at: DateTime<Utc>) -> Result<Self, FeedError> {
+        let error = |message: String| FeedError { line, message };
+        let get = |name: &str| fields.get(name).map(|value| value.trim()).filter(|value| !value.is_empty());
+        let require = |name: &str| get(name).ok_or_else(|| error(format!("missing \"{}\"", name)));
+
+        let product_id = get("product_id").or(get("id")).ok_or_else(|| error("missing \"product_id\"".to_string()))?;
+        let market = require("market")?;
+        let market_id = Market::from_str(market).ok_or_else(|| error(format!("unknown market \"{}\"", market)))?.get_id();
+        let price = match get("price") {
+            Some(price) => Some(Price::parse_cents(price).ok_or_else(|| error(format!("invalid price \"{}\"", price)))?),
+            None => None,
+        };
+        let parse_date_time = |name: &str| match get(name) {
+            Some(date_time) => DateTime::parse_from_rfc3339(date_time)
+                .map(|date_time| Some(date_time.with_timezone(&Utc)))
+                .map_err(|_| error(format!("invalid {} \"{}\", expected RFC 3339", name, date_time))),
+            None => Ok(None),
+        };
+        let timestamp = parse_date_time("timestamp")?.unwrap_or(imported_at);
+        let promotion = match get("promo_price") {
+            Some(promo_price) => Some(FeedPromotion {
+                // a promotion without a start runs from the time of the record
+                starts_at: parse_date_time("promo_start")?.unwrap_or(timestamp),
+                ends_at: parse_date_time("promo_end")?.ok_or_else(|| error("missing \"promo_end\"".to_string()))?,
+                price: Price::parse_cents(promo_price).ok_or_else(|| error(format!("invalid promo_price \"{}\"", promo_price)))?,
+                condition: match get("promo_condition") {
+                    Some(condition) => Some(PromotionCondition::from_str(condition)
+                        .ok_or_else(|| error(format!("unknown promo_condition \"{}\"", condition)))?),
+                    None => None,
+                },
+            }),
+            None => None,
+        };
+        if promotion.as_ref().is_some_and(|promotion| promotion.ends_at <= promotion.starts_at) {
+            return Err(error("promo_end must be after promo_start".to_string()));
+        }
+
+        Ok(Self {
+            product_id: product_id.to_string(),
+            name: require("name")?.to_string(),
+            market_id,
+            images: get("images")
+                .map(|images| images.split(IMAGE_SEPARATOR).map(|image| image.trim().to_string()).filter(|image| !image.is_empty()).collect())
+                .unwrap_or_default(),
+            url: require("url")?.to_string(),
+            price,
+            currency: get("currency").unwrap_or(DEFAULT_CURRENCY).to_uppercase(),
+            timestamp,
+            promotion,
+        })
+    }
+}
+
+pub fn parse_feed(content: &str, format: FeedFormat, imported_at: DateTime<Utc>) -> Result<Vec<FeedRecord>, FeedError> {
+    let rows = match format {
+        FeedFormat::Csv => parse_csv_rows(content)?,
+        FeedFormat::Json => parse_json_rows(content)?,
+    };
+
+    rows.iter()
+        .map(|(line, fields)| FeedRecord::from_fields(fields, *line, imported_at))
+        .collect()
+}
+
+// a header row followed by the records, separated by "," or ";", quoted fields may contain separators, quotes ("") and line breaks
+pub(crate) fn parse_csv_rows(content: &str) -> Result<Vec<FeedRow>, FeedError> {
+    let mut rows: Vec<(usize, Vec<String>)> = vec![];
+    let mut row = vec![];
+    let mut field = String::new();
+    let mut is_quoted = false;
+    let mut line = 1;
+    let mut row_line = 1;
+    let content = content.trim_start_matches('\u{feff}');
+    // spreadsheets with a german locale separate by ";" because "," is the decimal separator
+    let header_line = content.lines().next().unwrap_or_default();
+    let separator = if header_line.contains(';') && !header_line.contains(',') { ';' } else { ',' };
+    let mut chars = content.chars().peekable();
+
+    while let Some(char) = chars.next() {
+        match (char, is_quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            },
+            ('"', true) => is_quoted = false,
+            ('"', false) if field.is_empty() => is_quoted = true,
+            (char, false) if char == separator => row.push(std::mem::take(&mut field)),
+            ('\r', false) => {},
+            ('\n', false) => {
+                row.push(std::mem::take(&mut field));
+                rows.push((row_line, std::mem::take(&mut row)));
+                line += 1;
+                row_line = line;
+            },
+            ('\n', true) => {
+                field.push(char);
+                line += 1;
+            },
+            _ => field.push(char),
+        }
+    }
+    if is_quoted {
+        return Err(FeedError { line: row_line, message: "unterminated quote".to_string() });
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push((row_line, row));
+    }
+
+    let mut rows = rows.into_iter().filter(|(_, row)| row.iter().any(|field| !field.trim().is_empty()));
+    let header = match rows.next() {
+        Some((_, header)) => header.into_iter().map(|name| name.trim().to_lowercase()).collect::<Vec<String>>(),
+        None => return Ok(vec![]),
+    };
+
+    rows.map(|(line, row)| {
+        if row.len() != header.len() {
+            return Err(FeedError { line, message: format!("expected {} columns, got {}", header.len(), row.len()) });
+        }
+        Ok((line, header.iter().cloned().zip(row).collect()))
+    }).collect()
+}
+
+// an array of objects, images may be an array, prices may be numbers
+fn parse_json_rows(content: &str) -> Result<Vec<FeedRow>, FeedError> {
+    let records = match serde_json::from_str::<Value>(content) {
+        Ok(Value::Array(records)) => records,
+        Ok(_) => return Err(FeedError { line: 1, message: "expected an array of records".to_string() }),
+        Err(e) => return Err(FeedError { line: e.line(), message: e.to_string() }),
+    };
+
+    records.into_iter().enumerate().map(|(index, record)| {
+        // json has no meaningful lines, the position of the record is reported instead
+        let line = index + 1;
+        let Value::Object(record) = record else {
+            return Err(FeedError { line, message: "expected an object".to_string() });
+        };
+
+        Ok((line, record.into_iter().map(|(name, value)| {
+            let value = match value {
+                Value::String(value) => value,
+                Value::Array(values) => values.iter()
+                    .filter_map(|value| value.as_str())
+                    .collect::<Vec<&str>>()
+                    .join(IMAGE_SEPARATOR.to_string().as_str()),
+                Value::Null => "".to_string(),
+                value => value.to_string(),
+            };
+            (name.to_lowercase(), value)
+        }).collect()))
+    }).collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportChange {
+    Created,
+    Updated(Vec<String>),
+    Unchanged,
+}
+
+#[derive(Debug)]
+pub struct ImportDiff {
+    pub product_id: String,
+    pub change: ImportChange,
+    pub is_price_added: bool,
+    pub is_promotion_saved: bool,
+}
+
+impl fmt::Display for ImportDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let price = match (self.is_price_added, self.is_promotion_saved) {
+            (true, true) => ", new price, promotion",
+            (true, false) => ", new price",
+            (false, true) => ", promotion",
+            (false, false) => "",
+        };
+        match &self.change {
+            ImportChange::Created => write!(f, "+ {}{}", self.product_id, price),
+            ImportChange::Updated(fields) => write!(f, "~ {} ({}){}", self.product_id, fields.join(", "), price),
+            ImportChange::Unchanged => write!(f, "  {}{}", self.product_id, price),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub diffs: Vec<ImportDiff>,
+}
+
+impl ImportReport {
+    pub fn count(&self, change: fn(&ImportChange) -> bool) -> usize {
+        self.diffs.iter().filter(|diff| change(&diff.change)).count()
+    }
+
+    pub fn count_prices_added(&self) -> usize {
+        self.diffs.iter().filter(|diff| diff.is_price_added).count()
+    }
+
+    pub fn count_promotions_saved(&self) -> usize {
+        self.diffs.iter().filter(|diff| diff.is_promotion_saved).count()
+    }
+
+    // only what changed, an idempotent re-run prints nothing but the summary
+    pub fn get_changes(&self) -> impl Iterator<Item = &ImportDiff> {
+        self.diffs.iter().filter(|diff| diff.change != ImportChange::Unchanged || diff.is_price_added || diff.is_promotion_saved)
+    }
+
+    pub fn format_summary(&self) -> String {
+        format!(
+            "{} created, {} updated, {} unchanged, {} prices added, {} promotions saved",
+            self.count(|change| *change == ImportChange::Created),
+            self.count(|change| matches!(change, ImportChange::Updated(_))),
+            self.count(|change| *change == ImportChange::Unchanged),
+            self.count_prices_added(),
+            self.count_promotions_saved(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use super::*;
+
+    fn imported_at() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 10, 19, 6, 0, 0).unwrap()
+    }
+
+    fn fields(row: &FeedRow) -> Vec<(&str, &str)> {
+        let mut fields = row.1.iter().map(|(name, value)| (name.as_str(), value.as_str())).collect::<Vec<_>>();
+        fields.sort_unstable();
+        fields
+    }
+
+    #[test]
+    fn feed_format_is_read_from_the_extension() {
+        assert_eq!(FeedFormat::from_path("feeds/lidl.CSV"), Some(FeedFormat::Csv));
+        assert_eq!(FeedFormat::from_path("aldisued.json"), Some(FeedFormat::Json));
+        assert_eq!(FeedFormat::from_path("feed.xml"), None);
+    }
+
+    #[test]
+    fn parse_csv_rows_reads_quoted_fields_with_separators_quotes_and_line_breaks() {
+        let rows = parse_csv_rows("\u{feff}Name,Note\r\n\"Milch, fettarm\",\"1,5% \"\"Bio\"\"\"\n\n\"Brot\",\"zwei\nZeilen\"\nEier,\n").unwrap();
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].0, 2);
+        assert_eq!(fields(&rows[0]), vec![("name", "Milch, fettarm"), ("note", "1,5% \"Bio\"")]);
+        assert_eq!(rows[1].0, 4);
+        assert_eq!(fields(&rows[1]), vec![("name", "Brot"), ("note", "zwei\nZeilen")]);
+        // the line a record starts on, after the line break in the quoted field
+        assert_eq!(rows[2].0, 6);
+        assert_eq!(fields(&rows[2]), vec![("name", "Eier"), ("note", "")]);
+    }
+
+    #[test]
+    fn parse_csv_rows_separates_by_semicolon_if_the_header_has_no_comma() {
+        let rows = parse_csv_rows("name;price\nMilch;1,09").unwrap();
+        assert_eq!(fields(&rows[0]), vec![("name", "Milch"), ("price", "1,09")]);
+    }
+
+    #[test]
+    fn parse_csv_rows_reports_the_line_of_invalid_rows() {
+        let error = parse_csv_rows("name,price\nMilch,1.09\nBrot\n").unwrap_err();
+        assert_eq!((error.line, error.message.as_str()), (3, "expected 2 columns, got 1"));
+
+        let error = parse_csv_rows("name,price\nMilch,1.09\n\"Brot,2.49\n").unwrap_err();
+        assert_eq!((error.line, error.message.as_str()), (3, "unterminated quote"));
+
+        assert!(parse_csv_rows("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn parse_feed_reads_csv_records() {
+        let content = "product_id,name,market,url,price,currency,images,timestamp,promo_price,promo_end,promo_condition\n\
+            lidl-1,Milbona Vollmilch,lidl,https://www.lidl.de/p/1,\"1,09\",,a.jpg| b.jpg,2026-10-18T08:00:00+02:00,0.99,2026-10-25T00:00:00Z,lidl_plus\n\
+            2,Brot,aldisued,https://www.aldi-sued.de/p/2,,chf,,,,,\n";
+        let records = parse_feed(content, FeedFormat::Csv, imported_at()).unwrap();
+
+        let timestamp = Utc.with_ymd_and_hms(2026, 10, 18, 6, 0, 0).unwrap();
+        assert_eq!(records[0], FeedRecord {
+            product_id: "lidl-1".to_string(),
+            name: "Milbona Vollmilch".to_string(),
+            market_id: Market::Lidl.get_id(),
+            images: vec!["a.jpg".to_string(), "b.jpg".to_string()],
+            url: "https://www.lidl.de/p/1".to_string(),
+            price: Some(109),
+            currency: "EUR".to_string(),
+            timestamp,
+            promotion: Some(FeedPromotion {
+                starts_at: timestamp,
+                ends_at: Utc.with_ymd_and_hms(2026, 10, 25, 0, 0, 0).unwrap(),
+                price: 99,
+                condition: Some(PromotionCondition::LoyaltyCard),
+            }),
+        });
+        assert_eq!(records[1].market_id, Market::AldiSued.get_id());
+        assert_eq!(records[1].price, None);
+        assert_eq!(records[1].currency, "CHF");
+        assert_eq!(records[1].timestamp, imported_at());
+        assert_eq!(records[1].promotion, None);
+    }
+
+    #[test]
+    fn parse_feed_reads_json_records() {
+        let content = r#"[{"id": "aldisued-7", "name": "Bananen", "market": "2", "url": "https://www.aldi-sued.de/p/7", "price": 1.29, "images": ["a.jpg", "b.jpg"], "currency": null}]"#;
+        let records = parse_feed(content, FeedFormat::Json, imported_at()).unwrap();
+
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].product_id, "aldisued-7");
+        assert_eq!(records[0].market_id, Market::AldiSued.get_id());
+        assert_eq!(records[0].price, Some(129));
+        assert_eq!(records[0].images, vec!["a.jpg".to_string(), "b.jpg".to_string()]);
+        assert_eq!(records[0].currency, "EUR");
+    }
+
+    #[test]
+    fn parse_feed_reports_the_line_of_invalid_records() {
+        let error = |content: &str, format: FeedFormat| parse_feed(content, format, imported_at()).unwrap_err().to_string();
+
+        assert_eq!(error("product_id,name,market,url\n1,Milch,edeka,https://x\n", FeedFormat::Csv), "line 2: unknown market \"edeka\"");
+        assert_eq!(error("product_id,name,market,url,price\n1,Milch,lidl,https://x,gratis\n", FeedFormat::Csv), "line 2: invalid price \"gratis\"");
+        assert_eq!(error("product_id,name,market\n1,Milch,lidl\n", FeedFormat::Csv), "line 2: missing \"url\"");
+        assert_eq!(
+            error("product_id,name,market,url,promo_price,promo_start,promo_end\n1,Milch,lidl,https://x,0.99,2026-10-20T00:00:00Z,2026-10-19T00:00:00Z\n", FeedFormat::Csv),
+            "line 2: promo_end must be after promo_start",
+        );
+        assert_eq!(error(r#"[{"id": "1"}, "Milch"]"#, FeedFormat::Json), "line 2: expected an object");
+        assert_eq!(error(r#"{"id": "1"}"#, FeedFormat::Json), "line 1: expected an array of records");
+    }
+}
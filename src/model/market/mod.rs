@@ -0,0 +1,41 @@
+// the markets products are imported from, the discriminant is products.market_id
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Market {
+    Lidl = 1,
+    AldiSued = 2,
+    AldiNord = 3,
+}
+
+impl Market {
+    pub const ALL: [Market; 3] = [Market::Lidl, Market::AldiSued, Market::AldiNord];
+
+    // either the id or the slug, e.g. "1" or "lidl"
+    pub fn from_str(str: &str) -> Option<Self> {
+        let str = str.trim().to_lowercase();
+        Market::ALL.into_iter().find(|market| market.get_slug() == str || market.get_id().to_string() == str)
+    }
+
+    pub fn get_id(&self) -> i64 {
+        *self as i64
+    }
+
+    pub fn get_slug(&self) -> &'static str {
+        match self {
+            Market::Lidl => "lidl",
+            Market::AldiSued => "aldisued",
+            Market::AldiNord => "aldinord",
+        }
+    }
+
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            Market::Lidl => "Lidl",
+            Market::AldiSued => "Aldi Süd",
+            Market::AldiNord => "Aldi Nord",
+        }
+    }
+
+    pub fn from_id(id: i64) -> Option<Self> {
+        Market::ALL.into_iter().find(|market| market.get_id() == id)
+    }
+}
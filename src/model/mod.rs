@@ -1,5 +1,10 @@
+pub mod category;
+pub mod import;
+pub mod market;
 pub mod misc;
 pub mod product;
 pub mod shopping_list;
 pub mod social_timeline;
+pub mod store;
+pub mod tour;
 pub mod user;
\ No newline at end of file
@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+
+// the current price has to be at least this much below the 90 day median to be a good price
+pub const GOOD_PRICE_DISCOUNT_PERCENT: i32 = 10;
+
+// from the product_price_stats materialized view, all prices in cents
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PriceStats {
+    pub all_time_low: Option<i32>,
+    pub all_time_high: Option<i32>,
+    pub median_price: Option<i32>,
+    // below the median, negative if the current price is above it
+    pub discount_percent: Option<i32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DealBadge {
+    AllTimeLow,
+    GoodPrice(i32),
+}
+
+impl PriceStats {
+    pub fn get_deal_badge(&self, price: Option<i32>) -> Option<DealBadge> {
+        let price = price?;
+        // a price that never changed is its own all-time low, that's no deal
+        let has_been_higher = self.all_time_high.zip(self.all_time_low).is_some_and(|(high, low)| high > low);
+        if has_been_higher && self.all_time_low.is_some_and(|low| price <= low) {
+            return Some(DealBadge::AllTimeLow);
+        }
+
+        self.discount_percent
+            .filter(|discount_percent| *discount_percent >= GOOD_PRICE_DISCOUNT_PERCENT)
+            .map(DealBadge::GoodPrice)
+    }
+}
+
+impl DealBadge {
+    pub fn is_all_time_low(&self) -> bool {
+        *self == DealBadge::AllTimeLow
+    }
+
+    pub fn get_label(&self) -> String {
+        match self {
+            DealBadge::AllTimeLow => "Bestpreis".to_string(),
+            DealBadge::GoodPrice(discount_percent) => format!("-{} %", discount_percent),
+        }
+    }
+
+    pub fn get_title(&self) -> String {
+        match self {
+            DealBadge::AllTimeLow => "So günstig war es noch nie".to_string(),
+            DealBadge::GoodPrice(discount_percent) => format!("{} % unter dem mittleren Preis der letzten 90 Tage", discount_percent),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum DealSortBy {
+    #[default]
+    Discount,
+    Price,
+    Name,
+}
+
+impl DealSortBy {
+    pub const ALL: [DealSortBy; 3] = [DealSortBy::Discount, DealSortBy::Price, DealSortBy::Name];
+
+    pub fn from_str(str: &str) -> Self {
+        match str {
+            "price" => DealSortBy::Price,
+            "name" => DealSortBy::Name,
+            _ => DealSortBy::Discount,
+        }
+    }
+
+    // the value of the "sort_by" query param
+    pub fn get_param(&self) -> &'static str {
+        match self {
+            DealSortBy::Discount => "discount",
+            DealSortBy::Price => "price",
+            DealSortBy::Name => "name",
+        }
+    }
+
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            DealSortBy::Discount => "Größter Rabatt",
+            DealSortBy::Price => "Preis",
+            DealSortBy::Name => "Name",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::product::test_product;
+    use super::*;
+
+    fn stats(all_time_low: i32, all_time_high: i32, discount_percent: Option<i32>) -> PriceStats {
+        PriceStats { all_time_low: Some(all_time_low), all_time_high: Some(all_time_high), median_price: None, discount_percent }
+    }
+
+    #[test]
+    fn the_lowest_price_ever_is_an_all_time_low() {
+        assert_eq!(stats(99, 149, Some(5)).get_deal_badge(Some(99)), Some(DealBadge::AllTimeLow));
+        assert_eq!(stats(99, 149, Some(30)).get_deal_badge(Some(89)), Some(DealBadge::AllTimeLow));
+        // a price that never changed
+        assert_eq!(stats(99, 99, Some(0)).get_deal_badge(Some(99)), None);
+        assert_eq!(PriceStats { all_time_low: Some(99), ..Default::default() }.get_deal_badge(Some(99)), None);
+    }
+
+    #[test]
+    fn a_good_price_needs_the_minimum_discount() {
+        assert_eq!(stats(99, 149, Some(GOOD_PRICE_DISCOUNT_PERCENT)).get_deal_badge(Some(109)), Some(DealBadge::GoodPrice(10)));
+        assert_eq!(stats(99, 149, Some(25)).get_deal_badge(Some(109)), Some(DealBadge::GoodPrice(25)));
+        assert_eq!(stats(99, 149, Some(GOOD_PRICE_DISCOUNT_PERCENT - 1)).get_deal_badge(Some(109)), None);
+        assert_eq!(stats(99, 149, Some(-20)).get_deal_badge(Some(149)), None);
+        assert_eq!(stats(99, 149, None).get_deal_badge(Some(109)), None);
+    }
+
+    #[test]
+    fn products_without_a_price_or_stats_have_no_badge() {
+        assert_eq!(stats(99, 149, Some(50)).get_deal_badge(None), None);
+
+        let mut product = test_product("lidl-1", 1, Some(99), "EUR");
+        assert_eq!(product.get_deal_badge(), None);
+        product.price_stats = Some(stats(99, 149, Some(20)));
+        assert_eq!(product.get_deal_badge(), Some(DealBadge::AllTimeLow));
+    }
+
+    #[test]
+    fn badges_are_labelled() {
+        assert_eq!(DealBadge::AllTimeLow.get_label(), "Bestpreis");
+        assert!(DealBadge::AllTimeLow.is_all_time_low());
+        assert_eq!(DealBadge::GoodPrice(15).get_label(), "-15 %");
+        assert_eq!(DealBadge::GoodPrice(15).get_title(), "15 % unter dem mittleren Preis der letzten 90 Tage");
+        assert!(!DealBadge::GoodPrice(15).is_all_time_low());
+    }
+
+    #[test]
+    fn deal_sort_params_round_trip() {
+        for sort_by in DealSortBy::ALL {
+            assert_eq!(DealSortBy::from_str(sort_by.get_param()), sort_by);
+        }
+        assert_eq!(DealSortBy::from_str("unknown"), DealSortBy::Discount);
+    }
+}
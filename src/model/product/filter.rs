@@ -0,0 +1,215 @@
+use serde::Serialize;
+use sqlx::FromRow;
+use crate::core::query_params::StateParams;
+use crate::model::product::search::SearchLanguage;
+
+#[derive(Debug, Clone, Default)]
+pub struct ProductFilter {
+    pub q: Option<String>,
+    pub language: SearchLanguage,
+    // slug of the category, products of all subcategories match as well
+    pub category: Option<String>,
+    pub market_ids: Vec<i64>,
+    // all prices in cents
+    pub min_price: Option<i32>,
+    pub max_price: Option<i32>,
+    pub min_unit_price: Option<i32>,
+    pub max_unit_price: Option<i32>,
+    pub on_sale: bool,
+    // the selected shopping list of the user, if only products "in my list" are requested
+    pub in_shopping_list_id: Option<i64>,
+}
+
+impl ProductFilter {
+    pub fn from_query_params(query_params: &StateParams, selected_shopping_list_id: Option<i64>) -> Self {
+        Self {
+            q: query_params.get_q().filter(|q| !q.is_empty()),
+            language: query_params.get_search_language(),
+            category: query_params.get_category(),
+            market_ids: query_params.get_market_ids(),
+            min_price: query_params.get_min_price(),
+            max_price: query_params.get_max_price(),
+            min_unit_price: query_params.get_min_unit_price(),
+            max_unit_price: query_params.get_max_unit_price(),
+            on_sale: query_params.is_on_sale(),
+            in_shopping_list_id: if query_params.is_in_list() {
+                // without a selected list nothing can be in it
+                Some(selected_shopping_list_id.unwrap_or(0))
+            } else {
+                None
+            },
+        }
+    }
+
+    pub fn with_category(mut self, category: Option<String>) -> Self {
+        self.category = category;
+        self
+    }
+
+    pub fn without_markets(&self) -> Self {
+        Self { market_ids: vec![], ..self.clone() }
+    }
+
+    pub fn without_category(&self) -> Self {
+        Self { category: None, ..self.clone() }
+    }
+
+    pub fn without_flags(&self) -> Self {
+        Self { on_sale: false, in_shopping_list_id: None, ..self.clone() }
+    }
+
+    pub fn has_market(&self, market_id: &str) -> bool {
+        market_id.parse::<i64>().is_ok_and(|id| self.market_ids.contains(&id))
+    }
+
+    // the "market" query params after toggling the given market
+    pub fn toggle_market(&self, market_id: &str) -> Vec<String> {
+        let Ok(market_id) = market_id.parse::<i64>() else {
+            return self.market_ids.iter().map(|id| id.to_string()).collect();
+        };
+
+        if self.market_ids.contains(&market_id) {
+            self.market_ids.iter().filter(|id| **id != market_id).map(|id| id.to_string()).collect()
+        } else {
+            self.market_ids.iter().chain([&market_id]).map(|id| id.to_string()).collect()
+        }
+    }
+
+    pub fn format_price_param(cents: &Option<i32>) -> String {
+        match cents {
+            Some(cents) => format!("{}.{:02}", cents / 100, cents % 100),
+            None => "".to_string(),
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.category.is_some()
+            || !self.market_ids.is_empty()
+            || self.min_price.is_some()
+            || self.max_price.is_some()
+            || self.min_unit_price.is_some()
+            || self.max_unit_price.is_some()
+            || self.on_sale
+            || self.in_shopping_list_id.is_some()
+    }
+}
+
+// the whitelisted columns a product list can be sorted by
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProductSortBy {
+    CreatedAt,
+    Name,
+    Price,
+    UnitPrice,
+}
+
+impl ProductSortBy {
+    pub fn from_str(str: &str) -> Self {
+        match str {
+            "name" => ProductSortBy::Name,
+            "price" => ProductSortBy::Price,
+            "unit_price" => ProductSortBy::UnitPrice,
+            _ => ProductSortBy::CreatedAt,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Facet {
+    pub value: String,
+    pub label: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Default)]
+pub struct ProductFacets {
+    pub markets: Vec<Facet>,
+    pub categories: Vec<Facet>,
+    pub on_sale_count: i64,
+    pub in_list_count: i64,
+}
+
+#[derive(Debug, FromRow)]
+pub struct FlagFacets {
+    pub on_sale_count: i64,
+    pub in_list_count: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(query: &str, selected_shopping_list_id: Option<i64>) -> ProductFilter {
+        ProductFilter::from_query_params(&StateParams::from_query(Some(query)), selected_shopping_list_id)
+    }
+
+    #[test]
+    fn filters_are_read_from_the_query() {
+        let filter = filter("q=milch&category=molkerei&market=1&market=3&min_price=0,99&max_price=2.5&min_unit_price=abc&on_sale=on", None);
+        assert_eq!(filter.q.as_deref(), Some("milch"));
+        assert_eq!(filter.category.as_deref(), Some("molkerei"));
+        assert_eq!(filter.market_ids, vec![1, 3]);
+        assert_eq!((filter.min_price, filter.max_price), (Some(99), Some(250)));
+        assert_eq!((filter.min_unit_price, filter.max_unit_price), (None, None));
+        assert!(filter.on_sale);
+        assert_eq!(filter.in_shopping_list_id, None);
+        assert!(filter.is_active());
+    }
+
+    #[test]
+    fn empty_and_invalid_values_filter_nothing() {
+        let filter = filter("q=&category=&min_price=-1&max_price=zwei&on_sale=nein", Some(7));
+        assert_eq!((filter.q.as_deref(), filter.category.as_deref()), (None, None));
+        assert_eq!((filter.min_price, filter.max_price), (None, None));
+        assert!(!filter.on_sale);
+        assert!(!filter.is_active());
+    }
+
+    #[test]
+    fn in_list_needs_a_selected_list() {
+        assert_eq!(filter("in_list=true", Some(7)).in_shopping_list_id, Some(7));
+        // matches nothing rather than everything
+        assert_eq!(filter("in_list=true", None).in_shopping_list_id, Some(0));
+        assert!(filter("in_list=1", None).is_active());
+        assert_eq!(filter("", Some(7)).in_shopping_list_id, None);
+    }
+
+    #[test]
+    fn markets_are_toggled() {
+        let filter = filter("market=1&market=3", None);
+        assert!(filter.has_market("3"));
+        assert!(!filter.has_market("2"));
+        assert!(!filter.has_market("lidl"));
+        assert_eq!(filter.toggle_market("3"), vec!["1"]);
+        assert_eq!(filter.toggle_market("2"), vec!["1", "3", "2"]);
+        assert_eq!(filter.toggle_market("lidl"), vec!["1", "3"]);
+        assert!(filter.without_markets().market_ids.is_empty());
+    }
+
+    #[test]
+    fn facets_leave_out_their_own_filter() {
+        let filter = filter("category=milch&market=1&on_sale=1&in_list=1", Some(7));
+        assert_eq!(filter.without_category().category, None);
+        assert_eq!(filter.without_category().market_ids, vec![1]);
+        let without_flags = filter.without_flags();
+        assert!(!without_flags.on_sale);
+        assert_eq!(without_flags.in_shopping_list_id, None);
+        assert_eq!(without_flags.category.as_deref(), Some("milch"));
+    }
+
+    #[test]
+    fn price_params_are_formatted_in_euros() {
+        assert_eq!(ProductFilter::format_price_param(&Some(199)), "1.99");
+        assert_eq!(ProductFilter::format_price_param(&Some(5)), "0.05");
+        assert_eq!(ProductFilter::format_price_param(&Some(1000)), "10.00");
+        assert_eq!(ProductFilter::format_price_param(&None), "");
+    }
+
+    #[test]
+    fn unknown_sort_columns_sort_by_creation() {
+        assert_eq!(ProductSortBy::from_str("price"), ProductSortBy::Price);
+        assert_eq!(ProductSortBy::from_str("unit_price"), ProductSortBy::UnitPrice);
+        assert_eq!(ProductSortBy::from_str("name"), ProductSortBy::Name);
+        assert_eq!(ProductSortBy::from_str("id; DROP TABLE products"), ProductSortBy::CreatedAt);
+    }
+}
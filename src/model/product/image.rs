@@ -0,0 +1,42 @@
+// the fixed sizes product images are resized to, anything else would let a client fill the image cache
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImageSize {
+    // list entries
+    Thumbnail,
+    Small,
+    // the detail view
+    Medium,
+    Large,
+}
+
+impl ImageSize {
+    pub fn from_str(str: &str) -> Option<Self> {
+        match str {
+            "thumb" => Some(ImageSize::Thumbnail),
+            "small" => Some(ImageSize::Small),
+            "medium" => Some(ImageSize::Medium),
+            "large" => Some(ImageSize::Large),
+            _ => None,
+        }
+    }
+
+    // the ":size" of "/img/:product_id/:size"
+    pub fn get_param(&self) -> &'static str {
+        match self {
+            ImageSize::Thumbnail => "thumb",
+            ImageSize::Small => "small",
+            ImageSize::Medium => "medium",
+            ImageSize::Large => "large",
+        }
+    }
+
+    // in pixels, images are fit into a square of it and never upscaled
+    pub fn get_width(&self) -> u32 {
+        match self {
+            ImageSize::Thumbnail => 96,
+            ImageSize::Small => 192,
+            ImageSize::Medium => 384,
+            ImageSize::Large => 768,
+        }
+    }
+}
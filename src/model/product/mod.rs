@@ -1,8 +1,18 @@
+use askama::{Html, MarkupDisplay};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, Row, postgres::PgRow};
+use crate::core::pagination::Cursor;
+use crate::model::shopping_list::quantity::ItemQuantity;
+use deal::{DealBadge, PriceStats};
 
+pub mod deal;
+pub mod filter;
+pub mod image;
 pub mod price_diagram;
+pub mod promotion;
+pub mod search;
+pub mod suggestion;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Product {
@@ -12,7 +22,27 @@ pub struct Product {
     pub images: Vec<String>,
     pub url: String,
     pub market_id: i64, // TODO it's actually an enum, "LIDL", "TESCO", "SAINSBURY" usw. => get it from the ID! or via SQL JOIN
+    pub category_id: Option<i64>,
     pub current_price: Option<Price>,
+    // in cents per unit, e.g. per "kg" or "l"
+    pub unit_price: Option<i32>,
+    pub unit: Option<String>,
+    // the name with highlighted search matches
+    pub headline: Option<String>,
+    // when it was put on a shopping list, only set for the products of a list
+    pub added_at: Option<DateTime<Utc>>,
+    // its place on a shopping list, lower comes first, only set for the products of a list
+    pub position: Option<i32>,
+    // when the link check found the product gone from its market, only set for the products of a list
+    pub stale_at: Option<DateTime<Utc>>,
+    // how much of it is on the list, only set for the products of a list
+    pub quantity: Option<ItemQuantity>,
+    // when it was checked off while shopping, only set for the products of a list
+    pub checked_at: Option<DateTime<Utc>>,
+    // only set where the query joins product_price_stats
+    pub price_stats: Option<PriceStats>,
+    // the sort column of a product list as the query ordered by it, only set for product lists
+    pub sort_key: Option<String>,
 }
 
 #[derive(Debug)]
@@ -29,6 +59,22 @@ impl<'r> FromRow<'r, PgRow> for Product {
         let images = row.try_get("images")?;
         let url = row.try_get("url")?;
         let market_id = row.try_get("market_id")?;
+        let category_id = row.try_get("category_id").ok().flatten();
+        let unit_price = row.try_get("unit_price").ok().flatten();
+        let unit = row.try_get("unit").ok().flatten();
+        let headline = row.try_get("headline").ok().flatten();
+        let added_at = row.try_get("added_at").ok().flatten();
+        let position = row.try_get("position").ok().flatten();
+        let stale_at = row.try_get("stale_at").ok().flatten();
+        let quantity = ItemQuantity::from_row(row).ok();
+        let checked_at = row.try_get("checked_at").ok().flatten();
+        let sort_key = row.try_get("sort_key").ok().flatten();
+        let price_stats = row.try_get::<Option<i32>, _>("all_time_low").ok().map(|all_time_low| PriceStats {
+            all_time_low,
+            all_time_high: row.try_get("all_time_high").ok().flatten(),
+            median_price: row.try_get("median_price").ok().flatten(),
+            discount_percent: row.try_get("discount_percent").ok().flatten(),
+        });
 
         let price = row.try_get("price");
         let currency = row.try_get("currency");
@@ -39,7 +85,8 @@ impl<'r> FromRow<'r, PgRow> for Product {
                 Price {
                     price: price.ok(),
                     currency: currency.unwrap(),
-                    created_at: None,
+                    // when it was imported, only set where the query joins the latest price
+                    created_at: row.try_get("price_created_at").ok().flatten(),
                 }
             );
         }
@@ -51,7 +98,18 @@ impl<'r> FromRow<'r, PgRow> for Product {
             images,
             url,
             market_id,
+            category_id,
             current_price,
+            unit_price,
+            unit,
+            headline,
+            added_at,
+            position,
+            stale_at,
+            quantity,
+            checked_at,
+            price_stats,
+            sort_key,
         })
     }
 }
@@ -64,6 +122,68 @@ impl Product {
 
         self.current_price.as_ref().expect("current_price must be some after the is_none check").format()
     }
+
+    // the keyset position on a shopping list, ordered by the position of the item
+    pub fn get_shopping_list_cursor(&self) -> Cursor {
+        Cursor::new(
+            self.position.map(|position| position.to_string()).unwrap_or_default(),
+            self.id.to_owned(),
+        )
+    }
+
+    // the keyset position in a product list, the sort key is the value the query compares
+    pub fn get_list_cursor(&self) -> Cursor {
+        Cursor::new(self.sort_key.to_owned().unwrap_or_default(), self.id.to_owned())
+    }
+
+    // the price times the quantity on the list, just the price for products that aren't on one
+    pub fn get_line_total(&self) -> Option<Price> {
+        let current_price = self.current_price.as_ref()?;
+        let price = current_price.price?;
+        let unit_price = self.unit_price.zip(self.unit.as_deref());
+
+        Some(Price {
+            price: Some(self.quantity.map(|quantity| quantity.get_total(price, unit_price)).unwrap_or(price)),
+            currency: current_price.currency.to_owned(),
+            created_at: None,
+        })
+    }
+
+    // a single piece costs just the price
+    pub fn has_line_total(&self) -> bool {
+        self.quantity.is_some_and(|quantity| quantity != ItemQuantity::default())
+    }
+
+    pub fn format_line_total(&self) -> String {
+        match self.get_line_total() {
+            Some(line_total) => line_total.format(),
+            None => self.format_price(),
+        }
+    }
+
+    pub fn get_deal_badge(&self) -> Option<DealBadge> {
+        let price = self.current_price.as_ref().and_then(|price| price.price);
+        self.price_stats.as_ref().and_then(|price_stats| price_stats.get_deal_badge(price))
+    }
+
+    // html, the headline is escaped by the query
+    pub fn get_display_name(&self) -> String {
+        match self.headline.as_ref() {
+            Some(headline) => headline.to_owned(),
+            None => MarkupDisplay::new_unsafe(self.name.as_str(), Html).to_string(),
+        }
+    }
+
+    pub fn format_unit_price(&self) -> String {
+        match (self.unit_price, self.unit.as_ref(), self.current_price.as_ref()) {
+            (Some(unit_price), Some(unit), Some(price)) => format!(
+                "{}/{}",
+                Price { price: Some(unit_price), currency: price.currency.clone(), created_at: None }.format(),
+                unit,
+            ),
+            _ => "".to_string(),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
@@ -79,14 +199,40 @@ impl Price {
             return "--.--".to_string();
         }
 
-        let price_val = self.price.expect("self.price must be some after the is_none check").to_string();
-        let euros = if price_val.len() > 2 {
-            &price_val[..(price_val.len() - 2)]
-        } else {
-            "0"
-        };
-        let cents = &price_val[(price_val.len() - 2)..];
+        let price_val = self.price.expect("self.price must be some after the is_none check");
+        format!("{}.{:02} {}", price_val / 100, price_val % 100, self.currency)
+    }
 
-        format!("{}.{} {}", euros, cents, self.currency)
+    // prices are entered in euros, "1,49" as well as "1.49"
+    pub fn parse_cents(euros: &str) -> Option<i32> {
+        let euros = euros.trim().replace(',', ".").parse::<f64>().ok()?;
+        if !euros.is_finite() || euros < 0f64 {
+            return None;
+        }
+        Some((euros * 100f64).round() as i32)
     }
-}
\ No newline at end of file
+}
+// a product as a shopping list query returns it, for the tests of the models built on top of it
+#[cfg(test)]
+pub fn test_product(id: &str, market_id: i64, price: Option<i32>, currency: &str) -> Product {
+    Product {
+        id: id.to_string(),
+        created_at: DateTime::UNIX_EPOCH,
+        name: id.to_string(),
+        images: vec![],
+        url: format!("https://example.com/{}", id),
+        market_id,
+        category_id: None,
+        current_price: Some(Price { price, currency: currency.to_string(), created_at: None }),
+        unit_price: None,
+        unit: None,
+        headline: None,
+        added_at: None,
+        position: None,
+        stale_at: None,
+        quantity: None,
+        checked_at: None,
+        price_stats: None,
+        sort_key: None,
+    }
+}
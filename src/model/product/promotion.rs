@@ -0,0 +1,183 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, Row, postgres::PgRow};
+use super::Price;
+
+// what it takes to get the promotion price, none means everybody gets it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PromotionCondition {
+    // e.g. "Lidl Plus"
+    LoyaltyCard,
+    // e.g. "2 für 1", buy 2 and pay for 1
+    MultiBuy { buy: i32, pay: i32 },
+}
+
+impl PromotionCondition {
+    // as in the feeds, "loyalty_card" or "<buy> for <pay>", e.g. "2for1" or "3 für 2"
+    pub fn from_str(str: &str) -> Option<Self> {
+        let str = str.trim().to_lowercase();
+        if matches!(str.as_str(), "loyalty_card" | "kundenkarte" | "lidl_plus") {
+            return Some(PromotionCondition::LoyaltyCard);
+        }
+
+        let (buy, pay) = str.split_once("for").or_else(|| str.split_once("für"))?;
+        let buy = buy.trim().parse::<i32>().ok()?;
+        let pay = pay.trim().parse::<i32>().ok()?;
+        (buy > pay && pay > 0).then_some(PromotionCondition::MultiBuy { buy, pay })
+    }
+
+    fn from_columns(condition: Option<&str>, buy_quantity: Option<i32>, pay_quantity: Option<i32>) -> Option<Self> {
+        match (condition?, buy_quantity, pay_quantity) {
+            ("loyalty_card", _, _) => Some(PromotionCondition::LoyaltyCard),
+            ("multi_buy", Some(buy), Some(pay)) => Some(PromotionCondition::MultiBuy { buy, pay }),
+            _ => None,
+        }
+    }
+
+    // the condition, buy_quantity and pay_quantity columns
+    pub fn get_columns(&self) -> (&'static str, Option<i32>, Option<i32>) {
+        match self {
+            PromotionCondition::LoyaltyCard => ("loyalty_card", None, None),
+            PromotionCondition::MultiBuy { buy, pay } => ("multi_buy", Some(*buy), Some(*pay)),
+        }
+    }
+
+    pub fn get_label(&self) -> String {
+        match self {
+            PromotionCondition::LoyaltyCard => "Nur mit Kundenkarte".to_string(),
+            PromotionCondition::MultiBuy { buy, pay } => format!("{} für {}", buy, pay),
+        }
+    }
+}
+
+// a temporary offer, kept apart from the regular prices so it doesn't skew their stats
+#[derive(Debug, Clone)]
+pub struct Promotion {
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    // in cents per item, for a multi buy what a single item costs when buying the whole bundle
+    pub price: i32,
+    pub currency: String,
+    pub condition: Option<PromotionCondition>,
+}
+
+impl<'r> FromRow<'r, PgRow> for Promotion {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let condition = row.try_get::<Option<String>, _>("condition")?;
+
+        Ok(Promotion {
+            starts_at: row.try_get("starts_at")?,
+            ends_at: row.try_get("ends_at")?,
+            price: row.try_get("price")?,
+            currency: row.try_get("currency")?,
+            condition: PromotionCondition::from_columns(
+                condition.as_deref(),
+                row.try_get("buy_quantity")?,
+                row.try_get("pay_quantity")?,
+            ),
+        })
+    }
+}
+
+impl Promotion {
+    pub fn is_active(&self) -> bool {
+        self.is_active_at(Utc::now())
+    }
+
+    pub fn is_active_at(&self, at: DateTime<Utc>) -> bool {
+        self.starts_at <= at && at < self.ends_at
+    }
+
+    pub fn format_price(&self) -> String {
+        Price { price: Some(self.price), currency: self.currency.to_owned(), created_at: None }.format()
+    }
+
+    // the end of an active promotion, the start of an upcoming one
+    pub fn get_countdown_target(&self) -> DateTime<Utc> {
+        self.get_countdown_target_at(Utc::now())
+    }
+
+    fn get_countdown_target_at(&self, at: DateTime<Utc>) -> DateTime<Utc> {
+        if self.is_active_at(at) { self.ends_at } else { self.starts_at }
+    }
+
+    // e.g. "2 Tage 3 Std.", the countdown element keeps it up to date in the browser
+    pub fn format_countdown(&self) -> String {
+        self.format_countdown_at(Utc::now())
+    }
+
+    fn format_countdown_at(&self, at: DateTime<Utc>) -> String {
+        let minutes = (self.get_countdown_target_at(at) - at).num_minutes().max(0);
+        let (days, hours, minutes) = (minutes / (24 * 60), minutes / 60 % 24, minutes % 60);
+        match (days, hours) {
+            (0, 0) => format!("{} Min.", minutes),
+            (0, _) => format!("{} Std. {} Min.", hours, minutes),
+            (1, _) => format!("1 Tag {} Std.", hours),
+            _ => format!("{} Tage {} Std.", days, hours),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+    use super::*;
+
+    #[test]
+    fn conditions_are_read_as_in_the_feeds() {
+        for condition in ["loyalty_card", "Kundenkarte", " lidl_plus "] {
+            assert_eq!(PromotionCondition::from_str(condition), Some(PromotionCondition::LoyaltyCard), "{}", condition);
+        }
+        assert_eq!(PromotionCondition::from_str("2for1"), Some(PromotionCondition::MultiBuy { buy: 2, pay: 1 }));
+        assert_eq!(PromotionCondition::from_str("3 für 2"), Some(PromotionCondition::MultiBuy { buy: 3, pay: 2 }));
+        assert_eq!(PromotionCondition::from_str("3 FOR 2"), Some(PromotionCondition::MultiBuy { buy: 3, pay: 2 }));
+        // paying for more than you get or for nothing isn't a promotion
+        for condition in ["1for2", "2for2", "2for0", "for1", "zwei für eins", "Lidl Plus", ""] {
+            assert_eq!(PromotionCondition::from_str(condition), None, "{}", condition);
+        }
+    }
+
+    #[test]
+    fn conditions_round_trip_through_their_columns() {
+        for condition in [PromotionCondition::LoyaltyCard, PromotionCondition::MultiBuy { buy: 3, pay: 2 }] {
+            let (name, buy_quantity, pay_quantity) = condition.get_columns();
+            assert_eq!(PromotionCondition::from_columns(Some(name), buy_quantity, pay_quantity), Some(condition));
+        }
+        assert_eq!(PromotionCondition::from_columns(None, None, None), None);
+        assert_eq!(PromotionCondition::from_columns(Some("multi_buy"), Some(2), None), None);
+        assert_eq!(PromotionCondition::MultiBuy { buy: 3, pay: 2 }.get_label(), "3 für 2");
+        assert_eq!(PromotionCondition::LoyaltyCard.get_label(), "Nur mit Kundenkarte");
+    }
+
+    fn promotion(starts_at: DateTime<Utc>, ends_at: DateTime<Utc>) -> Promotion {
+        Promotion { starts_at, ends_at, price: 99, currency: "EUR".to_string(), condition: None }
+    }
+
+    #[test]
+    fn promotions_are_active_from_their_start_until_their_end() {
+        let starts_at = Utc.with_ymd_and_hms(2026, 10, 19, 0, 0, 0).unwrap();
+        let ends_at = Utc.with_ymd_and_hms(2026, 10, 25, 0, 0, 0).unwrap();
+        let promotion = promotion(starts_at, ends_at);
+
+        assert!(!promotion.is_active_at(starts_at - Duration::seconds(1)));
+        assert!(promotion.is_active_at(starts_at));
+        assert!(promotion.is_active_at(ends_at - Duration::seconds(1)));
+        assert!(!promotion.is_active_at(ends_at));
+        assert_eq!(promotion.format_price(), Price { price: Some(99), currency: "EUR".to_string(), created_at: None }.format());
+    }
+
+    #[test]
+    fn the_countdown_runs_to_the_end_or_the_start() {
+        let starts_at = Utc.with_ymd_and_hms(2026, 10, 19, 0, 0, 0).unwrap();
+        let promotion = promotion(starts_at, starts_at + Duration::days(6));
+
+        // upcoming
+        assert_eq!(promotion.get_countdown_target_at(starts_at - Duration::hours(1)), starts_at);
+        assert_eq!(promotion.format_countdown_at(starts_at - Duration::minutes(42)), "42 Min.");
+        assert_eq!(promotion.format_countdown_at(starts_at - Duration::minutes(3 * 60 + 5)), "3 Std. 5 Min.");
+        // active
+        assert_eq!(promotion.format_countdown_at(starts_at + Duration::days(4) + Duration::hours(20)), "1 Tag 4 Std.");
+        assert_eq!(promotion.format_countdown_at(starts_at), "6 Tage 0 Std.");
+        // over
+        assert_eq!(promotion.format_countdown_at(starts_at + Duration::days(7)), "0 Min.");
+    }
+}
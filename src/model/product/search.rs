@@ -0,0 +1,36 @@
+// the postgres text search configuration used to stem the search and product names
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum SearchLanguage {
+    #[default]
+    German,
+    English,
+    // no stemming at all, e.g. for brand names
+    Simple,
+}
+
+impl SearchLanguage {
+    pub fn from_str(str: &str) -> Self {
+        match str {
+            "en" | "english" => SearchLanguage::English,
+            "simple" => SearchLanguage::Simple,
+            _ => SearchLanguage::German,
+        }
+    }
+
+    // the value of the "lang" query param
+    pub fn get_param(&self) -> &'static str {
+        match self {
+            SearchLanguage::German => "de",
+            SearchLanguage::English => "en",
+            SearchLanguage::Simple => "simple",
+        }
+    }
+
+    pub fn get_regconfig(&self) -> &'static str {
+        match self {
+            SearchLanguage::German => "german",
+            SearchLanguage::English => "english",
+            SearchLanguage::Simple => "simple",
+        }
+    }
+}
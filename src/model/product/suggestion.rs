@@ -0,0 +1,57 @@
+use askama::{Html, MarkupDisplay};
+use serde::Serialize;
+use sqlx::FromRow;
+use crate::model::product::filter::Facet;
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ProductSuggestion {
+    pub id: String,
+    pub name: String,
+    // the name with highlighted search matches
+    pub headline: Option<String>,
+    pub market_id: i64,
+}
+
+impl ProductSuggestion {
+    // html, the headline is escaped by the query
+    pub fn get_display_name(&self) -> String {
+        match self.headline.as_ref() {
+            Some(headline) => headline.to_owned(),
+            None => MarkupDisplay::new_unsafe(self.name.as_str(), Html).to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SearchSuggestions {
+    pub products: Vec<ProductSuggestion>,
+    pub categories: Vec<Facet>,
+    pub markets: Vec<Facet>,
+}
+
+impl SearchSuggestions {
+    pub fn is_empty(&self) -> bool {
+        self.products.is_empty() && self.categories.is_empty() && self.markets.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn suggestion(name: &str, headline: Option<&str>) -> ProductSuggestion {
+        ProductSuggestion { id: "lidl-1".to_string(), name: name.to_string(), headline: headline.map(str::to_string), market_id: 1 }
+    }
+
+    #[test]
+    fn display_name_escapes_the_name_without_a_headline() {
+        let suggestion = suggestion("<img src=x onerror=alert(1)> Milch & Honig", None);
+        assert_eq!(suggestion.get_display_name(), "&lt;img src=x onerror=alert(1)&gt; Milch &amp; Honig");
+    }
+
+    #[test]
+    fn display_name_is_the_headline_of_a_search() {
+        let suggestion = suggestion("Milch & Honig", Some("<mark>Milch</mark> &amp; Honig"));
+        assert_eq!(suggestion.get_display_name(), "<mark>Milch</mark> &amp; Honig");
+    }
+}
@@ -0,0 +1,133 @@
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+// a change to a shared list the other collaborators are notified about
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShoppingListChange<'a> {
+    // by product id, the notification shows the product's name
+    ProductAdded(&'a str),
+    ProductRemoved(&'a str),
+    QuantityChanged(&'a str),
+    TextItemAdded(&'a str),
+    TextItemRemoved(&'a str),
+    // e.g. "3 Einträge"
+    TripFinished(&'a str),
+    Renamed(&'a str),
+    // by the new member's email
+    MemberAdded(&'a str),
+    // e.g. "5 Einträge"
+    ItemsImported(&'a str),
+}
+
+impl<'a> ShoppingListChange<'a> {
+    pub fn get_column(&self) -> &'static str {
+        match self {
+            ShoppingListChange::ProductAdded(_) => "product_added",
+            ShoppingListChange::ProductRemoved(_) => "product_removed",
+            ShoppingListChange::QuantityChanged(_) => "quantity_changed",
+            ShoppingListChange::TextItemAdded(_) => "text_item_added",
+            ShoppingListChange::TextItemRemoved(_) => "text_item_removed",
+            ShoppingListChange::TripFinished(_) => "trip_finished",
+            ShoppingListChange::Renamed(_) => "renamed",
+            ShoppingListChange::MemberAdded(_) => "member_added",
+            ShoppingListChange::ItemsImported(_) => "items_imported",
+        }
+    }
+
+    pub fn get_subject(&self) -> &'a str {
+        match self {
+            ShoppingListChange::ProductAdded(subject)
+            | ShoppingListChange::ProductRemoved(subject)
+            | ShoppingListChange::QuantityChanged(subject)
+            | ShoppingListChange::TextItemAdded(subject)
+            | ShoppingListChange::TextItemRemoved(subject)
+            | ShoppingListChange::TripFinished(subject)
+            | ShoppingListChange::Renamed(subject)
+            | ShoppingListChange::MemberAdded(subject)
+            | ShoppingListChange::ItemsImported(subject) => subject,
+        }
+    }
+
+    pub fn get_product_id(&self) -> Option<&'a str> {
+        match self {
+            ShoppingListChange::ProductAdded(product_id)
+            | ShoppingListChange::ProductRemoved(product_id)
+            | ShoppingListChange::QuantityChanged(product_id) => Some(product_id),
+            _ => None,
+        }
+    }
+}
+
+// an unread change by another collaborator
+#[derive(Debug, Clone, FromRow)]
+pub struct ShoppingListNotification {
+    pub created_at: DateTime<Utc>,
+    pub by_email: String,
+    pub change: String,
+    pub subject: String,
+}
+
+impl ShoppingListNotification {
+    // e.g. "anna@example.com hat „Butter“ hinzugefügt"
+    pub fn format(&self) -> String {
+        let (by, subject) = (self.by_email.as_str(), self.subject.as_str());
+        match self.change.as_str() {
+            "product_added" | "text_item_added" => format!("{by} hat „{subject}“ hinzugefügt"),
+            "product_removed" | "text_item_removed" => format!("{by} hat „{subject}“ entfernt"),
+            "quantity_changed" => format!("{by} hat die Menge von „{subject}“ geändert"),
+            "trip_finished" => format!("{by} hat einen Einkauf abgeschlossen: {subject}"),
+            "renamed" => format!("{by} hat den Einkaufszettel in „{subject}“ umbenannt"),
+            "member_added" => format!("{by} hat den Einkaufszettel mit {subject} geteilt"),
+            "items_imported" => format!("{by} hat {subject} importiert"),
+            _ => format!("{by} hat den Einkaufszettel geändert"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notification(change: &str, subject: &str) -> ShoppingListNotification {
+        ShoppingListNotification {
+            created_at: DateTime::UNIX_EPOCH,
+            by_email: "anna@example.com".to_string(),
+            change: change.to_string(),
+            subject: subject.to_string(),
+        }
+    }
+
+    #[test]
+    fn product_changes_carry_the_product_id() {
+        let change = ShoppingListChange::QuantityChanged("lidl-1");
+        assert_eq!((change.get_column(), change.get_subject(), change.get_product_id()), ("quantity_changed", "lidl-1", Some("lidl-1")));
+
+        let change = ShoppingListChange::TextItemAdded("Brot");
+        assert_eq!((change.get_column(), change.get_subject(), change.get_product_id()), ("text_item_added", "Brot", None));
+        assert_eq!(ShoppingListChange::MemberAdded("ben@example.com").get_product_id(), None);
+    }
+
+    #[test]
+    fn every_change_is_formatted_as_a_sentence() {
+        let changes = [
+            ShoppingListChange::ProductAdded("Butter"),
+            ShoppingListChange::ProductRemoved("Butter"),
+            ShoppingListChange::QuantityChanged("Butter"),
+            ShoppingListChange::TextItemAdded("Brot"),
+            ShoppingListChange::TextItemRemoved("Brot"),
+            ShoppingListChange::TripFinished("3 Einträge"),
+            ShoppingListChange::Renamed("Wocheneinkauf"),
+            ShoppingListChange::MemberAdded("ben@example.com"),
+            ShoppingListChange::ItemsImported("5 Einträge"),
+        ];
+        for change in changes {
+            let text = notification(change.get_column(), change.get_subject()).format();
+            assert!(text.starts_with("anna@example.com hat "), "{}", text);
+            assert!(text.contains(change.get_subject()), "{}", text);
+        }
+
+        assert_eq!(notification("product_added", "Butter").format(), "anna@example.com hat „Butter“ hinzugefügt");
+        assert_eq!(notification("quantity_changed", "Butter").format(), "anna@example.com hat die Menge von „Butter“ geändert");
+        assert_eq!(notification("unknown", "").format(), "anna@example.com hat den Einkaufszettel geändert");
+    }
+}
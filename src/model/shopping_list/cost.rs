@@ -0,0 +1,185 @@
+use chrono::{DateTime, Utc};
+use crate::model::product::{Price, Product};
+
+const DEFAULT_CURRENCY: &str = "EUR";
+// prices are checked daily, anything older likely missed an import
+const OUTDATED_PRICE_AGE_IN_DAYS: i64 = 7;
+
+// what the products on a list cost at their market's current price, free text items have no price
+#[derive(Debug, Clone, Default)]
+pub struct ShoppingListCost {
+    // ordered by market
+    pub markets: Vec<MarketCost>,
+    // when the least recently checked price the totals are based on was checked
+    pub oldest_price_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MarketCost {
+    pub market_id: i64,
+    pub total: Price,
+    pub item_count: usize,
+    // products without a price aren't part of the total
+    pub missing_price_count: usize,
+}
+
+impl MarketCost {
+    pub fn has_price(&self) -> bool {
+        self.item_count > self.missing_price_count
+    }
+}
+
+impl ShoppingListCost {
+    pub fn from_products<'a>(products: impl IntoIterator<Item = &'a Product>) -> Self {
+        let mut cost = ShoppingListCost::default();
+        for product in products {
+            let index = match cost.markets.binary_search_by_key(&product.market_id, |market| market.market_id) {
+                Ok(index) => index,
+                Err(index) => {
+                    cost.markets.insert(index, MarketCost {
+                        market_id: product.market_id,
+                        total: Price { price: Some(0), currency: DEFAULT_CURRENCY.to_string(), created_at: None },
+                        item_count: 0,
+                        missing_price_count: 0,
+                    });
+                    index
+                },
+            };
+            let market = &mut cost.markets[index];
+            let has_price = market.has_price();
+            market.item_count += 1;
+
+            match product.get_line_total() {
+                // a price in another currency can't be added, it's left out like a missing one
+                Some(Price { price: Some(line_total), currency, .. }) if !has_price || currency == market.total.currency => {
+                    market.total.price = market.total.price.map(|total| total.saturating_add(line_total));
+                    market.total.currency = currency;
+                },
+                _ => market.missing_price_count += 1,
+            }
+
+            let price_at = product.current_price.as_ref().and_then(|price| price.created_at);
+            if price_at.is_some() && (cost.oldest_price_at.is_none() || price_at < cost.oldest_price_at) {
+                cost.oldest_price_at = price_at;
+            }
+        }
+        cost
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.markets.is_empty()
+    }
+
+    // the markets' totals added up, as if everything was bought at once. no price if they are in different currencies
+    pub fn get_total(&self) -> Price {
+        let mut priced_markets = self.markets.iter().filter(|market| market.has_price());
+        let currency = priced_markets.next()
+            .map(|market| market.total.currency.to_owned())
+            .unwrap_or(DEFAULT_CURRENCY.to_string());
+        let price = match priced_markets.all(|market| market.total.currency == currency) {
+            true => Some(self.markets.iter().filter(|market| market.has_price()).filter_map(|market| market.total.price).sum()),
+            false => None,
+        };
+
+        Price { price, currency, created_at: None }
+    }
+
+    pub fn get_missing_price_count(&self) -> usize {
+        self.markets.iter().map(|market| market.missing_price_count).sum()
+    }
+
+    pub fn is_outdated(&self) -> bool {
+        self.oldest_price_at
+            .is_some_and(|oldest_price_at| (Utc::now() - oldest_price_at).num_days() >= OUTDATED_PRICE_AGE_IN_DAYS)
+    }
+
+    pub fn format_price_age(&self) -> String {
+        let oldest_price_at = match self.oldest_price_at {
+            Some(oldest_price_at) => oldest_price_at,
+            None => return "Keine Preise".to_string(),
+        };
+
+        match (Utc::now().date_naive() - oldest_price_at.date_naive()).num_days() {
+            days if days <= 0 => "Preise von heute".to_string(),
+            1 => "Preise von gestern".to_string(),
+            days => format!("Preise von vor {} Tagen", days),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use crate::model::{product::test_product, shopping_list::quantity::{ItemQuantity, ItemUnit}};
+    use super::*;
+
+    fn totals(cost: &ShoppingListCost) -> Vec<(i64, Option<i32>, &str, usize, usize)> {
+        cost.markets.iter()
+            .map(|market| (market.market_id, market.total.price, market.total.currency.as_str(), market.item_count, market.missing_price_count))
+            .collect()
+    }
+
+    #[test]
+    fn totals_are_added_up_per_market_by_quantity() {
+        let mut butter = test_product("lidl-1", 1, Some(199), "EUR");
+        butter.quantity = ItemQuantity::new(2, ItemUnit::Piece);
+        let products = [butter, test_product("aldisued-1", 2, Some(99), "EUR"), test_product("lidl-2", 1, Some(49), "EUR")];
+
+        let cost = ShoppingListCost::from_products(products.iter());
+        assert_eq!(totals(&cost), vec![(1, Some(447), "EUR", 2, 0), (2, Some(99), "EUR", 1, 0)]);
+        assert_eq!((cost.get_total().price, cost.get_total().currency.as_str()), (Some(546), "EUR"));
+        assert_eq!(cost.get_missing_price_count(), 0);
+    }
+
+    #[test]
+    fn products_without_a_price_are_counted_as_missing() {
+        let products = [test_product("lidl-1", 1, None, "EUR"), test_product("lidl-2", 1, Some(49), "EUR"), test_product("aldisued-1", 2, None, "EUR")];
+
+        let cost = ShoppingListCost::from_products(products.iter());
+        assert_eq!(totals(&cost), vec![(1, Some(49), "EUR", 2, 1), (2, Some(0), "EUR", 1, 1)]);
+        assert!(!cost.markets[1].has_price());
+        assert_eq!(cost.get_total().price, Some(49));
+        assert_eq!(cost.get_missing_price_count(), 2);
+    }
+
+    #[test]
+    fn mixed_currencies_are_never_added_up() {
+        // the first price sets the currency of the market, others are left out like a missing price
+        let products = [test_product("lidl-1", 1, Some(199), "CHF"), test_product("lidl-2", 1, Some(99), "EUR"), test_product("aldisued-1", 2, Some(49), "EUR")];
+
+        let cost = ShoppingListCost::from_products(products.iter());
+        assert_eq!(totals(&cost), vec![(1, Some(199), "CHF", 2, 1), (2, Some(49), "EUR", 1, 0)]);
+        // the markets can't be added up either
+        assert_eq!(cost.get_total().price, None);
+        assert_eq!(cost.get_total().format(), "--.--");
+
+        let single_currency = ShoppingListCost::from_products(products[..2].iter());
+        assert_eq!((single_currency.get_total().price, single_currency.get_total().currency.as_str()), (Some(199), "CHF"));
+    }
+
+    #[test]
+    fn an_empty_list_costs_nothing() {
+        let cost = ShoppingListCost::from_products(std::iter::empty());
+        assert!(cost.is_empty());
+        assert_eq!((cost.get_total().price, cost.get_total().currency.as_str()), (Some(0), "EUR"));
+        assert_eq!(cost.format_price_age(), "Keine Preise");
+        assert!(!cost.is_outdated());
+    }
+
+    #[test]
+    fn the_age_is_the_one_of_the_oldest_price() {
+        let priced_at = |id: &str, days_ago: i64| {
+            let mut product = test_product(id, 1, Some(99), "EUR");
+            product.current_price.as_mut().unwrap().created_at = Some(Utc::now() - Duration::days(days_ago));
+            product
+        };
+
+        let cost = ShoppingListCost::from_products([priced_at("lidl-1", 0), priced_at("lidl-2", 3), priced_at("lidl-3", 1)].iter());
+        assert_eq!(cost.format_price_age(), "Preise von vor 3 Tagen");
+        assert!(!cost.is_outdated());
+
+        assert_eq!(ShoppingListCost::from_products([priced_at("lidl-1", 0)].iter()).format_price_age(), "Preise von heute");
+        assert_eq!(ShoppingListCost::from_products([priced_at("lidl-1", 1)].iter()).format_price_age(), "Preise von gestern");
+        assert!(ShoppingListCost::from_products([priced_at("lidl-1", OUTDATED_PRICE_AGE_IN_DAYS)].iter()).is_outdated());
+    }
+}
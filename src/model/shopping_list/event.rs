@@ -0,0 +1,153 @@
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShoppingListEventKind {
+    ItemAdded,
+    ItemRemoved,
+    ItemChecked,
+    // dragged to another place on the list
+    ItemMoved,
+    // e.g. its quantity
+    ItemUpdated,
+    // a free text item turned into a product
+    ItemLinked,
+    ListRenamed,
+    ListDeleted,
+    MembersChanged,
+}
+
+// a change to a list as sent by the triggers on the "shopping_list_changes" channel
+#[derive(Debug, Clone, Deserialize)]
+pub struct ShoppingListEvent {
+    pub shopping_list_id: i64,
+    pub kind: ShoppingListEventKind,
+    // none for a free text item
+    pub product_id: Option<String>,
+}
+
+impl ShoppingListEvent {
+    pub fn is_item_event(&self) -> bool {
+        !matches!(
+            self.kind,
+            ShoppingListEventKind::ListRenamed | ShoppingListEventKind::ListDeleted | ShoppingListEventKind::MembersChanged,
+        )
+    }
+
+    pub fn is_text_item_event(&self) -> bool {
+        self.is_item_event() && (self.product_id.is_none() || self.kind == ShoppingListEventKind::ItemLinked)
+    }
+}
+
+// what's open in the browser, decides which fragments are pushed
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShoppingListEventView {
+    Detail,
+    ShoppingMode,
+}
+
+impl ShoppingListEventView {
+    // whether the view shows anything the event changes
+    pub fn is_changed_by(&self, event: &ShoppingListEvent) -> bool {
+        match (self, event.kind) {
+            // the product list doesn't show whether a product is checked, moved products are in place after a reload
+            (ShoppingListEventView::Detail, ShoppingListEventKind::ItemChecked | ShoppingListEventKind::ItemMoved) => {
+                event.product_id.is_none()
+            },
+            // the checklist has no name to rename
+            (ShoppingListEventView::ShoppingMode, ShoppingListEventKind::ListRenamed) => false,
+            _ => true,
+        }
+    }
+
+    // the products of the detail page are swapped one by one, without knowing which changed the page is reloaded.
+    // the checklist is rendered as a whole and catches up on its own
+    pub fn is_reloaded_after_missed_events(&self) -> bool {
+        *self == ShoppingListEventView::Detail
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ShoppingListEventsParams {
+    pub view: ShoppingListEventView,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KINDS: [ShoppingListEventKind; 9] = [
+        ShoppingListEventKind::ItemAdded,
+        ShoppingListEventKind::ItemRemoved,
+        ShoppingListEventKind::ItemChecked,
+        ShoppingListEventKind::ItemMoved,
+        ShoppingListEventKind::ItemUpdated,
+        ShoppingListEventKind::ItemLinked,
+        ShoppingListEventKind::ListRenamed,
+        ShoppingListEventKind::ListDeleted,
+        ShoppingListEventKind::MembersChanged,
+    ];
+
+    fn event(kind: ShoppingListEventKind, product_id: Option<&str>) -> ShoppingListEvent {
+        ShoppingListEvent { shopping_list_id: 1, kind, product_id: product_id.map(str::to_string) }
+    }
+
+    #[test]
+    fn item_and_text_item_events_by_kind() {
+        for kind in KINDS {
+            let is_list_event = matches!(
+                kind,
+                ShoppingListEventKind::ListRenamed | ShoppingListEventKind::ListDeleted | ShoppingListEventKind::MembersChanged,
+            );
+            let product_event = event(kind, Some("lidl-1"));
+            let text_item_event = event(kind, None);
+
+            assert_eq!(product_event.is_item_event(), !is_list_event, "{:?}", kind);
+            assert_eq!(text_item_event.is_item_event(), !is_list_event, "{:?}", kind);
+            assert_eq!(text_item_event.is_text_item_event(), !is_list_event, "{:?}", kind);
+            // a linked item leaves the free text items
+            assert_eq!(product_event.is_text_item_event(), kind == ShoppingListEventKind::ItemLinked, "{:?}", kind);
+        }
+    }
+
+    #[test]
+    fn views_are_changed_by_the_events_they_show() {
+        use ShoppingListEventKind::*;
+        use ShoppingListEventView::*;
+
+        // (view, kind, changed by a product event, changed by a text item event)
+        let matrix = [
+            (Detail, ItemAdded, true, true),
+            (Detail, ItemRemoved, true, true),
+            (Detail, ItemChecked, false, true),
+            (Detail, ItemMoved, false, true),
+            (Detail, ItemUpdated, true, true),
+            (Detail, ItemLinked, true, true),
+            (Detail, ListRenamed, true, true),
+            (Detail, ListDeleted, true, true),
+            (Detail, MembersChanged, true, true),
+            (ShoppingMode, ItemAdded, true, true),
+            (ShoppingMode, ItemRemoved, true, true),
+            (ShoppingMode, ItemChecked, true, true),
+            (ShoppingMode, ItemMoved, true, true),
+            (ShoppingMode, ItemUpdated, true, true),
+            (ShoppingMode, ItemLinked, true, true),
+            (ShoppingMode, ListRenamed, false, false),
+            (ShoppingMode, ListDeleted, true, true),
+            (ShoppingMode, MembersChanged, true, true),
+        ];
+        assert_eq!(matrix.len(), 2 * KINDS.len());
+
+        for (view, kind, by_product, by_text_item) in matrix {
+            assert_eq!(view.is_changed_by(&event(kind, Some("lidl-1"))), by_product, "{:?} {:?}", view, kind);
+            assert_eq!(view.is_changed_by(&event(kind, None)), by_text_item, "{:?} {:?}", view, kind);
+        }
+    }
+
+    #[test]
+    fn only_the_detail_page_is_reloaded_after_missed_events() {
+        assert!(ShoppingListEventView::Detail.is_reloaded_after_missed_events());
+        assert!(!ShoppingListEventView::ShoppingMode.is_reloaded_after_missed_events());
+    }
+}
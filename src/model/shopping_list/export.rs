@@ -0,0 +1,282 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::model::market::Market;
+use super::{ShoppingChecklist, ShoppingList};
+
+pub const CSV_COLUMNS: [&str; 9] = ["name", "amount", "unit", "note", "product_id", "market", "line_total", "currency", "checked"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShoppingListExportFormat {
+    Csv,
+    Json,
+    Markdown,
+    Text,
+}
+
+impl ShoppingListExportFormat {
+    pub const ALL: [ShoppingListExportFormat; 4] = [
+        ShoppingListExportFormat::Csv,
+        ShoppingListExportFormat::Json,
+        ShoppingListExportFormat::Markdown,
+        ShoppingListExportFormat::Text,
+    ];
+
+    pub fn from_str(str: &str) -> Option<Self> {
+        ShoppingListExportFormat::ALL.into_iter().find(|format| format.get_param() == str)
+    }
+
+    pub fn get_param(&self) -> &'static str {
+        match self {
+            ShoppingListExportFormat::Csv => "csv",
+            ShoppingListExportFormat::Json => "json",
+            ShoppingListExportFormat::Markdown => "md",
+            ShoppingListExportFormat::Text => "txt",
+        }
+    }
+
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            ShoppingListExportFormat::Csv => "CSV",
+            ShoppingListExportFormat::Json => "JSON",
+            ShoppingListExportFormat::Markdown => "Markdown",
+            ShoppingListExportFormat::Text => "Text",
+        }
+    }
+
+    pub fn get_content_type(&self) -> &'static str {
+        match self {
+            ShoppingListExportFormat::Csv => "text/csv; charset=utf-8",
+            ShoppingListExportFormat::Json => "application/json",
+            ShoppingListExportFormat::Markdown => "text/markdown; charset=utf-8",
+            ShoppingListExportFormat::Text => "text/plain; charset=utf-8",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ShoppingListExportParams {
+    // defaults to json
+    pub format: Option<String>,
+}
+
+impl ShoppingListExportParams {
+    // none for an unknown format
+    pub fn get_format(&self) -> Option<ShoppingListExportFormat> {
+        match self.format.as_deref() {
+            Some(format) => ShoppingListExportFormat::from_str(format),
+            None => Some(ShoppingListExportFormat::Json),
+        }
+    }
+}
+
+// everything on a list, the way it leaves the app. the csv and text formats can be imported again
+#[derive(Debug, Serialize)]
+pub struct ShoppingListExport {
+    pub name: String,
+    pub is_template: bool,
+    pub exported_at: DateTime<Utc>,
+    pub items: Vec<ShoppingListExportItem>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ShoppingListExportItem {
+    pub name: String,
+    // free text items may have no amount
+    pub amount: Option<i32>,
+    pub unit: Option<&'static str>,
+    pub note: Option<String>,
+    // free text items aren't in the catalogue
+    pub product_id: Option<String>,
+    pub market: Option<&'static str>,
+    // the price times the amount in euros, e.g. "2.58"
+    pub line_total: Option<String>,
+    pub currency: Option<String>,
+    pub checked: bool,
+    // e.g. "2 Stück", for the formats read by people
+    #[serde(skip)]
+    pub quantity_label: Option<String>,
+}
+
+impl ShoppingListExport {
+    pub fn new(shopping_list: &ShoppingList, checklist: &ShoppingChecklist, exported_at: DateTime<Utc>) -> Self {
+        let products = checklist.products.iter().map(|product| {
+            let line_total = product.get_line_total().and_then(|line_total| line_total.price.map(|price| (price, line_total.currency)));
+            ShoppingListExportItem {
+                name: product.name.to_owned(),
+                amount: product.quantity.map(|quantity| quantity.amount),
+                unit: product.quantity.map(|quantity| quantity.unit.get_param()),
+                note: None,
+                product_id: Some(product.id.to_owned()),
+                market: Market::from_id(product.market_id).map(|market| market.get_slug()),
+                line_total: line_total.as_ref().map(|(price, _)| format!("{}.{:02}", price / 100, price % 100)),
+                currency: line_total.map(|(_, currency)| currency),
+                checked: product.checked_at.is_some(),
+                quantity_label: product.quantity.map(|quantity| quantity.format()),
+            }
+        });
+        let text_items = checklist.text_items.iter().map(|text_item| ShoppingListExportItem {
+            name: text_item.name.to_owned(),
+            amount: text_item.quantity.map(|quantity| quantity.amount),
+            unit: text_item.quantity.map(|quantity| quantity.unit.get_param()),
+            note: text_item.note.to_owned(),
+            product_id: None,
+            market: None,
+            line_total: None,
+            currency: None,
+            checked: text_item.checked_at.is_some(),
+            quantity_label: text_item.quantity.map(|quantity| quantity.format()),
+        });
+
+        Self {
+            name: shopping_list.name.to_owned(),
+            is_template: shopping_list.is_template,
+            exported_at,
+            items: products.chain(text_items).collect(),
+        }
+    }
+
+    // e.g. "wocheneinkauf.csv"
+    pub fn get_file_name(&self, format: &ShoppingListExportFormat) -> String {
+        format!("{}.{}", get_file_stem(self.name.as_str()), format.get_param())
+    }
+
+    pub fn render(&self, format: &ShoppingListExportFormat) -> String {
+        match format {
+            ShoppingListExportFormat::Csv => self.render_csv(),
+            ShoppingListExportFormat::Json => serde_json::to_string_pretty(self).unwrap_or_default(),
+            ShoppingListExportFormat::Markdown => self.render_markdown(),
+            ShoppingListExportFormat::Text => self.render_text(),
+        }
+    }
+
+    // separated by "," and quoted where needed, the header is CSV_COLUMNS
+    fn render_csv(&self) -> String {
+        let mut csv = CSV_COLUMNS.join(",");
+        csv.push('\n');
+        for item in self.items.iter() {
+            let fields = [
+                item.name.to_owned(),
+                item.amount.map(|amount| amount.to_string()).unwrap_or_default(),
+                item.unit.unwrap_or_default().to_string(),
+                item.note.to_owned().unwrap_or_default(),
+                item.product_id.to_owned().unwrap_or_default(),
+                item.market.unwrap_or_default().to_string(),
+                item.line_total.to_owned().unwrap_or_default(),
+                item.currency.to_owned().unwrap_or_default(),
+                item.checked.to_string(),
+            ];
+            csv.push_str(fields.iter().map(|field| quote_csv_field(field)).collect::<Vec<String>>().join(",").as_str());
+            csv.push('\n');
+        }
+        csv
+    }
+
+    // a task list, checked items are ticked off
+    fn render_markdown(&self) -> String {
+        let mut markdown = format!("# {}\n\n", self.name);
+        for item in self.items.iter() {
+            markdown.push_str(if item.checked { "- [x] " } else { "- [ ] " });
+            markdown.push_str(item.format_line().as_str());
+            if let (Some(line_total), Some(currency)) = (item.line_total.as_ref(), item.currency.as_ref()) {
+                markdown.push_str(format!(" · {} {}", line_total, currency).as_str());
+            }
+            markdown.push('\n');
+        }
+        markdown
+    }
+
+    // a line per item, e.g. "2 Stück Milch (fettarm)"
+    fn render_text(&self) -> String {
+        self.items.iter().map(|item| format!("{}\n", item.format_line())).collect()
+    }
+}
+
+impl ShoppingListExportItem {
+    fn format_line(&self) -> String {
+        let mut line = match self.quantity_label.as_ref() {
+            Some(quantity_label) => format!("{} {}", quantity_label, self.name),
+            None => self.name.to_owned(),
+        };
+        if let Some(note) = self.note.as_ref() {
+            line.push_str(format!(" ({})", note).as_str());
+        }
+        line
+    }
+}
+
+// the name of a list as a file name without the extension, non-ascii characters are replaced so the header stays valid
+pub fn get_file_stem(name: &str) -> String {
+    let name = name.to_lowercase()
+        .replace('ä', "ae").replace('ö', "oe").replace('ü', "ue").replace('ß', "ss")
+        .chars()
+        .map(|char| if char.is_ascii_alphanumeric() { char } else { '-' })
+        .collect::<String>();
+    let name = name.split('-').filter(|part| !part.is_empty()).collect::<Vec<&str>>().join("-");
+    if name.is_empty() { "einkaufszettel".to_string() } else { name }
+}
+
+fn quote_csv_field(field: &str) -> String {
+    if field.contains([',', ';', '"', '\n', '\r']) || field.trim() != field {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use super::*;
+    use crate::model::shopping_list::{import::parse_shopping_list_import, quantity::{ItemQuantity, ItemUnit}};
+
+    fn item(name: &str, quantity: Option<ItemQuantity>, note: Option<&str>, product_id: Option<&str>) -> ShoppingListExportItem {
+        ShoppingListExportItem {
+            name: name.to_string(),
+            amount: quantity.map(|quantity| quantity.amount),
+            unit: quantity.map(|quantity| quantity.unit.get_param()),
+            note: note.map(str::to_string),
+            product_id: product_id.map(str::to_string),
+            market: product_id.map(|_| "aldi"),
+            line_total: product_id.map(|_| "2.58".to_string()),
+            currency: product_id.map(|_| "EUR".to_string()),
+            checked: note.is_none(),
+            quantity_label: quantity.map(|quantity| quantity.format()),
+        }
+    }
+
+    #[test]
+    fn quote_csv_field_quotes_separators_quotes_and_padding() {
+        assert_eq!(quote_csv_field("Milch"), "Milch");
+        assert_eq!(quote_csv_field("Milch, fettarm"), "\"Milch, fettarm\"");
+        assert_eq!(quote_csv_field("3,5%; Bio"), "\"3,5%; Bio\"");
+        assert_eq!(quote_csv_field("Brot \"Dinkel\""), "\"Brot \"\"Dinkel\"\"\"");
+        assert_eq!(quote_csv_field("zwei\nZeilen"), "\"zwei\nZeilen\"");
+        assert_eq!(quote_csv_field(" Milch"), "\" Milch\"");
+    }
+
+    #[test]
+    fn render_csv_is_imported_as_it_was_exported() {
+        let items = vec![
+            item("Milbona Vollmilch; 3,5%", ItemQuantity::new(2, ItemUnit::Piece), None, Some("lidl-1")),
+            item("Brot \"Dinkel\"", None, Some("vom Bäcker, geschnitten"), None),
+            item("Kartoffeln", ItemQuantity::new(2500, ItemUnit::Gram), Some("mehlig\nfestkochend geht auch"), None),
+        ];
+        let export = ShoppingListExport {
+            name: "Wocheneinkauf".to_string(),
+            is_template: false,
+            exported_at: Utc.with_ymd_and_hms(2026, 10, 19, 8, 0, 0).unwrap(),
+            items,
+        };
+
+        let lines = parse_shopping_list_import(export.render_csv().as_str()).unwrap();
+
+        assert_eq!(lines.len(), export.items.len());
+        for (line, item) in lines.iter().zip(export.items.iter()) {
+            assert_eq!(line.name, item.name);
+            assert_eq!(line.quantity.map(|quantity| quantity.amount), item.amount);
+            assert_eq!(line.quantity.map(|quantity| quantity.unit.get_param()), item.unit);
+            assert_eq!(line.note, item.note);
+            assert_eq!(line.product_id, item.product_id);
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+use crate::model::{category::CategoryTree, market::Market, product::Product, store::aisle::StoreAisles};
+
+const UNCATEGORIZED_LABEL: &str = "Sonstiges";
+
+// how the products of a list are put under headings, free text items have neither a category nor a market
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShoppingListGrouping {
+    #[default]
+    Category,
+    Market,
+}
+
+impl ShoppingListGrouping {
+    pub const ALL: [ShoppingListGrouping; 2] = [ShoppingListGrouping::Category, ShoppingListGrouping::Market];
+
+    pub fn from_str(str: &str) -> Option<Self> {
+        ShoppingListGrouping::ALL.into_iter().find(|grouping| grouping.get_param() == str)
+    }
+
+    pub fn get_param(&self) -> &'static str {
+        match self {
+            ShoppingListGrouping::Category => "category",
+            ShoppingListGrouping::Market => "market",
+        }
+    }
+
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            ShoppingListGrouping::Category => "Nach Kategorie",
+            ShoppingListGrouping::Market => "Nach Markt",
+        }
+    }
+
+    // the position of the product's group and its heading. products are grouped by their top level category,
+    // along the aisles of the store and then in the order of the category tree, uncategorized products come last
+    pub fn get_group(&self, product: &Product, categories: &CategoryTree, aisles: &StoreAisles) -> (usize, String) {
+        match self {
+            ShoppingListGrouping::Category => product.category_id
+                .and_then(|category_id| categories.get_path(&category_id).first().copied())
+                .and_then(|root| aisles.get_position(&root.id)
+                    .or_else(|| categories.categories.iter()
+                        .position(|category| category.id == root.id)
+                        .map(|position| aisles.len() + position))
+                    .map(|position| (position, root.name.to_owned())))
+                .unwrap_or((aisles.len() + categories.categories.len(), UNCATEGORIZED_LABEL.to_string())),
+            ShoppingListGrouping::Market => match Market::from_id(product.market_id) {
+                Some(market) => (market.get_id() as usize, market.get_label().to_string()),
+                None => (usize::MAX, UNCATEGORIZED_LABEL.to_string()),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ShoppingListGroupingForm {
+    pub shopping_list_id: i64,
+    // empty keeps the order of the list
+    pub grouping: String,
+    // empty for none
+    #[serde(default)]
+    pub store_id: String,
+}
+
+impl ShoppingListGroupingForm {
+    // none for an unknown grouping
+    pub fn get_grouping(&self) -> Option<Option<ShoppingListGrouping>> {
+        if self.grouping.is_empty() {
+            return Some(None);
+        }
+        ShoppingListGrouping::from_str(self.grouping.as_str()).map(Some)
+    }
+
+    // none for an invalid id
+    pub fn get_store_id(&self) -> Option<Option<i64>> {
+        if self.store_id.is_empty() {
+            return Some(None);
+        }
+        self.store_id.parse::<i64>().ok().map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::{category::Category, product::test_product};
+    use super::*;
+
+    fn category(id: i64, parent_id: Option<i64>, name: &str) -> Category {
+        Category { id, parent_id, name: name.to_string(), slug: name.to_lowercase(), depth: 0, product_count: 0 }
+    }
+
+    // obst & gemüse > äpfel, molkerei > milch, getränke
+    fn categories() -> CategoryTree {
+        CategoryTree {
+            categories: vec![
+                category(1, None, "Obst & Gemüse"),
+                category(2, Some(1), "Äpfel"),
+                category(3, None, "Molkerei"),
+                category(4, Some(3), "Milch"),
+                category(5, None, "Getränke"),
+            ],
+        }
+    }
+
+    fn product_in(category_id: Option<i64>, market_id: i64) -> Product {
+        let mut product = test_product("lidl-1", market_id, Some(99), "EUR");
+        product.category_id = category_id;
+        product
+    }
+
+    #[test]
+    fn products_are_grouped_by_their_top_level_category_in_the_order_of_the_tree() {
+        let (categories, aisles) = (categories(), StoreAisles::default());
+        let group = |category_id| ShoppingListGrouping::Category.get_group(&product_in(category_id, 1), &categories, &aisles);
+
+        assert_eq!(group(Some(2)), (0, "Obst & Gemüse".to_string()));
+        assert_eq!(group(Some(4)), (2, "Molkerei".to_string()));
+        assert_eq!(group(Some(3)), (2, "Molkerei".to_string()));
+        // uncategorized and unknown categories come last
+        assert_eq!(group(None), (5, UNCATEGORIZED_LABEL.to_string()));
+        assert_eq!(group(Some(42)), (5, UNCATEGORIZED_LABEL.to_string()));
+    }
+
+    #[test]
+    fn placed_aisles_come_before_the_rest_of_the_tree() {
+        let categories = categories();
+        let aisles = StoreAisles { category_ids: vec![5, 3] };
+        let group = |category_id| ShoppingListGrouping::Category.get_group(&product_in(category_id, 1), &categories, &aisles).0;
+
+        // getränke, molkerei, then obst & gemüse by its place in the tree, then the uncategorized
+        assert!(group(Some(5)) < group(Some(4)));
+        assert!(group(Some(4)) < group(Some(2)));
+        assert!(group(Some(2)) < group(None));
+
+        let roots = aisles.sort_categories(&categories).iter().map(|category| category.id).collect::<Vec<i64>>();
+        assert_eq!(roots, vec![5, 3, 1]);
+    }
+
+    #[test]
+    fn products_are_grouped_by_market() {
+        let (categories, aisles) = (categories(), StoreAisles::default());
+        let group = |market_id| ShoppingListGrouping::Market.get_group(&product_in(Some(2), market_id), &categories, &aisles);
+
+        assert_eq!(group(Market::AldiSued.get_id()), (2, "Aldi Süd".to_string()));
+        assert!(group(Market::Lidl.get_id()).0 < group(Market::AldiSued.get_id()).0);
+        assert_eq!(group(42), (usize::MAX, UNCATEGORIZED_LABEL.to_string()));
+    }
+
+    #[test]
+    fn grouping_forms_read_the_grouping_and_the_store() {
+        let form = |grouping: &str, store_id: &str| ShoppingListGroupingForm { shopping_list_id: 1, grouping: grouping.to_string(), store_id: store_id.to_string() };
+
+        assert_eq!(form("market", "").get_grouping(), Some(Some(ShoppingListGrouping::Market)));
+        assert_eq!(form("", "").get_grouping(), Some(None));
+        assert_eq!(form("aisle", "").get_grouping(), None);
+        assert_eq!(form("", "7").get_store_id(), Some(Some(7)));
+        assert_eq!(form("", "").get_store_id(), Some(None));
+        assert_eq!(form("", "sieben").get_store_id(), None);
+        for grouping in ShoppingListGrouping::ALL {
+            assert_eq!(ShoppingListGrouping::from_str(grouping.get_param()), Some(grouping));
+        }
+    }
+}
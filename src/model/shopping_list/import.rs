@@ -0,0 +1,403 @@
+use std::collections::{BTreeMap, HashMap};
+use serde::Deserialize;
+use crate::model::{import::parse_csv_rows, product::Product};
+use super::quantity::{ItemQuantity, ItemUnit};
+
+// more lines are split into several imports, every line is matched against the catalogue
+pub const MAX_IMPORT_LINES: usize = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct ShoppingListImportPreviewForm {
+    pub shopping_list_id: i64,
+    // pasted lines or a csv with a "name" column, e.g. an export
+    pub content: String,
+}
+
+// an item to import as it was read, before it's matched
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShoppingListImportLine {
+    pub name: String,
+    pub quantity: Option<ItemQuantity>,
+    pub note: Option<String>,
+    // from the product_id column of an export
+    pub product_id: Option<String>,
+}
+
+// a line with the catalogue products it may be, the best match first
+#[derive(Debug)]
+pub struct ShoppingListImportMatch {
+    pub line: ShoppingListImportLine,
+    pub products: Vec<Product>,
+}
+
+// a csv if the first line has a "name" column, otherwise an item per line
+pub fn parse_shopping_list_import(content: &str) -> Result<Vec<ShoppingListImportLine>, String> {
+    let content = content.trim_start_matches('\u{feff}');
+    let header_line = content.lines().find(|line| !line.trim().is_empty()).unwrap_or_default();
+    let is_csv = header_line
+        .split([',', ';'])
+        .any(|column| column.trim().trim_matches('"').eq_ignore_ascii_case("name"));
+
+    let lines = if is_csv {
+        parse_csv_lines(content)?
+    } else {
+        content.lines().filter_map(parse_text_line).collect::<Vec<ShoppingListImportLine>>()
+    };
+
+    if lines.is_empty() {
+        return Err("Keine Einträge gefunden".to_string());
+    }
+    if lines.len() > MAX_IMPORT_LINES {
+        return Err(format!("Bitte importiere höchstens {} Einträge auf einmal", MAX_IMPORT_LINES));
+    }
+    Ok(lines)
+}
+
+// the columns of an export, only "name" is required
+fn parse_csv_lines(content: &str) -> Result<Vec<ShoppingListImportLine>, String> {
+    let rows = parse_csv_rows(content).map_err(|e| format!("Ungültige CSV in Zeile {}", e.line))?;
+
+    rows.iter().map(|(line, fields)| {
+        let get = |name: &str| fields.get(name).map(|value| value.trim()).filter(|value| !value.is_empty());
+        let name = get("name").ok_or(format!("Zeile {}: Name fehlt", line))?;
+        let quantity = match get("amount") {
+            Some(amount) => {
+                let unit = get("unit").unwrap_or(ItemUnit::Piece.get_param());
+                Some(parse_quantity(amount, unit).ok_or(format!("Zeile {}: Ungültige Menge \"{} {}\"", line, amount, unit))?)
+            },
+            None => None,
+        };
+
+        Ok(ShoppingListImportLine {
+            name: name.to_string(),
+            quantity,
+            note: get("note").map(str::to_string),
+            product_id: get("product_id").map(str::to_string),
+        })
+    }).collect()
+}
+
+// e.g. "2 kg Kartoffeln", "500g Hackfleisch", "- [ ] 3x Joghurt" or "Brötchen (vom Bäcker)", none for empty lines and headings
+fn parse_text_line(line: &str) -> Option<ShoppingListImportLine> {
+    let line = line.trim();
+    if line.starts_with('#') {
+        return None;
+    }
+    // the price of a markdown export
+    let line = line.split(" · ").next().unwrap_or_default();
+    let line = strip_list_marker(line);
+    let (line, note) = match line.strip_suffix(')').and_then(|line| line.rsplit_once(" (")) {
+        Some((line, note)) if !note.trim().is_empty() => (line.trim(), Some(note.trim().to_string())),
+        _ => (line, None),
+    };
+    if line.is_empty() {
+        return None;
+    }
+
+    let (quantity, name) = split_quantity(line);
+    Some(ShoppingListImportLine { name: name.to_string(), quantity, note, product_id: None })
+}
+
+// the bullets and checkboxes of markdown and other list apps, "- [x] ", "* ", "• " or "1. "
+fn strip_list_marker(line: &str) -> &str {
+    let mut line = line.trim();
+    for marker in ["- [ ]", "- [x]", "- [X]", "-", "*", "•", "+"] {
+        if let Some(rest) = line.strip_prefix(marker) {
+            line = rest.trim_start();
+            break;
+        }
+    }
+    let digit_count = line.chars().take_while(char::is_ascii_digit).count();
+    match line[digit_count..].strip_prefix(". ").or(line[digit_count..].strip_prefix(") ")) {
+        Some(rest) if digit_count > 0 => rest.trim_start(),
+        _ => line,
+    }
+}
+
+// the leading quantity of a line and the rest, the whole line is the name if there's none
+fn split_quantity(line: &str) -> (Option<ItemQuantity>, &str) {
+    let number_length = line.find(|char: char| !char.is_ascii_digit() && char != ',' && char != '.').unwrap_or(line.len());
+    if number_length == 0 {
+        return (None, line);
+    }
+    let (number, rest) = line.split_at(number_length);
+
+    // the unit may be attached, "500g", or separate, "500 g"
+    let (unit, name) = match rest.split_once(' ') {
+        Some((unit, name)) if !unit.is_empty() => (unit, name),
+        Some((_, rest)) => match rest.trim_start().split_once(' ') {
+            Some((unit, name)) if parse_unit(unit).is_some() => (unit, name),
+            _ => (ItemUnit::Piece.get_param(), rest),
+        },
+        None => return (None, line),
+    };
+
+    match parse_quantity(number, unit) {
+        Some(quantity) if !name.trim().is_empty() => (Some(quantity), name.trim()),
+        _ => (None, line),
+    }
+}
+
+// "1,5" kg are stored as 1500 g, there are no fractions of other units
+fn parse_quantity(amount: &str, unit: &str) -> Option<ItemQuantity> {
+    let amount = amount.replace(',', ".").parse::<f64>().ok()?;
+    let unit = parse_unit(unit)?;
+    let (amount, unit) = match unit {
+        _ if amount.fract() == 0f64 => (amount, unit),
+        ItemUnit::Kilogram => (amount * 1000f64, ItemUnit::Gram),
+        ItemUnit::Liter => (amount * 1000f64, ItemUnit::Milliliter),
+        _ => return None,
+    };
+    if amount > i32::MAX as f64 {
+        return None;
+    }
+    ItemQuantity::new(amount.round() as i32, unit)
+}
+
+// the params and labels of the units and what's commonly written instead
+fn parse_unit(unit: &str) -> Option<ItemUnit> {
+    let unit = unit.trim().trim_end_matches('.').to_lowercase();
+    match unit.as_str() {
+        "x" | "st" | "stück" => Some(ItemUnit::Piece),
+        "packung" | "packungen" | "pck" | "pack" => Some(ItemUnit::Pack),
+        "gr" | "gramm" => Some(ItemUnit::Gram),
+        "kilo" => Some(ItemUnit::Kilogram),
+        "liter" => Some(ItemUnit::Liter),
+        unit => ItemUnit::from_str(unit),
+    }
+}
+
+// what to do with a line of the review
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShoppingListImportChoice {
+    Product(String),
+    Text,
+    Skip,
+}
+
+impl ShoppingListImportChoice {
+    pub const TEXT_PARAM: &'static str = "text";
+    pub const PRODUCT_PREFIX: &'static str = "product:";
+
+    pub fn from_str(str: &str) -> Self {
+        match str {
+            ShoppingListImportChoice::TEXT_PARAM => ShoppingListImportChoice::Text,
+            str => match str.strip_prefix(ShoppingListImportChoice::PRODUCT_PREFIX) {
+                Some(product_id) if !product_id.is_empty() => ShoppingListImportChoice::Product(product_id.to_string()),
+                _ => ShoppingListImportChoice::Skip,
+            },
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ShoppingListImportItem {
+    pub line: ShoppingListImportLine,
+    pub choice: ShoppingListImportChoice,
+}
+
+// the reviewed lines, the fields of a line are suffixed with its index, e.g. "name.0" and "choice.0"
+#[derive(Debug)]
+pub struct ShoppingListImportForm {
+    pub shopping_list_id: i64,
+    pub items: Vec<ShoppingListImportItem>,
+}
+
+impl ShoppingListImportForm {
+    pub fn from_pairs(pairs: &[(String, String)]) -> Result<Self, String> {
+        let mut shopping_list_id = None;
+        let mut lines: BTreeMap<usize, HashMap<&str, &str>> = BTreeMap::new();
+        for (name, value) in pairs.iter() {
+            if name == "shopping_list_id" {
+                shopping_list_id = value.parse::<i64>().ok();
+                continue;
+            }
+            if let Some((field, index)) = name.split_once('.').and_then(|(field, index)| Some((field, index.parse::<usize>().ok()?))) {
+                lines.entry(index).or_default().insert(field, value.trim());
+            }
+        }
+        if lines.len() > MAX_IMPORT_LINES {
+            return Err(format!("Bitte importiere höchstens {} Einträge auf einmal", MAX_IMPORT_LINES));
+        }
+
+        let items = lines.values().map(|fields| {
+            let get = |name: &str| fields.get(name).copied().filter(|value| !value.is_empty());
+            let name = get("name").ok_or("Name fehlt".to_string())?;
+            let quantity = match get("amount") {
+                Some(amount) => {
+                    let unit = get("unit").unwrap_or(ItemUnit::Piece.get_param());
+                    Some(parse_quantity(amount, unit).ok_or(format!("Ungültige Menge für \"{}\"", name))?)
+                },
+                None => None,
+            };
+
+            Ok(ShoppingListImportItem {
+                line: ShoppingListImportLine {
+                    name: name.to_string(),
+                    quantity,
+                    note: get("note").map(str::to_string),
+                    product_id: None,
+                },
+                choice: ShoppingListImportChoice::from_str(get("choice").unwrap_or_default()),
+            })
+        }).collect::<Result<Vec<ShoppingListImportItem>, String>>()?;
+
+        Ok(Self {
+            shopping_list_id: shopping_list_id.ok_or("Kein Einkaufszettel ausgewählt".to_string())?,
+            items,
+        })
+    }
+}
+
+// how an import went, products already on the list are left as they are
+#[derive(Debug, Default)]
+pub struct ShoppingListImportResult {
+    pub added_count: usize,
+    pub skipped_count: usize,
+}
+
+impl ShoppingListImportResult {
+    // e.g. "3 Einträge importiert, 1 war bereits vorhanden"
+    pub fn format(&self) -> String {
+        let added = match self.added_count {
+            1 => "1 Eintrag importiert".to_string(),
+            count => format!("{} Einträge importiert", count),
+        };
+        match self.skipped_count {
+            0 => added,
+            1 => format!("{}, 1 war bereits vorhanden", added),
+            count => format!("{}, {} waren bereits vorhanden", added, count),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quantity(amount: i32, unit: ItemUnit) -> Option<ItemQuantity> {
+        Some(ItemQuantity { amount, unit })
+    }
+
+    fn pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect()
+    }
+
+    #[test]
+    fn parse_quantity_reads_german_units_and_decimal_commas() {
+        assert_eq!(parse_quantity("2", "Stück"), quantity(2, ItemUnit::Piece));
+        assert_eq!(parse_quantity("3", "x"), quantity(3, ItemUnit::Piece));
+        assert_eq!(parse_quantity("1", "Packung"), quantity(1, ItemUnit::Pack));
+        assert_eq!(parse_quantity("500", "Gramm"), quantity(500, ItemUnit::Gram));
+        assert_eq!(parse_quantity("2,5", "kg"), quantity(2500, ItemUnit::Gram));
+        assert_eq!(parse_quantity("1.5", "Liter"), quantity(1500, ItemUnit::Milliliter));
+        assert_eq!(parse_quantity("2", "kg"), quantity(2, ItemUnit::Kilogram));
+    }
+
+    #[test]
+    fn parse_quantity_rejects_fractions_of_pieces_and_unknown_units() {
+        assert_eq!(parse_quantity("1,5", "stk"), None);
+        assert_eq!(parse_quantity("0", "stk"), None);
+        assert_eq!(parse_quantity("2", "Dosen"), None);
+        assert_eq!(parse_quantity("zwei", "stk"), None);
+    }
+
+    #[test]
+    fn split_quantity_reads_attached_and_separate_units() {
+        assert_eq!(split_quantity("500g Hackfleisch"), (quantity(500, ItemUnit::Gram), "Hackfleisch"));
+        assert_eq!(split_quantity("2,5kg Kartoffeln"), (quantity(2500, ItemUnit::Gram), "Kartoffeln"));
+        assert_eq!(split_quantity("2 kg Kartoffeln"), (quantity(2, ItemUnit::Kilogram), "Kartoffeln"));
+        assert_eq!(split_quantity("3x Joghurt"), (quantity(3, ItemUnit::Piece), "Joghurt"));
+        assert_eq!(split_quantity("2 Stück Milch"), (quantity(2, ItemUnit::Piece), "Milch"));
+        assert_eq!(split_quantity("4 rote Äpfel"), (quantity(4, ItemUnit::Piece), "rote Äpfel"));
+    }
+
+    #[test]
+    fn split_quantity_keeps_the_line_without_a_quantity() {
+        assert_eq!(split_quantity("Brötchen"), (None, "Brötchen"));
+        assert_eq!(split_quantity("2kg"), (None, "2kg"));
+        assert_eq!(split_quantity("1,5 Äpfel"), (None, "1,5 Äpfel"));
+    }
+
+    #[test]
+    fn strip_list_marker_removes_bullets_checkboxes_and_numbers() {
+        assert_eq!(strip_list_marker("- [ ] Milch"), "Milch");
+        assert_eq!(strip_list_marker("- [x] Milch"), "Milch");
+        assert_eq!(strip_list_marker("* Milch"), "Milch");
+        assert_eq!(strip_list_marker("• Milch"), "Milch");
+        assert_eq!(strip_list_marker("1. Milch"), "Milch");
+        assert_eq!(strip_list_marker("12) Milch"), "Milch");
+        assert_eq!(strip_list_marker("500g Mehl"), "500g Mehl");
+    }
+
+    #[test]
+    fn parse_text_line_reads_quantity_name_and_note() {
+        assert_eq!(parse_text_line("- [ ] 3x Joghurt"), Some(ShoppingListImportLine {
+            name: "Joghurt".to_string(),
+            quantity: quantity(3, ItemUnit::Piece),
+            note: None,
+            product_id: None,
+        }));
+        assert_eq!(parse_text_line("Brötchen (vom Bäcker)"), Some(ShoppingListImportLine {
+            name: "Brötchen".to_string(),
+            quantity: None,
+            note: Some("vom Bäcker".to_string()),
+            product_id: None,
+        }));
+        // a line of the markdown export
+        assert_eq!(parse_text_line("- [x] 2 Stück Milch (fettarm) · 2.58 EUR"), Some(ShoppingListImportLine {
+            name: "Milch".to_string(),
+            quantity: quantity(2, ItemUnit::Piece),
+            note: Some("fettarm".to_string()),
+            product_id: None,
+        }));
+    }
+
+    #[test]
+    fn parse_text_line_skips_headings_and_empty_lines() {
+        assert_eq!(parse_text_line("# Wocheneinkauf"), None);
+        assert_eq!(parse_text_line("   "), None);
+        assert_eq!(parse_text_line("- [ ] "), None);
+    }
+
+    #[test]
+    fn parse_shopping_list_import_reads_csv_with_a_name_column() {
+        let lines = parse_shopping_list_import("name;amount;unit\nMehl;1,5;kg\nEier;10;\n").unwrap();
+        assert_eq!(lines.iter().map(|line| (line.name.as_str(), line.quantity)).collect::<Vec<_>>(), vec![
+            ("Mehl", quantity(1500, ItemUnit::Gram)),
+            ("Eier", quantity(10, ItemUnit::Piece)),
+        ]);
+        assert!(parse_shopping_list_import("name\n\"Milch\n").is_err());
+        assert!(parse_shopping_list_import("\n# Nur eine Überschrift\n").is_err());
+    }
+
+    #[test]
+    fn from_pairs_reads_the_indexed_fields_in_order() {
+        let form = ShoppingListImportForm::from_pairs(&pairs(&[
+            ("shopping_list_id", "3"),
+            ("name.10", "Brot"),
+            ("choice.10", "text"),
+            ("name.2", "Milch"),
+            ("amount.2", "2,5"),
+            ("unit.2", "l"),
+            ("choice.2", "product:aldi-1"),
+            ("name.4", "Butter"),
+            ("note.4", " "),
+        ])).unwrap();
+
+        assert_eq!(form.shopping_list_id, 3);
+        assert_eq!(form.items.iter().map(|item| item.line.name.as_str()).collect::<Vec<_>>(), vec!["Milch", "Butter", "Brot"]);
+        assert_eq!(form.items[0].line.quantity, quantity(2500, ItemUnit::Milliliter));
+        assert_eq!(form.items[0].choice, ShoppingListImportChoice::Product("aldi-1".to_string()));
+        assert_eq!(form.items[1].line.note, None);
+        assert_eq!(form.items[1].choice, ShoppingListImportChoice::Skip);
+        assert_eq!(form.items[2].choice, ShoppingListImportChoice::Text);
+    }
+
+    #[test]
+    fn from_pairs_rejects_missing_names_lists_and_invalid_amounts() {
+        assert!(ShoppingListImportForm::from_pairs(&pairs(&[("name.0", "Milch")])).is_err());
+        assert!(ShoppingListImportForm::from_pairs(&pairs(&[("shopping_list_id", "3"), ("amount.0", "2")])).is_err());
+        assert!(ShoppingListImportForm::from_pairs(&pairs(&[("shopping_list_id", "3"), ("name.0", "Eier"), ("amount.0", "1,5")])).is_err());
+    }
+}
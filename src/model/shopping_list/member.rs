@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Row, postgres::PgRow};
+
+// what a user may do with a list, the owner is the only one to rename, share or delete it
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum ShoppingListRole {
+    #[default]
+    Owner,
+    Editor,
+    Viewer,
+}
+
+impl ShoppingListRole {
+    // the role column of shopping_lists_access, members are either "viewer" or "editor"
+    pub fn from_str(str: &str) -> Option<Self> {
+        match str {
+            "owner" => Some(ShoppingListRole::Owner),
+            "editor" => Some(ShoppingListRole::Editor),
+            "viewer" => Some(ShoppingListRole::Viewer),
+            _ => None,
+        }
+    }
+
+    pub fn get_param(&self) -> &'static str {
+        match self {
+            ShoppingListRole::Owner => "owner",
+            ShoppingListRole::Editor => "editor",
+            ShoppingListRole::Viewer => "viewer",
+        }
+    }
+
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            ShoppingListRole::Owner => "Besitzer",
+            ShoppingListRole::Editor => "Darf bearbeiten",
+            ShoppingListRole::Viewer => "Darf ansehen",
+        }
+    }
+
+    pub fn is_owner(&self) -> bool {
+        *self == ShoppingListRole::Owner
+    }
+
+    pub fn can_edit(&self) -> bool {
+        matches!(self, ShoppingListRole::Owner | ShoppingListRole::Editor)
+    }
+}
+
+// a contact a list is shared with
+#[derive(Debug, Clone)]
+pub struct ShoppingListMember {
+    pub user_id: i64,
+    pub email: String,
+    pub role: ShoppingListRole,
+}
+
+impl<'r> FromRow<'r, PgRow> for ShoppingListMember {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let role = row.try_get::<String, _>("role")?;
+
+        Ok(ShoppingListMember {
+            user_id: row.try_get("user_id")?,
+            email: row.try_get("email")?,
+            role: ShoppingListRole::from_str(role.as_str()).unwrap_or(ShoppingListRole::Viewer),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ShareShoppingListForm {
+    pub shopping_list_id: i64,
+    pub user_id: i64,
+    pub role: String,
+}
+
+impl ShareShoppingListForm {
+    // a list has a single owner, it can only be shared for viewing or editing
+    pub fn get_role(&self) -> Option<ShoppingListRole> {
+        ShoppingListRole::from_str(self.role.as_str()).filter(|role| !role.is_owner())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ShoppingListMemberForm {
+    pub shopping_list_id: i64,
+    pub user_id: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROLES: [ShoppingListRole; 3] = [ShoppingListRole::Owner, ShoppingListRole::Editor, ShoppingListRole::Viewer];
+
+    #[test]
+    fn roles_round_trip_through_their_column() {
+        for role in ROLES {
+            assert_eq!(ShoppingListRole::from_str(role.get_param()), Some(role));
+        }
+        assert_eq!(ShoppingListRole::from_str("admin"), None);
+    }
+
+    #[test]
+    fn only_the_owner_and_editors_can_edit() {
+        let permissions = ROLES.map(|role| (role.is_owner(), role.can_edit()));
+        assert_eq!(permissions, [(true, true), (false, true), (false, false)]);
+    }
+
+    #[test]
+    fn lists_are_shared_for_viewing_or_editing_only() {
+        let form = |role: &str| ShareShoppingListForm { shopping_list_id: 1, user_id: 2, role: role.to_string() };
+        assert_eq!(form("viewer").get_role(), Some(ShoppingListRole::Viewer));
+        assert_eq!(form("editor").get_role(), Some(ShoppingListRole::Editor));
+        assert_eq!(form("owner").get_role(), None);
+        assert_eq!(form("").get_role(), None);
+    }
+}
@@ -1,15 +1,67 @@
 use serde::{Deserialize, Serialize};
-use chrono::{DateTime, FixedOffset};
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc, Weekday};
 use sqlx::{FromRow, Row, postgres::PgRow};
+use grouping::ShoppingListGrouping;
+use member::ShoppingListRole;
+use quantity::{ItemQuantity, ItemUnit};
+use template::{from_iso_weekday, get_weekday_label};
+use text_item::ShoppingListTextItem;
+use crate::model::{category::CategoryTree, product::Product, store::aisle::StoreAisles};
 
-#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub mod change;
+pub mod cost;
+pub mod event;
+pub mod export;
+pub mod grouping;
+pub mod import;
+pub mod member;
+pub mod order;
+pub mod print;
+pub mod quantity;
+pub mod template;
+pub mod text_item;
+pub mod trip;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ShoppingList {
     pub id: i64,
     pub created_at: DateTime<FixedOffset>,
     pub name: String,
-    #[sqlx(default)]
     pub user_id: i64,
-    pub emoji_presentation: Option<String>
+    pub emoji_presentation: Option<String>,
+    // the role of the user the list was loaded for
+    pub role: ShoppingListRole,
+    // set for lists shared with the user
+    pub owner_email: Option<String>,
+    pub unread_notification_count: i64,
+    pub is_template: bool,
+    // templates only, a copy is created every week on this day
+    pub recurring_weekday: Option<Weekday>,
+    // how the items are put under headings while shopping, none keeps the order of the list
+    pub item_grouping: Option<ShoppingListGrouping>,
+    // the store the list is shopped at, its categories follow the user's aisles of it
+    pub store_id: Option<i64>,
+}
+
+impl<'r> FromRow<'r, PgRow> for ShoppingList {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let role = row.try_get::<String, _>("role").ok();
+
+        Ok(ShoppingList {
+            id: row.try_get("id")?,
+            created_at: row.try_get("created_at")?,
+            name: row.try_get("name")?,
+            user_id: row.try_get("user_id").unwrap_or_default(),
+            emoji_presentation: row.try_get("emoji_presentation")?,
+            role: role.as_deref().and_then(ShoppingListRole::from_str).unwrap_or_default(),
+            owner_email: row.try_get("owner_email").ok().flatten(),
+            unread_notification_count: row.try_get("unread_notification_count").unwrap_or_default(),
+            is_template: row.try_get("is_template").unwrap_or_default(),
+            recurring_weekday: row.try_get::<Option<i16>, _>("recurring_weekday").ok().flatten().and_then(from_iso_weekday),
+            item_grouping: row.try_get::<Option<String>, _>("item_grouping").ok().flatten().as_deref().and_then(ShoppingListGrouping::from_str),
+            store_id: row.try_get("store_id").ok().flatten(),
+        })
+    }
 }
 
 impl ShoppingList {
@@ -20,6 +72,13 @@ impl ShoppingList {
             name: String::default(),
             user_id: 0,
             emoji_presentation: None,
+            role: ShoppingListRole::Owner,
+            owner_email: None,
+            unread_notification_count: 0,
+            is_template: false,
+            recurring_weekday: None,
+            item_grouping: None,
+            store_id: None,
         }
     }
 
@@ -34,6 +93,39 @@ impl ShoppingList {
     pub fn get_name(&self) -> &str {
         self.name.as_str()
     }
+
+    pub fn get_href(&self) -> String {
+        format!("/einkaufszettel/{}", self.id)
+    }
+
+    pub fn get_shopping_mode_href(&self) -> String {
+        format!("/einkaufszettel/{}/einkaufen", self.id)
+    }
+
+    pub fn get_print_href(&self) -> String {
+        format!("/einkaufszettel/{}/drucken", self.id)
+    }
+
+    pub fn is_owner(&self) -> bool {
+        self.role.is_owner()
+    }
+
+    pub fn can_edit(&self) -> bool {
+        self.role.can_edit()
+    }
+
+    // a template keeps its name, a list created from one gets the day it's for
+    pub fn get_copy_name(&self, as_template: bool, created_on: NaiveDate) -> String {
+        match (self.is_template, as_template) {
+            (true, false) => format!("{} {}", self.name, created_on.format("%d.%m.")),
+            (false, true) => self.name.to_owned(),
+            _ => format!("{} (Kopie)", self.name),
+        }
+    }
+
+    pub fn get_recurrence_label(&self) -> Option<String> {
+        self.recurring_weekday.map(|weekday| format!("Jeden {}", get_weekday_label(&weekday)))
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -47,6 +139,39 @@ pub struct ShoppingListUpdateForm {
 pub struct AddShoppingListItemForm {
     pub shopping_list_id: Option<i64>,
     pub product_id: String,
+    // only used when the product is added, defaults to a single piece
+    pub amount: Option<i32>,
+    pub unit: Option<String>,
+}
+
+impl AddShoppingListItemForm {
+    pub fn get_quantity(&self) -> Option<ItemQuantity> {
+        let unit = match self.unit.as_deref() {
+            Some(unit) => ItemUnit::from_str(unit)?,
+            None => ItemUnit::Piece,
+        };
+        ItemQuantity::new(self.amount.unwrap_or(1), unit)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ShoppingListItemForm {
+    pub shopping_list_id: i64,
+    pub product_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ShoppingListItemQuantityForm {
+    pub shopping_list_id: i64,
+    pub product_id: String,
+    pub amount: i32,
+    pub unit: String,
+}
+
+impl ShoppingListItemQuantityForm {
+    pub fn get_quantity(&self) -> Option<ItemQuantity> {
+        ItemQuantity::new(self.amount, ItemUnit::from_str(self.unit.as_str())?)
+    }
 }
 
 #[derive(Debug)]
@@ -75,12 +200,79 @@ impl<'r> FromRow<'r, PgRow> for ToggleShoppingListItemOp {
 #[derive(Debug)]
 pub struct ShoppingListItem {
     pub product_id: String,
+    // when it was put into the cart while shopping
+    pub checked_at: Option<DateTime<Utc>>,
 }
 
 impl<'r> FromRow<'r, PgRow> for ShoppingListItem {
     fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
         Ok(ShoppingListItem {
-            product_id: row.try_get("product_id")?
+            product_id: row.try_get("product_id")?,
+            checked_at: row.try_get("checked_at").ok().flatten(),
         })
     }
-}
\ No newline at end of file
+}
+
+// everything on a list while shopping, the unchecked items first
+#[derive(Debug, Default)]
+pub struct ShoppingChecklist {
+    pub products: Vec<Product>,
+    pub text_items: Vec<ShoppingListTextItem>,
+    // the user's aisles of the store the list is shopped at
+    pub aisles: StoreAisles,
+    // the heading of each product once the list is grouped, in the order of the products
+    pub group_names: Vec<String>,
+}
+
+impl ShoppingChecklist {
+    pub fn len(&self) -> usize {
+        self.products.len() + self.text_items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_grouped(&self) -> bool {
+        !self.group_names.is_empty()
+    }
+
+    // puts the unchecked products under the headings of the grouping, a group keeps the order of the list
+    pub fn group(&mut self, grouping: &ShoppingListGrouping, categories: &CategoryTree) {
+        let mut products = std::mem::take(&mut self.products).into_iter()
+            .map(|product| {
+                let (position, name) = grouping.get_group(&product, categories, &self.aisles);
+                // the checked products stay below, in the order they were checked in
+                let key = match product.checked_at {
+                    Some(_) => (true, 0),
+                    None => (false, position),
+                };
+                (key, name, product)
+            })
+            .collect::<Vec<((bool, usize), String, Product)>>();
+        products.sort_by_key(|(key, _, _)| *key);
+
+        (self.group_names, self.products) = products.into_iter().map(|(_, name, product)| (name, product)).unzip();
+    }
+
+    // the heading above the first unchecked product of each group
+    pub fn get_group_heading(&self, index: &usize) -> Option<&str> {
+        if self.products.get(*index)?.checked_at.is_some() {
+            return None;
+        }
+        let name = self.group_names.get(*index)?;
+        match index.checked_sub(1).and_then(|previous| self.group_names.get(previous)) {
+            Some(previous_name) if previous_name == name => None,
+            _ => Some(name.as_str()),
+        }
+    }
+
+    pub fn has_unchecked_text_items(&self) -> bool {
+        self.text_items.iter().any(|text_item| text_item.checked_at.is_none())
+    }
+
+    pub fn get_checked_count(&self) -> usize {
+        self.products.iter().filter(|product| product.checked_at.is_some()).count()
+            + self.text_items.iter().filter(|text_item| text_item.checked_at.is_some()).count()
+    }
+}
@@ -0,0 +1,83 @@
+use std::{collections::HashSet, hash::Hash};
+
+// the items of a page of a list in the order they were dropped in, the products or the free text items
+#[derive(Debug)]
+pub struct ShoppingListItemOrderForm {
+    pub shopping_list_id: i64,
+    pub product_ids: Vec<String>,
+    pub text_item_ids: Vec<i64>,
+}
+
+impl ShoppingListItemOrderForm {
+    // the ids repeat, which a struct can't be deserialized from. none without a list, for an invalid id
+    // and for forms the positions couldn't be told from: both kinds of items at once or an item twice
+    pub fn from_pairs(pairs: &[(String, String)]) -> Option<Self> {
+        let mut shopping_list_id = None;
+        let mut product_ids = vec![];
+        let mut text_item_ids = vec![];
+        for (key, value) in pairs.iter() {
+            match key.as_str() {
+                "shopping_list_id" => shopping_list_id = Some(value.parse::<i64>().ok()?),
+                "product_id" => product_ids.push(value.to_owned()),
+                "text_item_id" => text_item_ids.push(value.parse::<i64>().ok()?),
+                _ => {},
+            }
+        }
+
+        if !product_ids.is_empty() && !text_item_ids.is_empty() {
+            return None;
+        }
+        if has_duplicates(&product_ids) || has_duplicates(&text_item_ids) {
+            return None;
+        }
+
+        Some(Self { shopping_list_id: shopping_list_id?, product_ids, text_item_ids })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.product_ids.is_empty() && self.text_item_ids.is_empty()
+    }
+}
+
+fn has_duplicates<T: Eq + Hash>(ids: &[T]) -> bool {
+    let mut seen = HashSet::new();
+    !ids.iter().all(|id| seen.insert(id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect()
+    }
+
+    #[test]
+    fn items_are_read_in_the_order_they_were_dropped() {
+        let form = ShoppingListItemOrderForm::from_pairs(&pairs(&[
+            ("shopping_list_id", "7"), ("product_id", "lidl-2"), ("product_id", "lidl-1"), ("unknown", "x"),
+        ])).unwrap();
+        assert_eq!((form.shopping_list_id, form.product_ids, form.text_item_ids), (7, vec!["lidl-2".to_string(), "lidl-1".to_string()], vec![]));
+
+        let form = ShoppingListItemOrderForm::from_pairs(&pairs(&[("text_item_id", "3"), ("text_item_id", "1"), ("shopping_list_id", "7")])).unwrap();
+        assert_eq!((form.product_ids.len(), form.text_item_ids), (0, vec![3, 1]));
+
+        assert!(ShoppingListItemOrderForm::from_pairs(&pairs(&[("shopping_list_id", "7")])).unwrap().is_empty());
+    }
+
+    #[test]
+    fn forms_without_clear_positions_are_rejected() {
+        for invalid in [
+            // both kinds, each numbered from 1
+            vec![("shopping_list_id", "7"), ("product_id", "lidl-1"), ("text_item_id", "1")],
+            // an item at two positions
+            vec![("shopping_list_id", "7"), ("product_id", "lidl-1"), ("product_id", "lidl-2"), ("product_id", "lidl-1")],
+            vec![("shopping_list_id", "7"), ("text_item_id", "1"), ("text_item_id", "1")],
+            vec![("shopping_list_id", "7"), ("text_item_id", "eins")],
+            vec![("shopping_list_id", "sieben"), ("product_id", "lidl-1")],
+            vec![("product_id", "lidl-1")],
+        ] {
+            assert!(ShoppingListItemOrderForm::from_pairs(&pairs(&invalid)).is_none(), "{:?}", invalid);
+        }
+    }
+}
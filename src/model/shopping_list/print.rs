@@ -0,0 +1,139 @@
+use chrono::{DateTime, Local};
+use serde::Deserialize;
+use crate::model::category::CategoryTree;
+use super::{
+    cost::ShoppingListCost,
+    export::get_file_stem,
+    grouping::ShoppingListGrouping,
+    ShoppingChecklist,
+    ShoppingList,
+};
+
+const TEXT_ITEMS_LABEL: &str = "Weitere Einträge";
+
+#[derive(Debug, Deserialize)]
+pub struct ShoppingListPrintParams {
+    // defaults to the category
+    pub group: Option<String>,
+}
+
+impl ShoppingListPrintParams {
+    // none for an unknown grouping
+    pub fn get_grouping(&self) -> Option<ShoppingListGrouping> {
+        match self.group.as_deref() {
+            Some(group) => ShoppingListGrouping::from_str(group),
+            None => Some(ShoppingListGrouping::default()),
+        }
+    }
+}
+
+// a list laid out for paper, rendered both as a page to print and as a pdf
+#[derive(Debug)]
+pub struct ShoppingListPrint {
+    pub shopping_list_id: i64,
+    pub name: String,
+    pub grouping: ShoppingListGrouping,
+    pub printed_at: DateTime<Local>,
+    pub groups: Vec<ShoppingListPrintGroup>,
+    pub cost: ShoppingListCost,
+}
+
+#[derive(Debug)]
+pub struct ShoppingListPrintGroup {
+    pub name: String,
+    pub items: Vec<ShoppingListPrintItem>,
+}
+
+#[derive(Debug)]
+pub struct ShoppingListPrintItem {
+    pub name: String,
+    // e.g. "2 Stück"
+    pub quantity_label: Option<String>,
+    pub note: Option<String>,
+    // the estimated line total, free text items and products without a price have none
+    pub price_label: Option<String>,
+    pub checked: bool,
+}
+
+impl ShoppingListPrint {
+    pub fn new(
+        shopping_list: &ShoppingList,
+        checklist: &ShoppingChecklist,
+        categories: &CategoryTree,
+        grouping: ShoppingListGrouping,
+        cost: ShoppingListCost,
+        printed_at: DateTime<Local>,
+    ) -> Self {
+        // ordered by the position of the group, along the aisles of the list's store. the items keep the order of the checklist
+        let mut groups: Vec<(usize, ShoppingListPrintGroup)> = vec![];
+        for product in checklist.products.iter() {
+            let (position, name) = grouping.get_group(product, categories, &checklist.aisles);
+            let index = match groups.binary_search_by_key(&position, |(position, _)| *position) {
+                Ok(index) => index,
+                Err(index) => {
+                    groups.insert(index, (position, ShoppingListPrintGroup { name, items: vec![] }));
+                    index
+                },
+            };
+            groups[index].1.items.push(ShoppingListPrintItem {
+                name: product.name.to_owned(),
+                quantity_label: product.quantity.map(|quantity| quantity.format()),
+                note: None,
+                price_label: product.get_line_total()
+                    .filter(|line_total| line_total.price.is_some())
+                    .map(|line_total| line_total.format()),
+                checked: product.checked_at.is_some(),
+            });
+        }
+
+        let mut groups = groups.into_iter().map(|(_, group)| group).collect::<Vec<ShoppingListPrintGroup>>();
+        if !checklist.text_items.is_empty() {
+            groups.push(ShoppingListPrintGroup {
+                name: TEXT_ITEMS_LABEL.to_string(),
+                items: checklist.text_items.iter().map(|text_item| ShoppingListPrintItem {
+                    name: text_item.name.to_owned(),
+                    quantity_label: text_item.quantity.map(|quantity| quantity.format()),
+                    note: text_item.note.to_owned(),
+                    price_label: None,
+                    checked: text_item.checked_at.is_some(),
+                }).collect(),
+            });
+        }
+
+        Self {
+            shopping_list_id: shopping_list.id,
+            name: shopping_list.name.to_owned(),
+            grouping,
+            printed_at,
+            groups,
+            cost,
+        }
+    }
+
+    pub fn get_item_count(&self) -> usize {
+        self.groups.iter().map(|group| group.items.len()).sum()
+    }
+
+    // e.g. "wocheneinkauf.pdf"
+    pub fn get_pdf_file_name(&self) -> String {
+        format!("{}.pdf", get_file_stem(self.name.as_str()))
+    }
+
+    pub fn get_print_href(&self, grouping: &ShoppingListGrouping) -> String {
+        format!("/einkaufszettel/{}/drucken?group={}", self.shopping_list_id, grouping.get_param())
+    }
+
+    pub fn get_pdf_href(&self) -> String {
+        format!("/shopping_list/pdf/{}?group={}", self.shopping_list_id, self.grouping.get_param())
+    }
+
+    // e.g. "Gedruckt am 19.10.2026 · Preise von heute"
+    pub fn format_subtitle(&self) -> String {
+        let printed_on = format!("Gedruckt am {}", self.printed_at.format("%d.%m.%Y"));
+        if self.cost.is_empty() {
+            printed_on
+        } else {
+            format!("{} · {}", printed_on, self.cost.format_price_age())
+        }
+    }
+}
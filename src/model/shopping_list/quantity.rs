@@ -0,0 +1,251 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Row, postgres::PgRow};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ItemUnit {
+    Piece,
+    Pack,
+    Gram,
+    Kilogram,
+    Milliliter,
+    Liter,
+}
+
+impl ItemUnit {
+    pub const ALL: [ItemUnit; 6] = [
+        ItemUnit::Piece,
+        ItemUnit::Pack,
+        ItemUnit::Gram,
+        ItemUnit::Kilogram,
+        ItemUnit::Milliliter,
+        ItemUnit::Liter,
+    ];
+
+    pub fn from_str(str: &str) -> Option<Self> {
+        ItemUnit::ALL.into_iter().find(|unit| unit.get_param() == str)
+    }
+
+    // the amount_unit column, pieces are stored as NULL
+    fn from_column(column: Option<&str>) -> Self {
+        column.and_then(ItemUnit::from_str).unwrap_or(ItemUnit::Piece)
+    }
+
+    pub fn get_column(&self) -> Option<&'static str> {
+        match self {
+            ItemUnit::Piece => None,
+            _ => Some(self.get_param()),
+        }
+    }
+
+    pub fn get_param(&self) -> &'static str {
+        match self {
+            ItemUnit::Piece => "stk",
+            ItemUnit::Pack => "pkg",
+            ItemUnit::Gram => "g",
+            ItemUnit::Kilogram => "kg",
+            ItemUnit::Milliliter => "ml",
+            ItemUnit::Liter => "l",
+        }
+    }
+
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            ItemUnit::Piece => "Stück",
+            ItemUnit::Pack => "Packung",
+            _ => self.get_param(),
+        }
+    }
+
+    // what the + and - buttons add or take away
+    pub fn get_step(&self) -> i32 {
+        match self {
+            ItemUnit::Gram | ItemUnit::Milliliter => 100,
+            _ => 1,
+        }
+    }
+
+    // the factor to the base unit of a shelf label's unit price, "kg" or "l"
+    fn get_base_factor(&self, base_unit: &str) -> Option<f64> {
+        match (self, base_unit.to_lowercase().as_str()) {
+            (ItemUnit::Gram, "kg") | (ItemUnit::Milliliter, "l") => Some(0.001),
+            (ItemUnit::Kilogram, "kg") | (ItemUnit::Liter, "l") => Some(1f64),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QuantityStep {
+    Increment,
+    Decrement,
+}
+
+// how much of a product is on a shopping list
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ItemQuantity {
+    pub amount: i32,
+    pub unit: ItemUnit,
+}
+
+impl Default for ItemQuantity {
+    fn default() -> Self {
+        Self { amount: 1, unit: ItemUnit::Piece }
+    }
+}
+
+impl<'r> FromRow<'r, PgRow> for ItemQuantity {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let unit = row.try_get::<Option<String>, _>("amount_unit")?;
+
+        Ok(ItemQuantity {
+            amount: row.try_get("amount")?,
+            unit: ItemUnit::from_column(unit.as_deref()),
+        })
+    }
+}
+
+impl ItemQuantity {
+    pub fn new(amount: i32, unit: ItemUnit) -> Option<Self> {
+        (amount > 0).then_some(Self { amount, unit })
+    }
+
+    // never steps below a single step, removing the item is what the toggle is for
+    pub fn step(&self, step: QuantityStep) -> Self {
+        let amount = match step {
+            QuantityStep::Increment => self.amount.saturating_add(self.unit.get_step()),
+            QuantityStep::Decrement if self.amount > self.unit.get_step() => (self.amount - self.unit.get_step()).max(self.unit.get_step()),
+            QuantityStep::Decrement => self.amount,
+        };
+        Self { amount, unit: self.unit }
+    }
+
+    pub fn is_minimum(&self) -> bool {
+        self.amount <= self.unit.get_step()
+    }
+
+    pub fn is_single_piece(&self) -> bool {
+        *self == Self::default()
+    }
+
+    // e.g. "2 Stück", "3 Packungen" or "500 g"
+    pub fn format(&self) -> String {
+        match (self.unit, self.amount) {
+            (ItemUnit::Pack, 1) => format!("{} Packung", self.amount),
+            (ItemUnit::Pack, _) => format!("{} Packungen", self.amount),
+            (unit, _) => format!("{} {}", self.amount, unit.get_label()),
+        }
+    }
+
+    // the total in cents for a product of `price` per piece, weights and volumes use the unit price if its unit
+    // matches and otherwise count as a single piece, there's no telling how many packs 500 g are
+    pub fn get_total(&self, price: i32, unit_price: Option<(i32, &str)>) -> i32 {
+        match self.unit {
+            ItemUnit::Piece | ItemUnit::Pack => price.saturating_mul(self.amount),
+            unit => unit_price
+                .and_then(|(unit_price, base_unit)| unit.get_base_factor(base_unit)
+                    .map(|factor| (unit_price as f64 * self.amount as f64 * factor).round() as i32))
+                .unwrap_or(price),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::{product::test_product, shopping_list::{AddShoppingListItemForm, ShoppingListItemQuantityForm}};
+    use super::*;
+
+    fn quantity(amount: i32, unit: ItemUnit) -> ItemQuantity {
+        ItemQuantity::new(amount, unit).unwrap()
+    }
+
+    #[test]
+    fn units_round_trip_through_their_params_and_columns() {
+        for unit in ItemUnit::ALL {
+            assert_eq!(ItemUnit::from_str(unit.get_param()), Some(unit));
+            assert_eq!(ItemUnit::from_column(unit.get_column()), unit);
+        }
+        assert_eq!(ItemUnit::Piece.get_column(), None);
+        assert_eq!(ItemUnit::from_str("Stück"), None);
+        assert_eq!(ItemUnit::from_column(Some("unbekannt")), ItemUnit::Piece);
+    }
+
+    #[test]
+    fn steps_never_go_below_a_single_step() {
+        assert_eq!(quantity(1, ItemUnit::Piece).step(QuantityStep::Increment), quantity(2, ItemUnit::Piece));
+        assert_eq!(quantity(2, ItemUnit::Piece).step(QuantityStep::Decrement), quantity(1, ItemUnit::Piece));
+        assert_eq!(quantity(1, ItemUnit::Piece).step(QuantityStep::Decrement), quantity(1, ItemUnit::Piece));
+        assert_eq!(quantity(500, ItemUnit::Gram).step(QuantityStep::Increment), quantity(600, ItemUnit::Gram));
+        assert_eq!(quantity(150, ItemUnit::Gram).step(QuantityStep::Decrement), quantity(100, ItemUnit::Gram));
+        assert_eq!(quantity(50, ItemUnit::Gram).step(QuantityStep::Decrement), quantity(50, ItemUnit::Gram));
+        assert_eq!(quantity(200, ItemUnit::Milliliter).step(QuantityStep::Decrement), quantity(100, ItemUnit::Milliliter));
+        assert_eq!(quantity(i32::MAX, ItemUnit::Piece).step(QuantityStep::Increment).amount, i32::MAX);
+
+        assert!(quantity(1, ItemUnit::Kilogram).is_minimum());
+        assert!(quantity(100, ItemUnit::Gram).is_minimum());
+        assert!(!quantity(200, ItemUnit::Gram).is_minimum());
+    }
+
+    #[test]
+    fn quantities_must_be_positive() {
+        assert_eq!(ItemQuantity::new(0, ItemUnit::Piece), None);
+        assert_eq!(ItemQuantity::new(-1, ItemUnit::Gram), None);
+        assert!(ItemQuantity::default().is_single_piece());
+        assert!(!quantity(1, ItemUnit::Pack).is_single_piece());
+    }
+
+    #[test]
+    fn quantities_are_formatted_with_their_unit() {
+        assert_eq!(quantity(2, ItemUnit::Piece).format(), "2 Stück");
+        assert_eq!(quantity(1, ItemUnit::Pack).format(), "1 Packung");
+        assert_eq!(quantity(3, ItemUnit::Pack).format(), "3 Packungen");
+        assert_eq!(quantity(500, ItemUnit::Gram).format(), "500 g");
+        assert_eq!(quantity(2, ItemUnit::Liter).format(), "2 l");
+    }
+
+    #[test]
+    fn totals_use_the_unit_price_for_weights_and_volumes() {
+        assert_eq!(quantity(3, ItemUnit::Piece).get_total(129, None), 387);
+        assert_eq!(quantity(2, ItemUnit::Pack).get_total(129, Some((1290, "kg"))), 258);
+        // 500 g at 8,90 €/kg
+        assert_eq!(quantity(500, ItemUnit::Gram).get_total(299, Some((890, "kg"))), 445);
+        assert_eq!(quantity(2, ItemUnit::Kilogram).get_total(299, Some((890, "KG"))), 1780);
+        assert_eq!(quantity(750, ItemUnit::Milliliter).get_total(99, Some((133, "l"))), 100);
+        // no unit price or one per another unit counts as a single piece
+        assert_eq!(quantity(500, ItemUnit::Gram).get_total(299, None), 299);
+        assert_eq!(quantity(500, ItemUnit::Gram).get_total(299, Some((133, "l"))), 299);
+        assert_eq!(quantity(i32::MAX, ItemUnit::Piece).get_total(2, None), i32::MAX);
+    }
+
+    #[test]
+    fn line_totals_multiply_the_price_by_the_quantity() {
+        let mut product = test_product("lidl-1", 1, Some(129), "EUR");
+        assert_eq!(product.get_line_total().and_then(|line_total| line_total.price), Some(129));
+
+        product.quantity = Some(quantity(3, ItemUnit::Piece));
+        let line_total = product.get_line_total().unwrap();
+        assert_eq!((line_total.price, line_total.currency.as_str()), (Some(387), "EUR"));
+
+        product.quantity = Some(quantity(500, ItemUnit::Gram));
+        (product.unit_price, product.unit) = (Some(890), Some("kg".to_string()));
+        assert_eq!(product.get_line_total().and_then(|line_total| line_total.price), Some(445));
+
+        assert!(test_product("lidl-2", 1, None, "EUR").get_line_total().is_none());
+    }
+
+    #[test]
+    fn forms_default_to_a_single_piece_and_reject_unknown_units() {
+        let form = |amount: Option<i32>, unit: Option<&str>| AddShoppingListItemForm {
+            shopping_list_id: None,
+            product_id: "lidl-1".to_string(),
+            amount,
+            unit: unit.map(str::to_string),
+        };
+        assert_eq!(form(None, None).get_quantity(), Some(ItemQuantity::default()));
+        assert_eq!(form(Some(500), Some("g")).get_quantity(), Some(quantity(500, ItemUnit::Gram)));
+        assert_eq!(form(Some(0), None).get_quantity(), None);
+        assert_eq!(form(Some(1), Some("Zentner")).get_quantity(), None);
+
+        let form = ShoppingListItemQuantityForm { shopping_list_id: 1, product_id: "lidl-1".to_string(), amount: 2, unit: "pkg".to_string() };
+        assert_eq!(form.get_quantity(), Some(quantity(2, ItemUnit::Pack)));
+    }
+}
@@ -0,0 +1,110 @@
+use chrono::Weekday;
+use serde::Deserialize;
+
+pub const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+// the recurring_weekday column, 1 is monday like postgres' ISODOW
+pub fn from_iso_weekday(iso_weekday: i16) -> Option<Weekday> {
+    usize::try_from(iso_weekday).ok()?.checked_sub(1).and_then(|index| WEEKDAYS.get(index)).copied()
+}
+
+pub fn get_iso_weekday(weekday: &Weekday) -> i16 {
+    weekday.number_from_monday() as i16
+}
+
+pub fn get_weekday_label(weekday: &Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "Montag",
+        Weekday::Tue => "Dienstag",
+        Weekday::Wed => "Mittwoch",
+        Weekday::Thu => "Donnerstag",
+        Weekday::Fri => "Freitag",
+        Weekday::Sat => "Samstag",
+        Weekday::Sun => "Sonntag",
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CopyShoppingListForm {
+    pub shopping_list_id: i64,
+    // saves the copy as a template instead of a list to shop with
+    #[serde(default)]
+    pub as_template: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ShoppingListRecurrenceForm {
+    pub shopping_list_id: i64,
+    // an ISO weekday, empty to stop the recurrence
+    pub weekday: String,
+}
+
+impl ShoppingListRecurrenceForm {
+    // none for an invalid weekday
+    pub fn get_weekday(&self) -> Option<Option<Weekday>> {
+        if self.weekday.is_empty() {
+            return Some(None);
+        }
+        self.weekday.parse::<i16>().ok().and_then(from_iso_weekday).map(Some)
+    }
+}
+
+// a list created from a recurring template
+#[derive(Debug)]
+pub struct RecurringShoppingList {
+    pub id: i64,
+    pub template_id: i64,
+    pub name: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use crate::model::shopping_list::ShoppingList;
+    use super::*;
+
+    #[test]
+    fn iso_weekdays_start_on_monday() {
+        for weekday in WEEKDAYS {
+            assert_eq!(from_iso_weekday(get_iso_weekday(&weekday)), Some(weekday));
+        }
+        assert_eq!(from_iso_weekday(1), Some(Weekday::Mon));
+        assert_eq!(from_iso_weekday(7), Some(Weekday::Sun));
+        for iso_weekday in [0, 8, -1, i16::MIN, i16::MAX] {
+            assert_eq!(from_iso_weekday(iso_weekday), None, "{}", iso_weekday);
+        }
+    }
+
+    #[test]
+    fn recurrence_forms_set_or_clear_the_weekday() {
+        let form = |weekday: &str| ShoppingListRecurrenceForm { shopping_list_id: 1, weekday: weekday.to_string() };
+        assert_eq!(form("3").get_weekday(), Some(Some(Weekday::Wed)));
+        assert_eq!(form("").get_weekday(), Some(None));
+        for weekday in ["0", "8", "-32768", "mittwoch"] {
+            assert_eq!(form(weekday).get_weekday(), None, "{}", weekday);
+        }
+    }
+
+    #[test]
+    fn copies_are_named_after_what_they_are() {
+        let created_on = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+        let list = ShoppingList { name: "Wocheneinkauf".to_string(), ..ShoppingList::default() };
+        let template = ShoppingList { is_template: true, recurring_weekday: Some(Weekday::Mon), ..list.clone() };
+
+        assert_eq!(template.get_copy_name(false, created_on), "Wocheneinkauf 19.10.");
+        assert_eq!(list.get_copy_name(true, created_on), "Wocheneinkauf");
+        assert_eq!(list.get_copy_name(false, created_on), "Wocheneinkauf (Kopie)");
+        assert_eq!(template.get_copy_name(true, created_on), "Wocheneinkauf (Kopie)");
+
+        assert_eq!(template.get_recurrence_label().as_deref(), Some("Jeden Montag"));
+        assert_eq!(list.get_recurrence_label(), None);
+    }
+}
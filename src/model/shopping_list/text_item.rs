@@ -0,0 +1,76 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Row, postgres::PgRow};
+use super::quantity::{ItemQuantity, ItemUnit};
+
+// an item that isn't in the catalogue (yet), e.g. "Brötchen vom Bäcker"
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ShoppingListTextItem {
+    pub id: i64,
+    pub added_at: DateTime<Utc>,
+    pub name: String,
+    pub note: Option<String>,
+    // free text items don't need an amount
+    pub quantity: Option<ItemQuantity>,
+    pub checked_at: Option<DateTime<Utc>>,
+}
+
+impl<'r> FromRow<'r, PgRow> for ShoppingListTextItem {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        Ok(ShoppingListTextItem {
+            id: row.try_get("id")?,
+            added_at: row.try_get("added_at")?,
+            name: row.try_get("name")?,
+            note: row.try_get("note")?,
+            quantity: ItemQuantity::from_row(row).ok(),
+            checked_at: row.try_get("checked_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddShoppingListTextItemForm {
+    pub shopping_list_id: i64,
+    pub name: String,
+    // empty when left blank
+    pub amount: Option<String>,
+    pub unit: Option<String>,
+    pub note: Option<String>,
+}
+
+impl AddShoppingListTextItemForm {
+    pub fn get_name(&self) -> Option<&str> {
+        Some(self.name.trim()).filter(|name| !name.is_empty())
+    }
+
+    pub fn get_note(&self) -> Option<&str> {
+        self.note.as_deref().map(str::trim).filter(|note| !note.is_empty())
+    }
+
+    // none without an amount, an error for an invalid one
+    pub fn get_quantity(&self) -> Result<Option<ItemQuantity>, String> {
+        let amount = match self.amount.as_deref().map(str::trim) {
+            Some("") | None => return Ok(None),
+            Some(amount) => amount.parse::<i32>().map_err(|_| format!("Ungültige Menge \"{amount}\""))?,
+        };
+        let unit = match self.unit.as_deref() {
+            Some(unit) => ItemUnit::from_str(unit).ok_or(format!("Unbekannte Einheit \"{unit}\""))?,
+            None => ItemUnit::Piece,
+        };
+
+        ItemQuantity::new(amount, unit).map(Some).ok_or(format!("Ungültige Menge \"{amount}\""))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ShoppingListTextItemForm {
+    pub shopping_list_id: i64,
+    pub item_id: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LinkShoppingListTextItemForm {
+    pub shopping_list_id: i64,
+    pub item_id: i64,
+    pub product_id: String,
+}
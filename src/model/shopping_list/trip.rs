@@ -0,0 +1,24 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+// a finished trip to the shops, made of the items checked off until then
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct ShoppingTrip {
+    pub id: i64,
+    // none once the list is deleted
+    pub shopping_list_id: Option<i64>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub item_count: i64,
+}
+
+impl ShoppingTrip {
+    pub fn format_duration(&self) -> String {
+        let minutes = (self.finished_at - self.started_at).num_minutes().max(0);
+        match minutes {
+            0..=59 => format!("{} Min.", minutes),
+            _ => format!("{} Std. {} Min.", minutes / 60, minutes % 60),
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use crate::model::category::{Category, CategoryTree};
+
+// the order a user walks through the aisles of a store, by top level category
+#[derive(Debug, Default, Clone)]
+pub struct StoreAisles {
+    pub category_ids: Vec<i64>,
+}
+
+impl StoreAisles {
+    pub fn len(&self) -> usize {
+        self.category_ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.category_ids.is_empty()
+    }
+
+    // none for a category that isn't placed yet
+    pub fn get_position(&self, category_id: &i64) -> Option<usize> {
+        self.category_ids.iter().position(|id| id == category_id)
+    }
+
+    // the top level categories along the aisles, the ones not placed yet follow in the order of the tree
+    pub fn sort_categories<'a>(&self, categories: &'a CategoryTree) -> Vec<&'a Category> {
+        let mut roots = categories.categories.iter()
+            .filter(|category| category.parent_id.is_none())
+            .collect::<Vec<&Category>>();
+        // stable, so the unplaced keep the order of the tree
+        roots.sort_by_key(|category| self.get_position(&category.id).unwrap_or(usize::MAX));
+        roots
+    }
+}
+
+// the top level categories in the order they were dropped in
+#[derive(Debug)]
+pub struct StoreAislesForm {
+    pub store_id: i64,
+    pub category_ids: Vec<i64>,
+}
+
+impl StoreAislesForm {
+    // the category ids repeat, which a struct can't be deserialized from. none without a store or for an invalid id
+    pub fn from_pairs(pairs: &[(String, String)]) -> Option<Self> {
+        let mut store_id = None;
+        let mut category_ids = vec![];
+        for (key, value) in pairs.iter() {
+            match key.as_str() {
+                "store_id" => store_id = Some(value.parse::<i64>().ok()?),
+                "category_id" => category_ids.push(value.parse::<i64>().ok()?),
+                _ => {},
+            }
+        }
+
+        Some(Self { store_id: store_id?, category_ids })
+    }
+}
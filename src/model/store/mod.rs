@@ -0,0 +1,150 @@
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, types::Json};
+
+pub mod aisle;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OpeningHours {
+    // 0 is monday, 6 is sunday
+    pub weekday: u32,
+    // local time as "HH:MM", zero-padded so they compare lexicographically
+    pub opens: String,
+    pub closes: String,
+}
+
+impl OpeningHours {
+    pub fn format(&self) -> String {
+        format!("{} - {} Uhr", self.opens, self.closes)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct Store {
+    pub id: i64,
+    pub created_at: DateTime<Utc>,
+    pub market_id: i64,
+    pub name: String,
+    pub address: String,
+    pub lng: f64,
+    pub lat: f64,
+    pub opening_hours: Json<Vec<OpeningHours>>,
+    #[sqlx(default)]
+    pub distance_in_meters: Option<f64>,
+}
+
+impl Store {
+    pub fn get_aisles_href(&self) -> String {
+        format!("/maerkte/{}/gaenge", self.id)
+    }
+
+    pub fn format_distance(&self) -> String {
+        match self.distance_in_meters {
+            Some(meters) => format_distance(meters),
+            None => "".to_string(),
+        }
+    }
+
+    pub fn get_opening_hours_for_today(&self) -> Option<&OpeningHours> {
+        self.get_opening_hours_at(Utc::now())
+    }
+
+    pub fn is_open_now(&self) -> bool {
+        self.is_open_at(Utc::now())
+    }
+
+    fn get_opening_hours_at(&self, at: DateTime<Utc>) -> Option<&OpeningHours> {
+        let weekday = to_store_time(at).weekday().num_days_from_monday();
+        self.opening_hours.iter().find(|hours| hours.weekday == weekday)
+    }
+
+    fn is_open_at(&self, at: DateTime<Utc>) -> bool {
+        let time = to_store_time(at).format("%H:%M").to_string();
+        match self.get_opening_hours_at(at) {
+            Some(hours) => hours.opens <= time && time < hours.closes,
+            None => false,
+        }
+    }
+}
+
+// opening hours are german local time whatever zone the server runs in.
+// CET, and CEST from the last sunday of march to the last sunday of october, both switch at 01:00 UTC
+fn to_store_time(at: DateTime<Utc>) -> DateTime<FixedOffset> {
+    let switch_at = |month: u32| {
+        let last_day = NaiveDate::from_ymd_opt(at.year(), month, 31).expect("march and october have 31 days");
+        let last_sunday = last_day - Duration::days(last_day.weekday().num_days_from_sunday() as i64);
+        Utc.from_utc_datetime(&last_sunday.and_hms_opt(1, 0, 0).expect("01:00 is a valid time"))
+    };
+    let offset_in_hours = if switch_at(3) <= at && at < switch_at(10) { 2 } else { 1 };
+    at.with_timezone(&FixedOffset::east_opt(offset_in_hours * 3600).expect("the offset is within a day"))
+}
+
+pub fn format_distance(meters: f64) -> String {
+    if meters >= 1000f64 {
+        format!("{:.1} km", meters / 1000f64)
+    } else {
+        format!("{:.0} m", meters)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, hour, minute, 0).unwrap()
+    }
+
+    fn store(opening_hours: Vec<(u32, &str, &str)>) -> Store {
+        Store {
+            id: 1,
+            created_at: DateTime::UNIX_EPOCH,
+            market_id: 1,
+            name: "Lidl Altstadt".to_string(),
+            address: "".to_string(),
+            lng: 11.5755,
+            lat: 48.1374,
+            opening_hours: Json(opening_hours.into_iter().map(|(weekday, opens, closes)| OpeningHours {
+                weekday,
+                opens: opens.to_string(),
+                closes: closes.to_string(),
+            }).collect()),
+            distance_in_meters: None,
+        }
+    }
+
+    #[test]
+    fn store_time_is_cet_in_winter_and_cest_in_summer() {
+        assert_eq!(to_store_time(at(2026, 1, 15, 12, 0)).format("%H:%M").to_string(), "13:00");
+        assert_eq!(to_store_time(at(2026, 7, 15, 12, 0)).format("%H:%M").to_string(), "14:00");
+    }
+
+    #[test]
+    fn store_time_switches_on_the_last_sundays_of_march_and_october() {
+        // 2026-03-29 and 2026-10-25 are the last sundays
+        assert_eq!(to_store_time(at(2026, 3, 29, 0, 59)).format("%d %H:%M").to_string(), "29 01:59");
+        assert_eq!(to_store_time(at(2026, 3, 29, 1, 0)).format("%d %H:%M").to_string(), "29 03:00");
+        assert_eq!(to_store_time(at(2026, 10, 25, 0, 59)).format("%d %H:%M").to_string(), "25 02:59");
+        assert_eq!(to_store_time(at(2026, 10, 25, 1, 0)).format("%d %H:%M").to_string(), "25 02:00");
+        // 2027-03-28 is the last sunday, not the 31st
+        assert_eq!(to_store_time(at(2027, 3, 28, 1, 0)).format("%H:%M").to_string(), "03:00");
+    }
+
+    #[test]
+    fn store_is_open_by_german_time() {
+        // 2026-10-19 is a monday
+        let store = store(vec![(0, "07:00", "21:00"), (1, "08:00", "20:00")]);
+
+        // 06:30 UTC is 08:30 CEST
+        assert!(store.is_open_at(at(2026, 10, 19, 6, 30)));
+        assert!(!store.is_open_at(at(2026, 10, 19, 4, 59)));
+        assert!(store.is_open_at(at(2026, 10, 19, 5, 0)));
+        // 21:00 CEST
+        assert!(!store.is_open_at(at(2026, 10, 19, 19, 0)));
+        // 22:30 UTC on monday is 00:30 on tuesday
+        assert_eq!(store.get_opening_hours_at(at(2026, 10, 19, 22, 30)).map(|hours| hours.weekday), Some(1));
+        assert!(!store.is_open_at(at(2026, 10, 19, 22, 30)));
+        // no hours on sunday
+        assert!(!store.is_open_at(at(2026, 10, 25, 12, 0)));
+    }
+}
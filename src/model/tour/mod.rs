@@ -0,0 +1,312 @@
+use std::collections::HashMap;
+use crate::model::{
+    product::{Price, Product},
+    store::{format_distance, Store},
+};
+
+const EARTH_RADIUS_IN_METERS: f64 = 6_371_000f64;
+const DEFAULT_CURRENCY: &str = "EUR";
+
+#[derive(Debug, Clone, Copy)]
+pub struct GeoPoint {
+    pub lng: f64,
+    pub lat: f64,
+}
+
+impl GeoPoint {
+    pub fn new(lng: f64, lat: f64) -> Self {
+        Self { lng, lat }
+    }
+
+    // straight-line (haversine) distance, good enough to order a handful of stops
+    pub fn distance_in_meters(&self, other: &GeoPoint) -> f64 {
+        let d_lat = (other.lat - self.lat).to_radians();
+        let d_lng = (other.lng - self.lng).to_radians();
+        let a = (d_lat / 2f64).sin().powi(2)
+            + self.lat.to_radians().cos() * other.lat.to_radians().cos() * (d_lng / 2f64).sin().powi(2);
+        2f64 * EARTH_RADIUS_IN_METERS * a.sqrt().asin()
+    }
+}
+
+#[derive(Debug)]
+pub struct TourStop {
+    pub store: Store,
+    pub products: Vec<Product>,
+    pub distance_from_previous_in_meters: f64,
+}
+
+impl TourStop {
+    pub fn get_estimated_total(&self) -> Price {
+        sum_prices(&self.products)
+    }
+
+    pub fn format_distance_from_previous(&self) -> String {
+        format_distance(self.distance_from_previous_in_meters)
+    }
+}
+
+#[derive(Debug)]
+pub struct Tour {
+    pub stops: Vec<TourStop>,
+    // products of markets without a store in reach
+    pub unassigned_products: Vec<Product>,
+    pub distance_back_in_meters: f64,
+}
+
+impl Tour {
+    pub fn plan(start: GeoPoint, stores: Vec<Store>, products: Vec<Product>) -> Self {
+        let mut products_by_market: HashMap<i64, Vec<Product>> = HashMap::new();
+        products.into_iter().for_each(|product| {
+            products_by_market.entry(product.market_id).or_default().push(product);
+        });
+
+        let stores = stores.into_iter()
+            .filter(|store| products_by_market.contains_key(&store.market_id))
+            .collect::<Vec<Store>>();
+        let points = stores.iter().map(|store| GeoPoint::new(store.lng, store.lat)).collect::<Vec<GeoPoint>>();
+        let order = order_stops(&start, &points);
+
+        let mut stores = stores.into_iter().map(Some).collect::<Vec<Option<Store>>>();
+        let mut previous = start;
+        let mut stops = Vec::with_capacity(order.len());
+        for index in order {
+            let store = stores[index].take().expect("every store is visited exactly once");
+            let products = products_by_market.remove(&store.market_id).unwrap_or_default();
+            stops.push(TourStop {
+                distance_from_previous_in_meters: previous.distance_in_meters(&points[index]),
+                store,
+                products,
+            });
+            previous = points[index];
+        }
+
+        Self {
+            distance_back_in_meters: previous.distance_in_meters(&start),
+            stops,
+            unassigned_products: products_by_market.into_values().flatten().collect(),
+        }
+    }
+
+    pub fn get_total_distance_in_meters(&self) -> f64 {
+        self.stops.iter().map(|stop| stop.distance_from_previous_in_meters).sum::<f64>() + self.distance_back_in_meters
+    }
+
+    pub fn format_total_distance(&self) -> String {
+        format_distance(self.get_total_distance_in_meters())
+    }
+
+    pub fn get_estimated_total(&self) -> Price {
+        let products = self.stops.iter().flat_map(|stop| stop.products.iter());
+        sum_prices(products)
+    }
+}
+
+// nearest neighbour tour starting and ending at `start`, improved with 2-opt until no swap shortens it
+fn order_stops(start: &GeoPoint, points: &[GeoPoint]) -> Vec<usize> {
+    let order = order_by_nearest_neighbour(start, points);
+    improve_by_two_opt(start, points, order)
+}
+
+fn order_by_nearest_neighbour(start: &GeoPoint, points: &[GeoPoint]) -> Vec<usize> {
+    let mut remaining = (0..points.len()).collect::<Vec<usize>>();
+    let mut order = Vec::with_capacity(points.len());
+    let mut current = *start;
+    while !remaining.is_empty() {
+        let (position, _) = remaining.iter().enumerate()
+            .min_by(|(_, a), (_, b)| {
+                current.distance_in_meters(&points[**a]).total_cmp(&current.distance_in_meters(&points[**b]))
+            })
+            .expect("remaining is not empty");
+        let next = remaining.swap_remove(position);
+        current = points[next];
+        order.push(next);
+    }
+    order
+}
+
+fn improve_by_two_opt(start: &GeoPoint, points: &[GeoPoint], mut order: Vec<usize>) -> Vec<usize> {
+    let point_at = |order: &[usize], position: usize| -> GeoPoint {
+        if position == 0 || position > order.len() {
+            *start
+        } else {
+            points[order[position - 1]]
+        }
+    };
+
+    // positions 0 and len + 1 are the fixed start/end, the stops in between may be reversed
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for i in 1..order.len() {
+            for j in (i + 1)..=order.len() {
+                let before = point_at(&order, i - 1).distance_in_meters(&point_at(&order, i))
+                    + point_at(&order, j).distance_in_meters(&point_at(&order, j + 1));
+                let after = point_at(&order, i - 1).distance_in_meters(&point_at(&order, j))
+                    + point_at(&order, i).distance_in_meters(&point_at(&order, j + 1));
+                if after + f64::EPSILON < before {
+                    order[(i - 1)..j].reverse();
+                    improved = true;
+                }
+            }
+        }
+    }
+
+    order
+}
+
+fn sum_prices<'a>(products: impl IntoIterator<Item = &'a Product>) -> Price {
+    let mut currency = None;
+    let total = products.into_iter()
+        .filter_map(|product| product.get_line_total())
+        .filter_map(|line_total| {
+            currency.get_or_insert(line_total.currency);
+            line_total.price
+        })
+        .sum::<i32>();
+
+    Price {
+        price: Some(total),
+        currency: currency.unwrap_or(DEFAULT_CURRENCY.to_string()),
+        created_at: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::DateTime;
+    use sqlx::types::Json;
+    use super::*;
+    use crate::model::product::test_product;
+
+    // about 1.1 km per 0.01 degrees of latitude
+    const START: GeoPoint = GeoPoint { lng: 11.57, lat: 48.13 };
+
+    fn point(lng_offset: f64, lat_offset: f64) -> GeoPoint {
+        GeoPoint::new(START.lng + lng_offset, START.lat + lat_offset)
+    }
+
+    fn store(id: i64, market_id: i64, point: GeoPoint) -> Store {
+        Store {
+            id,
+            created_at: DateTime::UNIX_EPOCH,
+            market_id,
+            name: format!("Markt {}", id),
+            address: "".to_string(),
+            lng: point.lng,
+            lat: point.lat,
+            opening_hours: Json(vec![]),
+            distance_in_meters: None,
+        }
+    }
+
+    fn route_length(points: &[GeoPoint], order: &[usize]) -> f64 {
+        let mut previous = START;
+        let mut length = 0f64;
+        for index in order {
+            length += previous.distance_in_meters(&points[*index]);
+            previous = points[*index];
+        }
+        length + previous.distance_in_meters(&START)
+    }
+
+    fn is_permutation(order: &[usize], len: usize) -> bool {
+        let mut sorted = order.to_vec();
+        sorted.sort_unstable();
+        sorted == (0..len).collect::<Vec<usize>>()
+    }
+
+    #[test]
+    fn a_single_stop_is_visited() {
+        assert_eq!(order_stops(&START, &[point(0.01, 0.01)]), vec![0]);
+        assert!(order_stops(&START, &[]).is_empty());
+    }
+
+    #[test]
+    fn the_nearer_of_two_stops_comes_first() {
+        assert_eq!(order_stops(&START, &[point(0.03, 0f64), point(0.01, 0f64)]), vec![1, 0]);
+    }
+
+    #[test]
+    fn four_stops_are_visited_without_crossing() {
+        // the corners of a square around the start, any tour along the edges is the shortest
+        let points = [point(0.01, 0.01), point(-0.01, -0.01), point(0.01, -0.01), point(-0.01, 0.01)];
+        let order = order_stops(&START, &points);
+
+        assert!(is_permutation(&order, points.len()));
+        let edges = order.windows(2)
+            .map(|pair| (points[pair[0]].lng == points[pair[1]].lng) || (points[pair[0]].lat == points[pair[1]].lat))
+            .collect::<Vec<bool>>();
+        assert_eq!(edges, vec![true, true, true]);
+    }
+
+    #[test]
+    fn two_opt_never_makes_the_route_longer() {
+        // a fixed pseudo random sequence, the same every run
+        let mut seed = 42u64;
+        let mut next = || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            ((seed >> 33) as f64 / (1u64 << 31) as f64 - 0.5) * 0.1
+        };
+        for len in 1..=8 {
+            for _ in 0..20 {
+                let points = (0..len).map(|_| point(next(), next())).collect::<Vec<GeoPoint>>();
+                let nearest_neighbour = order_by_nearest_neighbour(&START, &points);
+                let improved = improve_by_two_opt(&START, &points, nearest_neighbour.to_owned());
+
+                assert!(is_permutation(&improved, len));
+                assert!(route_length(&points, &improved) <= route_length(&points, &nearest_neighbour) + 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn two_opt_untangles_a_crossing_route() {
+        // nearest neighbour goes 1, 2, 0, 3 and crosses its own path on the way back
+        let points = [point(-0.03, -0.03), point(-0.02, 0f64), point(0.01, -0.02), point(0.02, 0.01)];
+        let nearest_neighbour = order_by_nearest_neighbour(&START, &points);
+        let improved = improve_by_two_opt(&START, &points, nearest_neighbour.to_owned());
+
+        assert_eq!(nearest_neighbour, vec![1, 2, 0, 3]);
+        assert_ne!(improved, nearest_neighbour);
+        assert!(route_length(&points, &improved) < route_length(&points, &nearest_neighbour));
+    }
+
+    #[test]
+    fn products_are_split_by_market_into_the_stops() {
+        let stores = vec![
+            store(1, 1, point(0.02, 0f64)),
+            store(2, 2, point(0.01, 0f64)),
+            // no product of market 3 is on the list
+            store(3, 3, point(0.005, 0f64)),
+        ];
+        let products = vec![
+            test_product("lidl-1", 1, Some(109), "EUR"),
+            test_product("lidl-2", 1, None, "EUR"),
+            test_product("aldi-1", 2, Some(249), "EUR"),
+            // no store of market 4 is in reach
+            test_product("netto-1", 4, Some(99), "EUR"),
+        ];
+        let tour = Tour::plan(START, stores, products);
+
+        assert_eq!(tour.stops.iter().map(|stop| stop.store.id).collect::<Vec<i64>>(), vec![2, 1]);
+        assert_eq!(tour.stops[1].products.iter().map(|product| product.id.as_str()).collect::<Vec<&str>>(), vec!["lidl-1", "lidl-2"]);
+        assert_eq!(tour.unassigned_products.iter().map(|product| product.id.as_str()).collect::<Vec<&str>>(), vec!["netto-1"]);
+        assert!((tour.get_total_distance_in_meters() - 2f64 * START.distance_in_meters(&point(0.02, 0f64))).abs() < 1f64);
+    }
+
+    #[test]
+    fn products_without_a_price_are_left_out_of_the_totals() {
+        let products = vec![
+            test_product("lidl-1", 1, Some(109), "EUR"),
+            test_product("lidl-2", 1, None, "EUR"),
+        ];
+        let tour = Tour::plan(START, vec![store(1, 1, point(0.01, 0f64))], products);
+
+        assert_eq!(tour.stops[0].products.len(), 2);
+        assert_eq!(tour.stops[0].get_estimated_total().price, Some(109));
+        assert_eq!(tour.get_estimated_total().format(), "1.09 EUR");
+
+        let tour = Tour::plan(START, vec![store(1, 1, point(0.01, 0f64))], vec![test_product("lidl-2", 1, None, "EUR")]);
+        assert_eq!(tour.get_estimated_total().format(), "0.00 EUR");
+    }
+}
@@ -0,0 +1,9 @@
+use axum::{routing::get, Router};
+
+use crate::{routes::controller::shopping_list::export::export_shopping_list, AppState};
+
+// machine readable endpoints, authenticated by the same cookie as the pages
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/shopping_lists/:id/export", get(export_shopping_list))
+}
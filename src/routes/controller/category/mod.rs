@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use askama::Template;
+use axum::{
+    extract::{Extension, Path, Request, State},
+    http::StatusCode,
+    response::{Html, IntoResponse},
+    routing::get,
+    Router,
+};
+use futures::try_join;
+
+use crate::{
+    core::{context::Context, pagination::{PageLimits, Pagination}, query_params::StateParams},
+    model::{product::{filter::ProductFilter, ListProduct}, user::User},
+    routes::minify_html_response,
+    services::{
+        category::find_category_tree,
+        product::find_products,
+        shopping_list::find_shopping_list_items,
+    },
+    view::category::CategoryBrowseTemplate,
+    AppState,
+};
+
+const PAGE_LIMITS: PageLimits = PageLimits::new(10, 50);
+
+pub async fn get_browse_page(
+    query_params: StateParams,
+    state: State<AppState>,
+    Extension(authenticated_user): Extension<Arc<Option<User>>>,
+    path: Path<HashMap<String, String>>,
+    request: Request,
+) -> impl IntoResponse {
+    let user = authenticated_user.as_ref().as_ref().expect("get_browse_page is an auth protected route");
+    let shopping_list_id = user.selected_shopping_list_id;
+    let authenticated_user_id = user.get_id().expect("authenticated user must have an id");
+
+    let category_tree = match find_category_tree(&state.db_pool).await {
+        Ok(category_tree) => category_tree,
+        Err(e) => {
+            eprintln!("unexpected error in controller::category::get_browse_page {e:?}");
+            return (StatusCode::INTERNAL_SERVER_ERROR, Html("".to_string())).into_response();
+        }
+    };
+
+    let selected_category = match path.get("slug") {
+        Some(slug) => match category_tree.find_by_slug(slug) {
+            Some(category) => Some(category),
+            None => {
+                return (StatusCode::TEMPORARY_REDIRECT, [("Location", "/stoebern")]).into_response();
+            }
+        },
+        None => None,
+    };
+
+    let sort_by = query_params.get_sort_by().unwrap_or("created_at".to_string());
+    let filter = ProductFilter::from_query_params(&query_params, shopping_list_id)
+        .with_category(selected_category.map(|category| category.slug.to_owned()));
+    let pagination = match Pagination::from_query_params(&query_params, &PAGE_LIMITS) {
+        Ok(pagination) => pagination.with_uri(request.uri().clone()),
+        Err(e) => {
+            return (StatusCode::BAD_REQUEST, Html(e.get_message())).into_response();
+        }
+    };
+    let pagination = if filter.q.is_none() { pagination.by_cursor() } else { pagination };
+
+    let (products, shopping_list_items) = match selected_category {
+        Some(_) => match try_join!(
+            find_products(
+                &state.db_pool,
+                &filter,
+                sort_by,
+                query_params.get_sort_order().unwrap_or("".to_string()),
+                pagination.limit + 1,
+                pagination.get_offset(),
+                pagination.get_cursor(),
+            ),
+            find_shopping_list_items(
+                &state.db_pool,
+                shopping_list_id.as_ref().unwrap_or(&0i64),
+                &authenticated_user_id,
+            ),
+        ) {
+            Ok(val) => val,
+            Err(e) => {
+                eprintln!("unexpected error in controller::category::get_browse_page {e:?}");
+                return (StatusCode::INTERNAL_SERVER_ERROR, Html("".to_string())).into_response();
+            }
+        },
+        None => (vec![], Default::default()),
+    };
+
+    let (pagination, products) = pagination.with_rows(products, |product| product.get_list_cursor());
+
+    let list_products = products.iter().map(|p| {
+        ListProduct {
+            product: p,
+            is_liked: shopping_list_items.contains(&p.id),
+        }
+    }).collect::<Vec<ListProduct>>();
+
+    let template = CategoryBrowseTemplate {
+        category_tree: &category_tree,
+        selected_category,
+        products: list_products,
+        pagination: &pagination,
+        authenticated_user: &authenticated_user,
+        notification: None,
+        context: Context::from_request(&request),
+    };
+
+    (StatusCode::OK, minify_html_response(&template.render().unwrap_or_default())).into_response()
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/stoebern", get(get_browse_page))
+        .route("/stoebern/:slug", get(get_browse_page))
+}
@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Router,
+};
+
+use crate::{
+    model::product::image::ImageSize,
+    routes::get_value_from_path,
+    services::image::{find_product_image_source, get_image_etag, load_resized_image},
+    AppState,
+};
+
+const PLACEHOLDER: &str = include_str!("../../../../assets/img/product_placeholder.svg");
+// resized images never change for an etag, a placeholder is retried soon
+const IMAGE_CACHE_CONTROL: &str = "private, max-age=604800";
+const PLACEHOLDER_CACHE_CONTROL: &str = "private, max-age=300";
+
+// product images are proxied, the markets never see the users and lists don't load the full size images
+pub async fn get_product_image(
+    state: State<AppState>,
+    path: Path<HashMap<String, String>>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let product_id = get_value_from_path(&path, "product_id");
+    let Some(size) = ImageSize::from_str(get_value_from_path(&path, "size").as_str()) else {
+        return (StatusCode::NOT_FOUND).into_response();
+    };
+
+    let source = match find_product_image_source(&state.db_pool, product_id.as_str()).await {
+        Ok(Some(source)) => source,
+        Ok(None) => return placeholder_response(),
+        Err(sqlx::Error::RowNotFound) => {
+            return (StatusCode::NOT_FOUND).into_response();
+        },
+        Err(sqlx::Error::PoolTimedOut) => {
+            return (StatusCode::TOO_MANY_REQUESTS).into_response();
+        },
+        Err(e) => {
+            eprintln!("unexpected error in controller::image::get_product_image {e:?}");
+            return (StatusCode::INTERNAL_SERVER_ERROR).into_response();
+        },
+    };
+
+    let etag = format!("\"{}\"", get_image_etag(source.as_str(), &size).await);
+    let is_not_modified = headers.get(header::IF_NONE_MATCH)
+        .and_then(|if_none_match| if_none_match.to_str().ok())
+        .is_some_and(|if_none_match| if_none_match.split(',').any(|tag| tag.trim() == etag || tag.trim() == "*"));
+    if is_not_modified {
+        return (
+            StatusCode::NOT_MODIFIED,
+            [(header::ETAG, etag), (header::CACHE_CONTROL, IMAGE_CACHE_CONTROL.to_string())],
+        ).into_response();
+    }
+
+    match load_resized_image(source.as_str(), &size, etag.trim_matches('"')).await {
+        Ok(bytes) => (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, "image/jpeg".to_string()),
+                (header::ETAG, etag),
+                (header::CACHE_CONTROL, IMAGE_CACHE_CONTROL.to_string()),
+            ],
+            bytes,
+        ).into_response(),
+        Err(e) => {
+            eprintln!("couldnt load image of product {product_id} in size {}: {e}", size.get_param());
+            placeholder_response()
+        },
+    }
+}
+
+fn placeholder_response() -> axum::response::Response {
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "image/svg+xml"), (header::CACHE_CONTROL, PLACEHOLDER_CACHE_CONTROL)],
+        PLACEHOLDER,
+    ).into_response()
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/img/:product_id/:size", get(get_product_image))
+}
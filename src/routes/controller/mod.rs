@@ -1,5 +1,10 @@
+pub mod category;
+pub mod image;
 pub mod product;
+pub mod search;
 pub mod shopping_list;
 pub mod social_timeline;
 pub mod static_page;
-pub mod user;
\ No newline at end of file
+pub mod store;
+pub mod tour;
+pub mod user;
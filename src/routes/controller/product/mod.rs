@@ -1,7 +1,7 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use axum::{
-    extract::{Extension, Path, Query, Request, State}, 
+    extract::{Extension, Path, Request, State}, 
     http::StatusCode,
     response::{IntoResponse, Html}, 
     routing::get, 
@@ -11,16 +11,29 @@ use askama::Template;
 use futures::try_join;
 
 use crate::{
-    core::{context::Context, pagination::Pagination, query_params::StateParams},
+    core::{context::Context, pagination::{PageLimits, Pagination}, query_params::StateParams},
     services::{
-        product::{find_product, find_products, find_product_prices},
+        category::find_category_path,
+        product::{
+            find_deals,
+            find_missing_product_name,
+            find_product,
+            find_products,
+            find_product_facets,
+            find_product_prices,
+            find_product_promotions,
+            find_similar_products,
+        },
         shopping_list::find_shopping_list_items,
     },
 };
 use crate::routes::{minify_html_response, get_value_from_path};
 use crate::AppState;
-use crate::model::{user::User, product::ListProduct};
-use crate::view::product::{ProductDetailTemplate, ProductListTemplate};
+use crate::model::{user::User, product::{deal::DealSortBy, filter::ProductFilter, ListProduct}};
+use crate::view::product::{ProductDealListTemplate, ProductDetailTemplate, ProductListTemplate, ProductNotFoundTemplate};
+
+const PAGE_LIMITS: PageLimits = PageLimits::new(10, 50);
+const SIMILAR_PRODUCTS_LIMIT: i64 = 5;
 
 pub async fn get_product_detail_page(
     state: State<AppState>,
@@ -37,6 +50,7 @@ pub async fn get_product_detail_page(
     match try_join!(
         find_product(&state.db_pool, product_id.as_str()),
         find_product_prices(&state.db_pool, product_id.as_str()),
+        find_product_promotions(&state.db_pool, product_id.as_str()),
         find_shopping_list_items(
             &state.db_pool,
             &shopping_list_id.as_ref().unwrap_or(&0i64),
@@ -46,11 +60,22 @@ pub async fn get_product_detail_page(
         Ok(val) => {
             let product = val.0;
             let prices = val.1;
-            let shopping_list_items = val.2;
+            let promotions = val.2;
+            let shopping_list_items = val.3;
+
+            let category_path = match product.category_id {
+                Some(category_id) => find_category_path(&state.db_pool, &category_id).await.unwrap_or_else(|e| {
+                    eprintln!("error while finding category path in get_product_detail_page: {:?}", e);
+                    vec![]
+                }),
+                None => vec![],
+            };
 
             let template = ProductDetailTemplate {
                 product: &product,
                 prices: &prices,
+                promotions: &promotions,
+                category_path: &category_path,
                 is_liked: shopping_list_items.contains(&product.id),
                 authenticated_user: &authenticated_user,
                 notification: None,
@@ -68,49 +93,173 @@ pub async fn get_product_detail_page(
         }
         Err(e) => {
             eprintln!("error in get_product_detail_page: {:?}", e);
-            return (StatusCode::TEMPORARY_REDIRECT, [("Location", "/einkaufstour")]).into_response();
+            return (StatusCode::TEMPORARY_REDIRECT, [("Location", "/produkte")]).into_response();
         }
     }
 
 }
 
+// the best deals right now, by how far below its usual price a product is
+pub async fn get_deal_list_page(
+    query_params: StateParams,
+    state: State<AppState>,
+    Extension(authenticated_user): Extension<Arc<Option<User>>>,
+    request: Request,
+) -> impl IntoResponse {
+    let sort_by = DealSortBy::from_str(query_params.get_sort_by().unwrap_or_default().as_str());
+    let user = authenticated_user.as_ref().as_ref().expect("get_deal_list_page is an auth protected route");
+    let shopping_list_id = user.selected_shopping_list_id;
+    let authenticated_user_id = user.get_id().expect("authenticated user must have an id");
+    let pagination = match Pagination::from_query_params(&query_params, &PAGE_LIMITS) {
+        Ok(pagination) => pagination.with_uri(request.uri().clone()),
+        Err(e) => {
+            return (StatusCode::BAD_REQUEST, Html(e.get_message())).into_response();
+        }
+    };
+
+    match try_join!(
+        find_deals(&state.db_pool, &sort_by, pagination.limit, pagination.get_offset()),
+        find_shopping_list_items(
+            &state.db_pool,
+            shopping_list_id.as_ref().unwrap_or(&0i64),
+            &authenticated_user_id,
+        ),
+    ) {
+        Ok(((products, total), shopping_list_items)) => {
+            let pagination = pagination.with_total(total);
+            let template = ProductDealListTemplate {
+                products: products.iter().map(|p| {
+                    ListProduct {
+                        product: p,
+                        is_liked: shopping_list_items.contains(&p.id),
+                    }
+                }).collect::<Vec<ListProduct>>(),
+                sort_by,
+                authenticated_user: &authenticated_user,
+                pagination: &pagination,
+                notification: None,
+                context: Context::from_request(&request),
+            };
+
+            (StatusCode::OK, minify_html_response(&template.render().unwrap_or_default())).into_response()
+        },
+        Err(sqlx::Error::PoolTimedOut) => {
+            (StatusCode::TOO_MANY_REQUESTS).into_response()
+        },
+        Err(e) => {
+            eprintln!("unexpected error in controller::products::get_deal_list_page {e:?}");
+            (StatusCode::INTERNAL_SERVER_ERROR, Html("".to_string())).into_response()
+        }
+    }
+}
+
+// the detail page redirects here for unknown products, removed ones are linked from the shopping lists
+pub async fn get_product_not_found_page(
+    state: State<AppState>,
+    Extension(authenticated_user): Extension<Arc<Option<User>>>,
+    path: Path<HashMap<String, String>>,
+    request: Request,
+) -> impl IntoResponse {
+    let product_id = get_value_from_path(&path, "product_id");
+
+    let user = authenticated_user.as_ref().as_ref().expect("get_product_not_found_page is an auth protected route");
+    let shopping_list_id = user.selected_shopping_list_id;
+    let authenticated_user_id = user.get_id().expect("authenticated user must have an id");
+
+    let product_name = match find_missing_product_name(&state.db_pool, product_id.as_str()).await {
+        Ok(product_name) => product_name,
+        Err(sqlx::Error::PoolTimedOut) => {
+            return (StatusCode::TOO_MANY_REQUESTS).into_response();
+        },
+        Err(e) => {
+            eprintln!("unexpected error in controller::products::get_product_not_found_page {e:?}");
+            return (StatusCode::INTERNAL_SERVER_ERROR, Html("".to_string())).into_response();
+        }
+    };
+
+    let (similar_products, shopping_list_items) = match &product_name {
+        None => (vec![], HashSet::new()),
+        Some(product_name) => match try_join!(
+            find_similar_products(&state.db_pool, product_id.as_str(), product_name.as_str(), SIMILAR_PRODUCTS_LIMIT),
+            find_shopping_list_items(
+                &state.db_pool,
+                shopping_list_id.as_ref().unwrap_or(&0i64),
+                &authenticated_user_id,
+            ),
+        ) {
+            Ok(val) => val,
+            Err(e) => {
+                eprintln!("unexpected error in controller::products::get_product_not_found_page {e:?}");
+                return (StatusCode::INTERNAL_SERVER_ERROR, Html("".to_string())).into_response();
+            }
+        },
+    };
+
+    let template = ProductNotFoundTemplate {
+        product_id: product_id.as_str(),
+        product_name: product_name.as_deref(),
+        products: similar_products.iter().map(|p| {
+            ListProduct {
+                product: p,
+                is_liked: shopping_list_items.contains(&p.id),
+            }
+        }).collect::<Vec<ListProduct>>(),
+        authenticated_user: &authenticated_user,
+        notification: None,
+        context: Context::from_request(&request),
+    };
+
+    (StatusCode::NOT_FOUND, minify_html_response(&template.render().unwrap_or_default())).into_response()
+}
+
 pub async fn get_product_list_page(
-    Query(query_params): Query<StateParams>,
+    query_params: StateParams,
     state: State<AppState>,
     Extension(authenticated_user): Extension<Arc<Option<User>>>,
     request: Request,
 ) -> impl IntoResponse {
 
-    let search_query = query_params.get_q();
     let sort_by = query_params.get_sort_by().unwrap_or("created_at".to_string());
     let sort_order = query_params.get_sort_order().unwrap_or("".to_string());
-    let limit: usize = query_params.get_limit().unwrap_or(10);
-    let page: usize = query_params.get_page().unwrap_or(0);
-    let offset = page * limit;
-
     let user = authenticated_user.as_ref().as_ref().expect("get_product_list_page is an auth protected route");
     let shopping_list_id = user.selected_shopping_list_id;
     let authenticated_user_id = user.get_id().expect("authenticated user must have an id");
+    let filter = ProductFilter::from_query_params(&query_params, shopping_list_id);
+
+    // a search is ranked, every other list is paginated by the keyset of its sort column
+    let pagination = match Pagination::from_query_params(&query_params, &PAGE_LIMITS) {
+        Ok(pagination) => pagination.with_uri(request.uri().clone()),
+        Err(e) => {
+            return (StatusCode::BAD_REQUEST, Html(e.get_message())).into_response();
+        }
+    };
+    let pagination = if filter.q.is_none() { pagination.by_cursor() } else { pagination };
 
     match try_join!(
         find_products(
             &state.db_pool,
-            search_query,
-            shopping_list_id,
+            &filter,
             sort_by,
             sort_order,
-            limit + 1,
-            offset
+            pagination.limit + 1,
+            pagination.get_offset(),
+            pagination.get_cursor(),
         ),
         find_shopping_list_items(
             &state.db_pool,
             &shopping_list_id.as_ref().unwrap_or(&0i64),
             &authenticated_user_id,
         ),
+        find_product_facets(
+            &state.db_pool,
+            &filter,
+            shopping_list_id,
+        ),
     ) {
         Ok(val) => {
-            let products = val.0;
             let shopping_list_items = val.1;
+            let facets = val.2;
+            let (pagination, products) = pagination.with_rows(val.0, |product| product.get_list_cursor());
 
             let list_products = products.iter().map(|p| {
                 ListProduct {
@@ -119,12 +268,10 @@ pub async fn get_product_list_page(
                 }
             }).collect::<Vec<ListProduct>>();
 
-            let pagination = Pagination::from_query_params(&query_params)
-                .with_count(products.len())
-                .with_uri(request.uri().clone());
-
             let template = ProductListTemplate {
                 products: list_products,
+                filter: &filter,
+                facets: &facets,
                 authenticated_user: &authenticated_user,
                 pagination: &pagination,
                 notification: None,
@@ -144,5 +291,7 @@ pub async fn get_product_list_page(
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/produkt/:product_id", get(get_product_detail_page))
-        .route("/einkaufstour", get(get_product_list_page))
+        .route("/produkte", get(get_product_list_page))
+        .route("/angebote", get(get_deal_list_page))
+        .route("/nicht-gefunden/produkt/:product_id", get(get_product_not_found_page))
 }
\ No newline at end of file
@@ -0,0 +1,64 @@
+use askama::Template;
+use axum::{
+    extract::{Request, State},
+    http::{header, StatusCode},
+    response::{Html, IntoResponse},
+    routing::get,
+    Json,
+    Router,
+};
+
+use crate::{
+    core::{query_params::StateParams, request_extension::HttpExt},
+    model::product::suggestion::SearchSuggestions,
+    routes::minify_html_response,
+    services::product::find_search_suggestions,
+    view::product::SearchSuggestionsTemplate,
+    AppState,
+};
+
+const MIN_SUGGESTION_QUERY_LENGTH: usize = 2;
+
+// htmx gets a fragment for the search input, everything asking for json gets json
+pub async fn get_search_suggestions(
+    query_params: StateParams,
+    state: State<AppState>,
+    request: Request,
+) -> impl IntoResponse {
+    let q = query_params.get_q().unwrap_or_default();
+    let wants_json = !request.is_hx_request() && request.headers()
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(|accept| accept.contains("application/json"));
+
+    let suggestions = if q.trim().chars().count() < MIN_SUGGESTION_QUERY_LENGTH {
+        SearchSuggestions::default()
+    } else {
+        match find_search_suggestions(&state.db_pool, q.as_str(), query_params.get_search_language()).await {
+            Ok(suggestions) => suggestions,
+            Err(sqlx::Error::PoolTimedOut) => {
+                return (StatusCode::TOO_MANY_REQUESTS).into_response();
+            },
+            Err(e) => {
+                eprintln!("unexpected error in controller::search::get_search_suggestions {e:?}");
+                return (StatusCode::INTERNAL_SERVER_ERROR, Html("".to_string())).into_response();
+            }
+        }
+    };
+
+    if wants_json {
+        return (StatusCode::OK, [(header::VARY, "Accept")], Json(suggestions)).into_response();
+    }
+
+    let template = SearchSuggestionsTemplate {
+        suggestions: &suggestions,
+        q: q.as_str(),
+    };
+
+    (StatusCode::OK, [(header::VARY, "Accept")], minify_html_response(&template.render().unwrap_or_default())).into_response()
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/search/suggest", get(get_search_suggestions))
+}
@@ -0,0 +1,222 @@
+use std::{collections::HashMap, convert::Infallible, sync::Arc};
+use askama::Template;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{sse::{Event, KeepAlive, Sse}, Html, IntoResponse},
+    Extension,
+};
+use futures::{future::join_all, stream};
+use tokio::sync::broadcast::{error::{RecvError, TryRecvError}, Receiver};
+
+use crate::{
+    model::{
+        shopping_list::event::{ShoppingListEvent, ShoppingListEventKind, ShoppingListEventView, ShoppingListEventsParams},
+        user::User,
+    },
+    routes::{get_value_from_path, minify_html_response, render_error_notification},
+    services::shopping_list,
+    view::shopping_list::ShoppingListLiveUpdateTemplate,
+    AppState,
+};
+
+// pushes the changes of the collaborators to a list that's open in the browser, see the LiveUpdates element
+pub async fn get_shopping_list_events(
+    state: State<AppState>,
+    Extension(authenticated_user): Extension<Arc<Option<User>>>,
+    path: Path<HashMap<String, String>>,
+    Query(params): Query<ShoppingListEventsParams>,
+) -> impl IntoResponse {
+    let authenticated_user_id = match authenticated_user.as_ref().as_ref().and_then(|user| *user.get_id()) {
+        Some(id) => id,
+        None => {
+            return (StatusCode::FORBIDDEN, Html("")).into_response();
+        }
+    };
+    let shopping_list_id = match get_value_from_path(&path, "id").parse::<i64>() {
+        Ok(id) => id,
+        Err(_) => {
+            return (StatusCode::BAD_REQUEST, Html("")).into_response();
+        }
+    };
+
+    // subscribed before the access check, so no change in between is missed
+    let receiver = state.shopping_list_events.subscribe();
+
+    match shopping_list::find_shopping_list(&state.db_pool, &shopping_list_id, &authenticated_user_id).await {
+        Ok(_) => {},
+        Err(sqlx::Error::RowNotFound) => {
+            return (StatusCode::NOT_FOUND, Html("")).into_response();
+        },
+        Err(sqlx::Error::PoolTimedOut) => {
+            return (StatusCode::TOO_MANY_REQUESTS, Html("")).into_response();
+        },
+        Err(e) => {
+            eprintln!("undefined error in controller::shopping_list::get_shopping_list_events {e:?}");
+            return (StatusCode::UNPROCESSABLE_ENTITY, Html("")).into_response();
+        },
+    }
+
+    let event_stream = ShoppingListEventStream {
+        state: state.0,
+        receiver,
+        user_id: authenticated_user_id,
+        shopping_list_id,
+        view: params.view,
+        has_missed_events: false,
+        is_closed: false,
+    };
+    let stream = stream::unfold(event_stream, |mut event_stream| async move {
+        if event_stream.is_closed {
+            return None;
+        }
+        loop {
+            let events = event_stream.receive().await?;
+            if let Some(event) = event_stream.render(&events).await {
+                return Some((Ok::<Event, Infallible>(event), event_stream));
+            }
+        }
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default()).into_response()
+}
+
+struct ShoppingListEventStream {
+    state: AppState,
+    receiver: Receiver<ShoppingListEvent>,
+    user_id: i64,
+    shopping_list_id: i64,
+    view: ShoppingListEventView,
+    // the receiver lagged behind and dropped events, which ones is unknown
+    has_missed_events: bool,
+    // after the user lost access to the list
+    is_closed: bool,
+}
+
+impl ShoppingListEventStream {
+    // the events of the list that are waiting, at least one unless some were missed. none once the listener is gone
+    async fn receive(&mut self) -> Option<Vec<ShoppingListEvent>> {
+        let mut events = vec![];
+        while events.is_empty() && !self.has_missed_events {
+            match self.receiver.recv().await {
+                Ok(event) if event.shopping_list_id == self.shopping_list_id => events.push(event),
+                Ok(_) => {},
+                Err(RecvError::Lagged(_)) => self.has_missed_events = true,
+                Err(RecvError::Closed) => return None,
+            }
+        }
+
+        // a single change can touch several items, e.g. finishing a trip, they are rendered together
+        loop {
+            match self.receiver.try_recv() {
+                Ok(event) if event.shopping_list_id == self.shopping_list_id => events.push(event),
+                Ok(_) => {},
+                Err(TryRecvError::Lagged(_)) => self.has_missed_events = true,
+                Err(_) => return Some(events),
+            }
+        }
+    }
+
+    // none if there's nothing to swap for the open view
+    async fn render(&mut self, events: &[ShoppingListEvent]) -> Option<Event> {
+        let has_missed_events = std::mem::take(&mut self.has_missed_events);
+        let events = events.iter()
+            // the role is only checked again for events the open view shows
+            .filter(|event| event.shopping_list_id == self.shopping_list_id && self.view.is_changed_by(event))
+            .collect::<Vec<_>>();
+        if events.is_empty() && !has_missed_events {
+            return None;
+        }
+
+        // the role is checked again, members can be removed or downgraded while the list is open
+        let shopping_list = match shopping_list::find_shopping_list(&self.state.db_pool, &self.shopping_list_id, &self.user_id).await {
+            Ok(shopping_list) => shopping_list,
+            Err(sqlx::Error::RowNotFound) => {
+                self.is_closed = true;
+                let notification = render_error_notification(Some("Einkaufszettel ist nicht mehr verfügbar"));
+                return Some(Event::default().event("close").data(minify_html_response(&notification).0));
+            },
+            Err(e) => {
+                eprintln!("error in controller::shopping_list::events::render {e:?}");
+                return None;
+            },
+        };
+
+        if has_missed_events && self.view.is_reloaded_after_missed_events() {
+            return Some(Event::default().event("reload").data(""));
+        }
+
+        let renamed = events.iter().any(|event| event.kind == ShoppingListEventKind::ListRenamed);
+        let item_events = events.into_iter().filter(|event| event.is_item_event()).collect::<Vec<_>>();
+
+        let (mut removed_product_ids, mut added_products, mut updated_products) = (vec![], vec![], vec![]);
+        let (mut text_items, mut cost, mut checklist) = (None, None, None);
+        match self.view {
+            ShoppingListEventView::ShoppingMode => {
+                // the checklist is rendered as a whole, it catches up on the missed events too
+                if !item_events.is_empty() || has_missed_events {
+                    match shopping_list::find_shopping_list_checklist(&self.state.db_pool, &self.shopping_list_id, &self.user_id).await {
+                        Ok(val) => checklist = Some(val),
+                        Err(e) => eprintln!("error in controller::shopping_list::events::render {e:?}"),
+                    }
+                }
+            },
+            ShoppingListEventView::Detail => {
+                if item_events.iter().any(|event| event.is_text_item_event()) {
+                    match shopping_list::find_shopping_list_text_items(&self.state.db_pool, &self.shopping_list_id, &self.user_id).await {
+                        Ok(val) => text_items = Some(val),
+                        Err(e) => eprintln!("error in controller::shopping_list::events::render {e:?}"),
+                    }
+                }
+
+                let mut product_ids = item_events.iter()
+                    .filter_map(|event| event.product_id.as_deref())
+                    .collect::<Vec<_>>();
+                product_ids.sort_unstable();
+                product_ids.dedup();
+
+                let products = join_all(product_ids.iter().map(|product_id| {
+                    shopping_list::find_shopping_list_product(&self.state.db_pool, &self.user_id, &self.shopping_list_id, product_id)
+                })).await;
+                for (product_id, product) in product_ids.into_iter().zip(products) {
+                    let is_added = item_events.iter().any(|event| {
+                        event.product_id.as_deref() == Some(product_id)
+                            && matches!(event.kind, ShoppingListEventKind::ItemAdded | ShoppingListEventKind::ItemLinked)
+                    });
+                    match product {
+                        Ok(product) if is_added => added_products.push(product),
+                        Ok(product) => updated_products.push(product),
+                        Err(sqlx::Error::RowNotFound) => removed_product_ids.push(product_id.to_string()),
+                        Err(e) => eprintln!("error in controller::shopping_list::events::render {e:?}"),
+                    }
+                }
+
+                // any change to the products changes the totals
+                if !removed_product_ids.is_empty() || !added_products.is_empty() || !updated_products.is_empty() {
+                    let shopping_list_ids = std::slice::from_ref(&self.shopping_list_id);
+                    match shopping_list::find_shopping_list_costs(&self.state.db_pool, &self.user_id, shopping_list_ids).await {
+                        Ok(mut costs) => cost = Some(costs.remove(&self.shopping_list_id).unwrap_or_default()),
+                        Err(e) => eprintln!("error in controller::shopping_list::events::render {e:?}"),
+                    }
+                }
+            },
+        }
+
+        if !renamed && removed_product_ids.is_empty() && added_products.is_empty() && updated_products.is_empty()
+            && text_items.is_none() && checklist.is_none() {
+            return None;
+        }
+
+        let template = ShoppingListLiveUpdateTemplate {
+            shopping_list: &shopping_list,
+            renamed,
+            removed_product_ids: &removed_product_ids,
+            added_products: &added_products,
+            updated_products: &updated_products,
+            text_items: text_items.as_ref(),
+            cost: cost.as_ref(),
+            checklist: checklist.as_ref(),
+        };
+        Some(Event::default().event("update").data(minify_html_response(&template.render().unwrap_or_default()).0))
+    }
+}
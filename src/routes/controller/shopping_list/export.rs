@@ -0,0 +1,72 @@
+use std::{collections::HashMap, sync::Arc};
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{Html, IntoResponse},
+    Extension,
+};
+use chrono::Utc;
+use futures::try_join;
+
+use crate::{
+    model::{
+        shopping_list::export::{ShoppingListExport, ShoppingListExportParams},
+        user::User,
+    },
+    routes::get_value_from_path,
+    services::shopping_list,
+    AppState,
+};
+
+// the list as a file, linked on the detail page and served by the api as well
+pub async fn export_shopping_list(
+    state: State<AppState>,
+    Extension(authenticated_user): Extension<Arc<Option<User>>>,
+    path: Path<HashMap<String, String>>,
+    Query(params): Query<ShoppingListExportParams>,
+) -> impl IntoResponse {
+    let authenticated_user_id = match authenticated_user.as_ref().as_ref().and_then(|user| *user.get_id()) {
+        Some(id) => id,
+        None => {
+            return (StatusCode::FORBIDDEN, Html("")).into_response();
+        }
+    };
+    let shopping_list_id = match get_value_from_path(&path, "id").parse::<i64>() {
+        Ok(id) => id,
+        Err(_) => {
+            return (StatusCode::BAD_REQUEST, Html("")).into_response();
+        }
+    };
+    let format = match params.get_format() {
+        Some(format) => format,
+        None => {
+            return (StatusCode::BAD_REQUEST, Html("Unbekanntes Format")).into_response();
+        }
+    };
+
+    let (shopping_list, checklist) = match try_join!(
+        shopping_list::find_shopping_list(&state.db_pool, &shopping_list_id, &authenticated_user_id),
+        shopping_list::find_shopping_list_checklist(&state.db_pool, &shopping_list_id, &authenticated_user_id),
+    ) {
+        Ok(val) => val,
+        Err(sqlx::Error::RowNotFound) => {
+            return (StatusCode::NOT_FOUND, Html("")).into_response();
+        },
+        Err(sqlx::Error::PoolTimedOut) => {
+            return (StatusCode::TOO_MANY_REQUESTS, Html("")).into_response();
+        },
+        Err(e) => {
+            eprintln!("undefined error in controller::shopping_list::export_shopping_list {e:?}");
+            return (StatusCode::UNPROCESSABLE_ENTITY, Html("")).into_response();
+        },
+    };
+
+    let export = ShoppingListExport::new(&shopping_list, &checklist, Utc::now());
+    let mut headers = HeaderMap::new();
+    headers.insert(header::CONTENT_TYPE, format.get_content_type().parse().unwrap());
+    headers.insert(
+        header::CONTENT_DISPOSITION,
+        format!("attachment; filename=\"{}\"", export.get_file_name(&format)).parse().unwrap(),
+    );
+    (StatusCode::OK, headers, export.render(&format)).into_response()
+}
@@ -0,0 +1,184 @@
+use std::sync::Arc;
+use askama::Template;
+use axum::{
+    extract::{FromRequest, Request, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Extension, Form,
+};
+
+use crate::{
+    model::{
+        shopping_list::{
+            change::ShoppingListChange,
+            import::{
+                parse_shopping_list_import,
+                ShoppingListImportChoice,
+                ShoppingListImportForm,
+                ShoppingListImportMatch,
+                ShoppingListImportPreviewForm,
+            },
+        },
+        user::User,
+    },
+    routes::{minify_html_response, render_error_notification, render_success_notification},
+    services::{product, shopping_list},
+    view::shopping_list::ShoppingListImportExportTemplate,
+    AppState,
+};
+use super::notify_collaborators;
+
+const IMPORT_SUGGESTION_LIMIT: i64 = 3;
+
+// matches the pasted lines to the catalogue, nothing is saved before the review
+pub async fn preview_shopping_list_import(
+    state: State<AppState>,
+    Extension(authenticated_user): Extension<Arc<Option<User>>>,
+    request: Request,
+) -> impl IntoResponse {
+    let mut headers = HeaderMap::new();
+
+    let authenticated_user_id = match authenticated_user.as_ref().as_ref().and_then(|user| *user.get_id()) {
+        Some(id) => id,
+        None => {
+            let notification = render_error_notification(None);
+            return (StatusCode::UNAUTHORIZED, headers, minify_html_response(&notification));
+        }
+    };
+
+    let form_data = match Form::<ShoppingListImportPreviewForm>::from_request(request, &state).await {
+        Ok(form_data) => form_data,
+        Err(e) => {
+            eprintln!("error in preview_shopping_list_import {e:?}");
+            let notification = render_error_notification(None);
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            return (StatusCode::BAD_REQUEST, headers, minify_html_response(&notification));
+        }
+    };
+
+    match shopping_list::find_shopping_list(&state.db_pool, &form_data.shopping_list_id, &authenticated_user_id).await {
+        Ok(shopping_list) if shopping_list.can_edit() => {},
+        Ok(_) | Err(sqlx::Error::RowNotFound) => {
+            let notification = render_error_notification(Some("Unerlaubter Zugriff"));
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            return (StatusCode::UNAUTHORIZED, headers, minify_html_response(&notification));
+        },
+        Err(e) => {
+            eprintln!("undefined error in controller::shopping_list::preview_shopping_list_import {e:?}");
+            let notification = render_error_notification(None);
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            return (StatusCode::UNPROCESSABLE_ENTITY, headers, minify_html_response(&notification));
+        },
+    }
+
+    let lines = match parse_shopping_list_import(form_data.content.as_str()) {
+        Ok(lines) => lines,
+        Err(message) => {
+            let notification = render_error_notification(Some(message.as_str()));
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            return (StatusCode::UNPROCESSABLE_ENTITY, headers, minify_html_response(&notification));
+        }
+    };
+
+    // one after the other, a long import would take up the whole pool otherwise
+    let mut matches = Vec::with_capacity(lines.len());
+    for line in lines {
+        // a product of an export is taken as it is, unless it's gone from the catalogue
+        let exact_product = match line.product_id.as_deref() {
+            Some(product_id) => product::find_product(&state.db_pool, product_id).await.ok(),
+            None => None,
+        };
+        let products = match exact_product {
+            Some(product) => vec![product],
+            None => product::find_products_by_text(&state.db_pool, line.name.as_str(), IMPORT_SUGGESTION_LIMIT)
+                .await
+                .unwrap_or_else(|e| {
+                    eprintln!("error while matching products in preview_shopping_list_import {e:?}");
+                    vec![]
+                }),
+        };
+        matches.push(ShoppingListImportMatch { line, products });
+    }
+
+    let template = ShoppingListImportExportTemplate {
+        shopping_list_id: form_data.shopping_list_id,
+        can_edit: true,
+        matches: Some(&matches),
+        notification: None,
+    };
+    (StatusCode::OK, headers, minify_html_response(&template.render().unwrap_or_default()))
+}
+
+pub async fn save_shopping_list_import(
+    state: State<AppState>,
+    Extension(authenticated_user): Extension<Arc<Option<User>>>,
+    request: Request,
+) -> impl IntoResponse {
+    let mut headers = HeaderMap::new();
+    headers.insert("hx-reswap", "none".parse().unwrap());
+
+    let authenticated_user_id = match authenticated_user.as_ref().as_ref().and_then(|user| *user.get_id()) {
+        Some(id) => id,
+        None => {
+            let notification = render_error_notification(None);
+            return (StatusCode::UNAUTHORIZED, headers, minify_html_response(&notification));
+        }
+    };
+
+    // the fields of the lines are numbered, see ShoppingListImportForm
+    let form_data = match Form::<Vec<(String, String)>>::from_request(request, &state).await {
+        Ok(Form(pairs)) => ShoppingListImportForm::from_pairs(&pairs),
+        Err(e) => {
+            eprintln!("error in save_shopping_list_import {e:?}");
+            let notification = render_error_notification(None);
+            return (StatusCode::BAD_REQUEST, headers, minify_html_response(&notification));
+        }
+    };
+    let form_data = match form_data {
+        Ok(form_data) if form_data.items.iter().all(|item| item.choice == ShoppingListImportChoice::Skip) => {
+            let notification = render_error_notification(Some("Keine Einträge ausgewählt"));
+            return (StatusCode::UNPROCESSABLE_ENTITY, headers, minify_html_response(&notification));
+        },
+        Ok(form_data) => form_data,
+        Err(message) => {
+            let notification = render_error_notification(Some(message.as_str()));
+            return (StatusCode::UNPROCESSABLE_ENTITY, headers, minify_html_response(&notification));
+        },
+    };
+
+    match shopping_list::import_shopping_list_items(
+        &state.db_pool,
+        &authenticated_user_id,
+        &form_data.shopping_list_id,
+        &form_data.items,
+    ).await {
+        Ok(result) => {
+            if result.added_count > 0 {
+                let subject = match result.added_count {
+                    1 => "1 Eintrag".to_string(),
+                    count => format!("{} Einträge", count),
+                };
+                notify_collaborators(&state, &authenticated_user_id, &form_data.shopping_list_id, ShoppingListChange::ItemsImported(subject.as_str())).await;
+            }
+
+            // the products end up in the paged product list
+            headers.insert("xui-redirect", format!("/einkaufszettel/{}", form_data.shopping_list_id).parse().unwrap());
+            let notification = render_success_notification(Some(result.format().as_str()));
+            (StatusCode::TEMPORARY_REDIRECT, headers, minify_html_response(&notification))
+        },
+        Err(sqlx::Error::RowNotFound) => {
+            let notification = render_error_notification(Some("Unerlaubter Zugriff"));
+            (StatusCode::UNAUTHORIZED, headers, minify_html_response(&notification))
+        },
+        // a product that was removed from the catalogue in the meantime
+        Err(sqlx::Error::Database(e)) if e.is_foreign_key_violation() => {
+            let notification = render_error_notification(Some("Ein Produkt gibt es nicht mehr, bitte prüfe den Import noch einmal"));
+            (StatusCode::UNPROCESSABLE_ENTITY, headers, minify_html_response(&notification))
+        },
+        Err(e) => {
+            eprintln!("undefined error in controller::shopping_list::save_shopping_list_import {e:?}");
+            let notification = render_error_notification(Some("Import konnte nicht gespeichert werden"));
+            (StatusCode::UNPROCESSABLE_ENTITY, headers, minify_html_response(&notification))
+        },
+    }
+}
@@ -1,3 +1,9 @@
+pub mod events;
+pub mod export;
+pub mod import;
+pub mod order;
+pub mod print;
+
 use std::collections::HashMap;
 use std::sync::Arc;
 use askama::Template;
@@ -7,24 +13,61 @@ use axum::{
     response::{IntoResponse, Html}, routing::{delete, get, post, put},
     Extension, Form, RequestExt, Router
 };
+use chrono::Local;
 use futures::try_join;
 
 use crate::{
-    core::{context::Context, pagination::Pagination, query_params::StateParams, request_extension::HttpExt},
+    core::{context::Context, pagination::{PageLimits, Pagination}, query_params::StateParams, request_extension::HttpExt},
     model::{
+        product::Product,
         shopping_list::{
+            change::ShoppingListChange,
+            member::{ShareShoppingListForm, ShoppingListMemberForm},
+            quantity::{ItemQuantity, QuantityStep},
+            template::{CopyShoppingListForm, ShoppingListRecurrenceForm},
+            text_item::{AddShoppingListTextItemForm, LinkShoppingListTextItemForm, ShoppingListTextItemForm},
             AddShoppingListItemForm,
             ShoppingList,
+            ShoppingListItemForm,
+            ShoppingListItemQuantityForm,
             ShoppingListUpdateForm,
             ToggleShoppingListItemOp::Added,
         },
         user::User,
     },
-    routes::{get_value_from_path, minify_html_response, render_error_notification, render_success_notification},
-    services::shopping_list::{self},
-    view::{product::AddProductToggle, shopping_list::{ShoppingListDetailTemplate, ShoppingListsTemplate}},
+    routes::{
+        create_notification,
+        get_value_from_path,
+        minify_html_response,
+        render_error_notification,
+        render_success_notification,
+    },
+    services::{product, shopping_list::{self}, store::find_stores_with_aisles, user::contacts::find_contacts},
+    view::{
+        product::AddProductToggle,
+        shopping_list::{
+            ShoppingListChecklistTemplate,
+            ShoppingListCopyTemplate,
+            ShoppingListDetailTemplate,
+            ShoppingListItemQuantityTemplate,
+            ShoppingListMembersTemplate,
+            ShoppingListTextItemSuggestionsTemplate,
+            ShoppingListTextItemsTemplate,
+            ShoppingListsTemplate,
+            ShoppingModeTemplate,
+        },
+    },
     AppState
 };
+use events::get_shopping_list_events;
+use export::export_shopping_list;
+use import::{preview_shopping_list_import, save_shopping_list_import};
+use order::{save_shopping_list_grouping, save_shopping_list_item_order};
+use print::{get_shopping_list_pdf, get_shopping_list_print_page};
+
+const LIST_PAGE_LIMITS: PageLimits = PageLimits::new(10, 50);
+const PRODUCT_PAGE_LIMITS: PageLimits = PageLimits::new(10, 100);
+const TEXT_ITEM_SUGGESTION_LIMIT: i64 = 5;
 
 pub async fn get_shopping_lists(
     state: State<AppState>,
@@ -39,23 +82,29 @@ pub async fn get_shopping_lists(
         }
     };
 
-    let query_params: Query<StateParams> = request.extract_parts_with_state::<Query::<StateParams>, _>(&state).await.unwrap();
-    let limit: usize = query_params.get_limit().unwrap_or(10);
-    let page: usize = query_params.get_page().unwrap_or(0);
-    let offset = page * limit;
+    let query_params = match request.extract_parts::<StateParams>().await {
+        Ok(query_params) => query_params,
+        Err(e) => {
+            return e.into_response();
+        }
+    };
+    let pagination = match Pagination::from_query_params(&query_params, &LIST_PAGE_LIMITS) {
+        Ok(pagination) => pagination.with_uri(request.uri().clone()),
+        Err(e) => {
+            return (StatusCode::BAD_REQUEST, Html(e.get_message())).into_response();
+        }
+    };
     let (shopping_lists, total) = shopping_list::find_shopping_lists(
         &state.db_pool,
         authenticated_user_id,
-        limit,
-        offset,
+        pagination.limit,
+        pagination.get_offset(),
     ).await;
 
     let context = Context::new(request.uri(), request.headers());
-    let pagination = Pagination::from_query_params(&query_params)
-        .with_total(total)
-        .with_uri(request.uri().clone());
+    let pagination = pagination.with_total(total);
 
-    if page > 0 && shopping_lists.len() == 0 {
+    if pagination.page > 0 && shopping_lists.len() == 0 {
         let redirect_to = context.preserve_query_state(&0, true);
         let header_name = if context.is_hx_request() {
             "Xui-Redirect"
@@ -65,8 +114,18 @@ pub async fn get_shopping_lists(
         return (StatusCode::SEE_OTHER, [(header_name, redirect_to)]).into_response();
     }
 
+    let shopping_list_ids = shopping_lists.iter().map(|shopping_list| shopping_list.id).collect::<Vec<i64>>();
+    // the overview works without the totals
+    let costs = shopping_list::find_shopping_list_costs(&state.db_pool, &authenticated_user_id, &shopping_list_ids)
+        .await
+        .unwrap_or_else(|e| {
+            eprintln!("error in controller::shopping_list::get_shopping_lists {e:?}");
+            HashMap::new()
+        });
+
     let template = ShoppingListsTemplate {
         shopping_lists: shopping_lists,
+        costs,
         authenticated_user: &authenticated_user,
         notification: None,
         pagination: &pagination,
@@ -79,7 +138,7 @@ pub async fn get_shopping_lists(
 }
 
 pub async fn get_shopping_list_detail_page(
-    Query(query_params): Query<StateParams>,
+    query_params: StateParams,
     state: State<AppState>,
     Extension(authenticated_user): Extension<Arc<Option<User>>>,
     path: Path<HashMap<String, String>>,
@@ -88,11 +147,14 @@ pub async fn get_shopping_list_detail_page(
     let id = get_value_from_path(&path, "id");
     let authenticated_user_id = authenticated_user.as_ref().as_ref().unwrap().get_id().as_ref().expect("the authenticated user must have an id");
     let context = Context::new(request.uri(), request.headers());
-    let pagination = Pagination::from_query_params(&query_params).with_uri(request.uri().clone());
-    // TODO check if this user owns the shopping_list! => or move it to service/db
-
-    let (shopping_list, (selected_products, total)) = if context.is_create_operation() {
-        (ShoppingList::default(), (vec![], 0))
+    let pagination = match Pagination::from_query_params(&query_params, &PRODUCT_PAGE_LIMITS) {
+        Ok(pagination) => pagination.with_uri(request.uri().clone()).by_cursor(),
+        Err(e) => {
+            return (StatusCode::BAD_REQUEST, Html(e.get_message())).into_response();
+        }
+    };
+    let (shopping_list, (selected_products, _), text_items, mut costs, members, contacts, notifications, stores) = if context.is_create_operation() {
+        (ShoppingList::default(), (vec![], 0), vec![], HashMap::new(), vec![], vec![], vec![], vec![])
     } else {
         let shopping_list_id = match id.parse::<i64>() {
             Ok(val) => val,
@@ -110,8 +172,31 @@ pub async fn get_shopping_list_detail_page(
             shopping_list::find_shopping_list_products(
                 &state.db_pool,
                 &shopping_list_id,
+                authenticated_user_id,
                 &pagination,
-            )
+            ),
+            shopping_list::find_shopping_list_text_items(
+                &state.db_pool,
+                &shopping_list_id,
+                authenticated_user_id,
+            ),
+            shopping_list::find_shopping_list_costs(
+                &state.db_pool,
+                authenticated_user_id,
+                std::slice::from_ref(&shopping_list_id),
+            ),
+            shopping_list::find_shopping_list_members(
+                &state.db_pool,
+                authenticated_user_id,
+                &shopping_list_id,
+            ),
+            find_contacts(&state.db_pool, authenticated_user_id),
+            shopping_list::find_shopping_list_notifications(
+                &state.db_pool,
+                authenticated_user_id,
+                &shopping_list_id,
+            ),
+            find_stores_with_aisles(&state.db_pool, authenticated_user_id),
         ) {
             Ok(val) => val,
             Err(sqlx::Error::RowNotFound) => {
@@ -127,11 +212,18 @@ pub async fn get_shopping_list_detail_page(
         }
     };
 
-    let pagination = pagination.with_total(total);
+    let (pagination, selected_products) = pagination.with_rows(selected_products, Product::get_shopping_list_cursor);
+    let cost = costs.remove(&shopping_list.id).unwrap_or_default();
 
     let template = ShoppingListDetailTemplate {
         shopping_list: &shopping_list,
         selected_products: Some(&selected_products),
+        text_items: (!context.is_create_operation()).then_some(&text_items),
+        cost: (!context.is_create_operation()).then_some(&cost),
+        members: (!context.is_create_operation()).then_some(&members),
+        contacts: Some(&contacts),
+        notifications: Some(&notifications),
+        stores: (!context.is_create_operation()).then_some(&stores),
         pagination: Some(&pagination),
         authenticated_user: &authenticated_user,
         notification: None,
@@ -153,8 +245,6 @@ pub async fn save_shopping_list(
         return (StatusCode::FORBIDDEN, headers, Html("".to_string()));
     }
 
-    // TODO check if this user owns the shopping_list! => or move it to service/db
-
     let uri = request.uri().clone();
     let req_headers = request.headers().clone();
     let context = Context::new(&uri, &req_headers);
@@ -163,6 +253,12 @@ pub async fn save_shopping_list(
 
     let updated_shopping_list = match shopping_list::upsert_shopping_list(&state.db_pool, authenticated_user_id, &form_data).await {
         Ok(shopping_list) => shopping_list,
+        // only the owner renames a list
+        Err(sqlx::Error::RowNotFound) => {
+            let notification = render_error_notification(Some("Unerlaubter Zugriff"));
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            return (StatusCode::UNAUTHORIZED, headers, minify_html_response(&notification));
+        },
         Err(e) => {
             eprintln!("error while upserting shopping list: {:?}", e);
             let notification = render_error_notification(None);
@@ -172,9 +268,22 @@ pub async fn save_shopping_list(
     };
 
     if form_data.id.is_some() {
+        notify_collaborators(
+            &state,
+            authenticated_user_id,
+            updated_shopping_list.get_id(),
+            ShoppingListChange::Renamed(updated_shopping_list.get_name()),
+        ).await;
+
         let template = ShoppingListDetailTemplate {
             shopping_list: &updated_shopping_list,
             selected_products: None,
+            text_items: None,
+            cost: None,
+            members: None,
+            contacts: None,
+            notifications: None,
+            stores: None,
             pagination: None,
             authenticated_user: &authenticated_user,
             notification: None,
@@ -203,7 +312,7 @@ pub async fn delete_shopping_list(
     state: State<AppState>,
     Extension(authenticated_user): Extension<Arc<Option<User>>>,
     path: Path<HashMap<String, String>>,
-    query_params: Query<StateParams>,
+    query_params: StateParams,
     request: Request,
 ) -> impl IntoResponse {
     let mut headers = HeaderMap::new();
@@ -295,14 +404,29 @@ pub async fn save_shopping_list_item(
         }
     };
 
+    let quantity = match form_data.get_quantity() {
+        Some(quantity) => quantity,
+        None => {
+            let notification = render_error_notification(Some("Ungültige Menge"));
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            return (StatusCode::BAD_REQUEST, headers, minify_html_response(&notification));
+        }
+    };
+
     match shopping_list::toggle_shopping_list_item(
         &state.db_pool,
         &authenticated_user_id,
         &shopping_list_id,
         form_data.product_id.as_str(),
-        1,
+        &quantity,
     ).await {
         Ok(executed_op) => {
+            let change = match executed_op {
+                Added => ShoppingListChange::ProductAdded(form_data.product_id.as_str()),
+                _ => ShoppingListChange::ProductRemoved(form_data.product_id.as_str()),
+            };
+            notify_collaborators(&state, &authenticated_user_id, &shopping_list_id, change).await;
+
             if form_data.shopping_list_id.is_some() {
                 // TODO list-item disappears, need to render whole list
                 let content = render_success_notification(Some("Produkt vom Einkaufszettel entfernt"));
@@ -320,6 +444,12 @@ pub async fn save_shopping_list_item(
             };
             (StatusCode::OK, headers, minify_html_response(&rendered_content.render().unwrap_or_default()))
         },
+        // not on the list or the user may only view it
+        Err(sqlx::Error::RowNotFound) => {
+            let rendered_content = render_error_notification(Some("Unerlaubter Zugriff"));
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            (StatusCode::UNAUTHORIZED, headers, minify_html_response(&rendered_content))
+        },
         Err(sqlx::Error::Database(e)) => {
             let rendered_content = if e.is_unique_violation() {
                 render_error_notification(Some("Produkt ist bereits vorhanden"))
@@ -338,13 +468,942 @@ pub async fn save_shopping_list_item(
     }
 }
 
+pub async fn increment_shopping_list_item(
+    state: State<AppState>,
+    Extension(authenticated_user): Extension<Arc<Option<User>>>,
+    request: Request,
+) -> impl IntoResponse {
+    step_shopping_list_item(state, authenticated_user, request, QuantityStep::Increment).await
+}
+
+pub async fn decrement_shopping_list_item(
+    state: State<AppState>,
+    Extension(authenticated_user): Extension<Arc<Option<User>>>,
+    request: Request,
+) -> impl IntoResponse {
+    step_shopping_list_item(state, authenticated_user, request, QuantityStep::Decrement).await
+}
+
+async fn step_shopping_list_item(
+    state: State<AppState>,
+    authenticated_user: Arc<Option<User>>,
+    request: Request,
+    step: QuantityStep,
+) -> (StatusCode, HeaderMap, Html<String>) {
+    let mut headers = HeaderMap::new();
+
+    let authenticated_user_id = match authenticated_user.as_ref().as_ref().and_then(|user| *user.get_id()) {
+        Some(id) => id,
+        None => {
+            let notification = render_error_notification(None);
+            return (StatusCode::UNAUTHORIZED, headers, minify_html_response(&notification));
+        }
+    };
+
+    let form_data = match Form::<ShoppingListItemForm>::from_request(request, &state).await {
+        Ok(form_data) => form_data,
+        Err(e) => {
+            eprintln!("error in step_shopping_list_item {e:?}");
+            let notification = render_error_notification(None);
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            return (StatusCode::BAD_REQUEST, headers, minify_html_response(&notification));
+        }
+    };
+
+    let result = shopping_list::step_shopping_list_item_quantity(
+        &state.db_pool,
+        &authenticated_user_id,
+        &form_data.shopping_list_id,
+        form_data.product_id.as_str(),
+        step,
+    ).await;
+    if result.is_ok() {
+        let change = ShoppingListChange::QuantityChanged(form_data.product_id.as_str());
+        notify_collaborators(&state, &authenticated_user_id, &form_data.shopping_list_id, change).await;
+    }
+    render_shopping_list_item_quantity(result, form_data.shopping_list_id, form_data.product_id.as_str(), headers)
+}
+
+pub async fn set_shopping_list_item_quantity(
+    state: State<AppState>,
+    Extension(authenticated_user): Extension<Arc<Option<User>>>,
+    request: Request,
+) -> impl IntoResponse {
+    let mut headers = HeaderMap::new();
+
+    let authenticated_user_id = match authenticated_user.as_ref().as_ref().and_then(|user| *user.get_id()) {
+        Some(id) => id,
+        None => {
+            let notification = render_error_notification(None);
+            return (StatusCode::UNAUTHORIZED, headers, minify_html_response(&notification));
+        }
+    };
+
+    let form_data = match Form::<ShoppingListItemQuantityForm>::from_request(request, &state).await {
+        Ok(form_data) => form_data,
+        Err(e) => {
+            eprintln!("error in set_shopping_list_item_quantity {e:?}");
+            let notification = render_error_notification(Some("Ungültige Menge"));
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            return (StatusCode::BAD_REQUEST, headers, minify_html_response(&notification));
+        }
+    };
+    let quantity = match form_data.get_quantity() {
+        Some(quantity) => quantity,
+        None => {
+            let notification = render_error_notification(Some("Ungültige Menge"));
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            return (StatusCode::BAD_REQUEST, headers, minify_html_response(&notification));
+        }
+    };
+
+    let result = shopping_list::set_shopping_list_item_quantity(
+        &state.db_pool,
+        &authenticated_user_id,
+        &form_data.shopping_list_id,
+        form_data.product_id.as_str(),
+        &quantity,
+    ).await;
+    if result.is_ok() {
+        let change = ShoppingListChange::QuantityChanged(form_data.product_id.as_str());
+        notify_collaborators(&state, &authenticated_user_id, &form_data.shopping_list_id, change).await;
+    }
+    render_shopping_list_item_quantity(result, form_data.shopping_list_id, form_data.product_id.as_str(), headers)
+}
+
+// the quantity stepper of a list item, swapped in place of the old one
+fn render_shopping_list_item_quantity(
+    result: Result<ItemQuantity, sqlx::Error>,
+    shopping_list_id: i64,
+    product_id: &str,
+    mut headers: HeaderMap,
+) -> (StatusCode, HeaderMap, Html<String>) {
+    match result {
+        Ok(quantity) => {
+            let template = ShoppingListItemQuantityTemplate {
+                shopping_list_id,
+                product_id,
+                quantity: &quantity,
+                notification: None,
+            };
+            (StatusCode::OK, headers, minify_html_response(&template.render().unwrap_or_default()))
+        },
+        Err(sqlx::Error::RowNotFound) => {
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            let notification = render_error_notification(Some("Produkt ist nicht auf dem Einkaufszettel"));
+            (StatusCode::NOT_FOUND, headers, minify_html_response(&notification))
+        },
+        Err(sqlx::Error::PoolTimedOut) => {
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            (StatusCode::TOO_MANY_REQUESTS, headers, Html("".to_string()))
+        },
+        Err(e) => {
+            eprintln!("undefined error in controller::shopping_list::render_shopping_list_item_quantity {e:?}");
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            let notification = render_error_notification(Some("Menge konnte nicht gespeichert werden"));
+            (StatusCode::UNPROCESSABLE_ENTITY, headers, minify_html_response(&notification))
+        },
+    }
+}
+
+pub async fn get_shopping_mode_page(
+    state: State<AppState>,
+    Extension(authenticated_user): Extension<Arc<Option<User>>>,
+    path: Path<HashMap<String, String>>,
+    request: Request,
+) -> impl IntoResponse {
+    let authenticated_user_id = match authenticated_user.as_ref().as_ref().and_then(|user| *user.get_id()) {
+        Some(id) => id,
+        None => {
+            return (StatusCode::FORBIDDEN, Html("")).into_response();
+        }
+    };
+    let shopping_list_id = match get_value_from_path(&path, "id").parse::<i64>() {
+        Ok(id) => id,
+        Err(_) => {
+            return (StatusCode::BAD_REQUEST, Html("")).into_response();
+        }
+    };
+    let context = Context::new(request.uri(), request.headers());
+
+    let (shopping_list, checklist) = match try_join!(
+        shopping_list::find_shopping_list(&state.db_pool, &shopping_list_id, &authenticated_user_id),
+        shopping_list::find_shopping_list_checklist(&state.db_pool, &shopping_list_id, &authenticated_user_id),
+    ) {
+        Ok(val) => val,
+        Err(sqlx::Error::PoolTimedOut) => {
+            return (StatusCode::TOO_MANY_REQUESTS).into_response();
+        },
+        Err(e) => {
+            if !matches!(e, sqlx::Error::RowNotFound) {
+                eprintln!("undefined error in get_shopping_mode_page: {:?}", e);
+            }
+            return (StatusCode::TEMPORARY_REDIRECT, [("Location", "/einkaufszettel")]).into_response();
+        }
+    };
+
+    let template = ShoppingModeTemplate {
+        shopping_list: &shopping_list,
+        checklist: &checklist,
+        authenticated_user: &authenticated_user,
+        notification: None,
+        context,
+    };
+
+    (StatusCode::OK, minify_html_response(&template.render().unwrap_or_default())).into_response()
+}
+
+pub async fn check_shopping_list_item(
+    state: State<AppState>,
+    Extension(authenticated_user): Extension<Arc<Option<User>>>,
+    request: Request,
+) -> impl IntoResponse {
+    let mut headers = HeaderMap::new();
+
+    let authenticated_user_id = match authenticated_user.as_ref().as_ref().and_then(|user| *user.get_id()) {
+        Some(id) => id,
+        None => {
+            let notification = render_error_notification(None);
+            return (StatusCode::UNAUTHORIZED, headers, minify_html_response(&notification));
+        }
+    };
+
+    let form_data = match Form::<ShoppingListItemForm>::from_request(request, &state).await {
+        Ok(form_data) => form_data,
+        Err(e) => {
+            eprintln!("error in check_shopping_list_item {e:?}");
+            let notification = render_error_notification(None);
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            return (StatusCode::BAD_REQUEST, headers, minify_html_response(&notification));
+        }
+    };
+
+    if let Err(e) = shopping_list::toggle_shopping_list_item_check(
+        &state.db_pool,
+        &authenticated_user_id,
+        &form_data.shopping_list_id,
+        form_data.product_id.as_str(),
+    ).await {
+        let (status, message) = match e {
+            sqlx::Error::RowNotFound => (StatusCode::NOT_FOUND, "Produkt ist nicht auf dem Einkaufszettel"),
+            sqlx::Error::PoolTimedOut => (StatusCode::TOO_MANY_REQUESTS, "Bitte versuche es gleich noch einmal"),
+            e => {
+                eprintln!("undefined error in controller::shopping_list::check_shopping_list_item {e:?}");
+                (StatusCode::UNPROCESSABLE_ENTITY, "Produkt konnte nicht abgehakt werden")
+            },
+        };
+        headers.insert("hx-reswap", "none".parse().unwrap());
+        return (status, headers, minify_html_response(&render_error_notification(Some(message))));
+    }
+
+    render_shopping_list_checklist(&state, &authenticated_user_id, &form_data.shopping_list_id, None, headers).await
+}
+
+pub async fn finish_shopping_trip(
+    state: State<AppState>,
+    Extension(authenticated_user): Extension<Arc<Option<User>>>,
+    path: Path<HashMap<String, String>>,
+) -> impl IntoResponse {
+    let mut headers = HeaderMap::new();
+
+    let authenticated_user_id = match authenticated_user.as_ref().as_ref().and_then(|user| *user.get_id()) {
+        Some(id) => id,
+        None => {
+            let notification = render_error_notification(None);
+            return (StatusCode::UNAUTHORIZED, headers, minify_html_response(&notification));
+        }
+    };
+    let shopping_list_id = match get_value_from_path(&path, "id").parse::<i64>() {
+        Ok(id) => id,
+        Err(_) => {
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            return (StatusCode::UNPROCESSABLE_ENTITY, headers, Html("".to_string()));
+        }
+    };
+
+    let trip = match shopping_list::finish_shopping_trip(&state.db_pool, &authenticated_user_id, &shopping_list_id).await {
+        Ok(trip) => trip,
+        Err(sqlx::Error::RowNotFound) => {
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            let notification = render_error_notification(Some("Es ist noch nichts abgehakt"));
+            return (StatusCode::UNPROCESSABLE_ENTITY, headers, minify_html_response(&notification));
+        },
+        Err(e) => {
+            eprintln!("undefined error in controller::shopping_list::finish_shopping_trip {e:?}");
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            let notification = render_error_notification(Some("Einkauf konnte nicht abgeschlossen werden"));
+            return (StatusCode::UNPROCESSABLE_ENTITY, headers, minify_html_response(&notification));
+        },
+    };
+
+    let item_count = format!("{} {}", trip.item_count, if trip.item_count == 1 { "Eintrag" } else { "Einträge" });
+    notify_collaborators(&state, &authenticated_user_id, &shopping_list_id, ShoppingListChange::TripFinished(item_count.as_str())).await;
+
+    let message = format!("Einkauf abgeschlossen: {} in {}", item_count, trip.format_duration());
+    render_shopping_list_checklist(&state, &authenticated_user_id, &shopping_list_id, Some(message.as_str()), headers).await
+}
+
+// the notifications are only marked as read when the user says so, not by opening or prefetching the list
+pub async fn read_shopping_list_notifications(
+    state: State<AppState>,
+    Extension(authenticated_user): Extension<Arc<Option<User>>>,
+    path: Path<HashMap<String, String>>,
+) -> impl IntoResponse {
+    let mut headers = HeaderMap::new();
+
+    let authenticated_user_id = match authenticated_user.as_ref().as_ref().and_then(|user| *user.get_id()) {
+        Some(id) => id,
+        None => {
+            let notification = render_error_notification(None);
+            return (StatusCode::UNAUTHORIZED, headers, minify_html_response(&notification));
+        }
+    };
+    let shopping_list_id = match get_value_from_path(&path, "id").parse::<i64>() {
+        Ok(id) => id,
+        Err(_) => {
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            return (StatusCode::UNPROCESSABLE_ENTITY, headers, Html("".to_string()));
+        }
+    };
+
+    match shopping_list::read_shopping_list_notifications(&state.db_pool, &authenticated_user_id, &shopping_list_id).await {
+        // the panel is swapped out
+        Ok(_) => (StatusCode::OK, headers, Html("".to_string())),
+        Err(sqlx::Error::PoolTimedOut) => {
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            let notification = render_error_notification(Some("Bitte versuche es gleich noch einmal"));
+            (StatusCode::TOO_MANY_REQUESTS, headers, minify_html_response(&notification))
+        },
+        Err(e) => {
+            eprintln!("undefined error in controller::shopping_list::read_shopping_list_notifications {e:?}");
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            let notification = render_error_notification(Some("Neuigkeiten konnten nicht als gelesen markiert werden"));
+            (StatusCode::UNPROCESSABLE_ENTITY, headers, minify_html_response(&notification))
+        },
+    }
+}
+
+// the whole checklist, checking an item off moves it to the bottom
+async fn render_shopping_list_checklist(
+    state: &AppState,
+    authenticated_user_id: &i64,
+    shopping_list_id: &i64,
+    success_message: Option<&str>,
+    mut headers: HeaderMap,
+) -> (StatusCode, HeaderMap, Html<String>) {
+    match shopping_list::find_shopping_list_checklist(&state.db_pool, shopping_list_id, authenticated_user_id).await {
+        Ok(checklist) => {
+            let template = ShoppingListChecklistTemplate {
+                shopping_list_id: *shopping_list_id,
+                checklist: &checklist,
+                can_edit: true,
+                notification: success_message.map(|message| create_notification(message, true)),
+            };
+            (StatusCode::OK, headers, minify_html_response(&template.render().unwrap_or_default()))
+        },
+        Err(e) => {
+            eprintln!("error in controller::shopping_list::render_shopping_list_checklist {e:?}");
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            let notification = render_error_notification(None);
+            (StatusCode::UNPROCESSABLE_ENTITY, headers, minify_html_response(&notification))
+        },
+    }
+}
+
+pub async fn save_shopping_list_text_item(
+    state: State<AppState>,
+    Extension(authenticated_user): Extension<Arc<Option<User>>>,
+    request: Request,
+) -> impl IntoResponse {
+    let mut headers = HeaderMap::new();
+
+    let authenticated_user_id = match authenticated_user.as_ref().as_ref().and_then(|user| *user.get_id()) {
+        Some(id) => id,
+        None => {
+            let notification = render_error_notification(None);
+            return (StatusCode::UNAUTHORIZED, headers, minify_html_response(&notification));
+        }
+    };
+
+    let form_data = match Form::<AddShoppingListTextItemForm>::from_request(request, &state).await {
+        Ok(form_data) => form_data,
+        Err(e) => {
+            eprintln!("error in save_shopping_list_text_item {e:?}");
+            let notification = render_error_notification(None);
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            return (StatusCode::BAD_REQUEST, headers, minify_html_response(&notification));
+        }
+    };
+    let (name, quantity) = match (form_data.get_name(), form_data.get_quantity()) {
+        (Some(name), Ok(quantity)) => (name, quantity),
+        (None, _) => {
+            let notification = render_error_notification(Some("Bitte gib einen Eintrag ein"));
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            return (StatusCode::UNPROCESSABLE_ENTITY, headers, minify_html_response(&notification));
+        },
+        (_, Err(message)) => {
+            let notification = render_error_notification(Some(message.as_str()));
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            return (StatusCode::UNPROCESSABLE_ENTITY, headers, minify_html_response(&notification));
+        },
+    };
+
+    match shopping_list::add_shopping_list_text_item(
+        &state.db_pool,
+        &authenticated_user_id,
+        &form_data.shopping_list_id,
+        name,
+        quantity.as_ref(),
+        form_data.get_note(),
+    ).await {
+        Ok(text_item) => {
+            let change = ShoppingListChange::TextItemAdded(text_item.name.as_str());
+            notify_collaborators(&state, &authenticated_user_id, &form_data.shopping_list_id, change).await;
+
+            let message = format!("\"{}\" wurde hinzugefügt", text_item.name);
+            render_shopping_list_text_items(&state, &authenticated_user_id, &form_data.shopping_list_id, &message, headers).await
+        },
+        Err(sqlx::Error::RowNotFound) => {
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            let notification = render_error_notification(Some("Unerlaubter Zugriff"));
+            (StatusCode::UNAUTHORIZED, headers, minify_html_response(&notification))
+        },
+        Err(e) => {
+            eprintln!("undefined error in controller::shopping_list::save_shopping_list_text_item {e:?}");
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            let notification = render_error_notification(Some("Eintrag konnte nicht gespeichert werden"));
+            (StatusCode::UNPROCESSABLE_ENTITY, headers, minify_html_response(&notification))
+        },
+    }
+}
+
+pub async fn delete_shopping_list_text_item(
+    state: State<AppState>,
+    Extension(authenticated_user): Extension<Arc<Option<User>>>,
+    path: Path<HashMap<String, String>>,
+) -> impl IntoResponse {
+    let mut headers = HeaderMap::new();
+
+    let authenticated_user_id = match authenticated_user.as_ref().as_ref().and_then(|user| *user.get_id()) {
+        Some(id) => id,
+        None => {
+            let notification = render_error_notification(None);
+            return (StatusCode::UNAUTHORIZED, headers, minify_html_response(&notification));
+        }
+    };
+    let item_id = match get_value_from_path(&path, "id").parse::<i64>() {
+        Ok(id) => id,
+        Err(_) => {
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            return (StatusCode::UNPROCESSABLE_ENTITY, headers, Html("".to_string()));
+        }
+    };
+
+    match shopping_list::delete_shopping_list_text_item(&state.db_pool, &authenticated_user_id, &item_id).await {
+        Ok((shopping_list_id, name)) => {
+            notify_collaborators(&state, &authenticated_user_id, &shopping_list_id, ShoppingListChange::TextItemRemoved(name.as_str())).await;
+            render_shopping_list_text_items(&state, &authenticated_user_id, &shopping_list_id, "Eintrag wurde entfernt", headers).await
+        },
+        Err(sqlx::Error::RowNotFound) => {
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            let notification = render_error_notification(Some("Unerlaubter Zugriff"));
+            (StatusCode::UNAUTHORIZED, headers, minify_html_response(&notification))
+        },
+        Err(e) => {
+            eprintln!("undefined error in controller::shopping_list::delete_shopping_list_text_item {e:?}");
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            let notification = render_error_notification(Some("Eintrag konnte nicht entfernt werden"));
+            (StatusCode::UNPROCESSABLE_ENTITY, headers, minify_html_response(&notification))
+        },
+    }
+}
+
+pub async fn check_shopping_list_text_item(
+    state: State<AppState>,
+    Extension(authenticated_user): Extension<Arc<Option<User>>>,
+    request: Request,
+) -> impl IntoResponse {
+    let mut headers = HeaderMap::new();
+
+    let authenticated_user_id = match authenticated_user.as_ref().as_ref().and_then(|user| *user.get_id()) {
+        Some(id) => id,
+        None => {
+            let notification = render_error_notification(None);
+            return (StatusCode::UNAUTHORIZED, headers, minify_html_response(&notification));
+        }
+    };
+
+    let form_data = match Form::<ShoppingListTextItemForm>::from_request(request, &state).await {
+        Ok(form_data) => form_data,
+        Err(e) => {
+            eprintln!("error in check_shopping_list_text_item {e:?}");
+            let notification = render_error_notification(None);
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            return (StatusCode::BAD_REQUEST, headers, minify_html_response(&notification));
+        }
+    };
+
+    if let Err(e) = shopping_list::toggle_shopping_list_text_item_check(
+        &state.db_pool,
+        &authenticated_user_id,
+        &form_data.shopping_list_id,
+        &form_data.item_id,
+    ).await {
+        let (status, message) = match e {
+            sqlx::Error::RowNotFound => (StatusCode::NOT_FOUND, "Eintrag ist nicht auf dem Einkaufszettel"),
+            sqlx::Error::PoolTimedOut => (StatusCode::TOO_MANY_REQUESTS, "Bitte versuche es gleich noch einmal"),
+            e => {
+                eprintln!("undefined error in controller::shopping_list::check_shopping_list_text_item {e:?}");
+                (StatusCode::UNPROCESSABLE_ENTITY, "Eintrag konnte nicht abgehakt werden")
+            },
+        };
+        headers.insert("hx-reswap", "none".parse().unwrap());
+        return (status, headers, minify_html_response(&render_error_notification(Some(message))));
+    }
+
+    render_shopping_list_checklist(&state, &authenticated_user_id, &form_data.shopping_list_id, None, headers).await
+}
+
+pub async fn get_shopping_list_text_item_suggestions(
+    state: State<AppState>,
+    Extension(authenticated_user): Extension<Arc<Option<User>>>,
+    Query(item): Query<ShoppingListTextItemForm>,
+) -> impl IntoResponse {
+    let authenticated_user_id = match authenticated_user.as_ref().as_ref().and_then(|user| *user.get_id()) {
+        Some(id) => id,
+        None => {
+            return (StatusCode::UNAUTHORIZED, Html("".to_string())).into_response();
+        }
+    };
+
+    let text_item = match shopping_list::find_shopping_list_text_item(
+        &state.db_pool,
+        &authenticated_user_id,
+        &item.shopping_list_id,
+        &item.item_id,
+    ).await {
+        Ok(text_item) => text_item,
+        Err(sqlx::Error::RowNotFound) => {
+            return (StatusCode::NOT_FOUND, Html("".to_string())).into_response();
+        },
+        Err(e) => {
+            eprintln!("error in controller::shopping_list::get_shopping_list_text_item_suggestions {e:?}");
+            return (StatusCode::UNPROCESSABLE_ENTITY, Html("".to_string())).into_response();
+        },
+    };
+    let products = match product::find_products_by_text(&state.db_pool, text_item.name.as_str(), TEXT_ITEM_SUGGESTION_LIMIT).await {
+        Ok(products) => products,
+        Err(e) => {
+            eprintln!("error while finding products in get_shopping_list_text_item_suggestions {e:?}");
+            vec![]
+        },
+    };
+
+    let template = ShoppingListTextItemSuggestionsTemplate {
+        shopping_list_id: item.shopping_list_id,
+        text_item: &text_item,
+        products: &products,
+    };
+    (StatusCode::OK, minify_html_response(&template.render().unwrap_or_default())).into_response()
+}
+
+pub async fn link_shopping_list_text_item(
+    state: State<AppState>,
+    Extension(authenticated_user): Extension<Arc<Option<User>>>,
+    request: Request,
+) -> impl IntoResponse {
+    let mut headers = HeaderMap::new();
+    headers.insert("hx-reswap", "none".parse().unwrap());
+
+    let authenticated_user_id = match authenticated_user.as_ref().as_ref().and_then(|user| *user.get_id()) {
+        Some(id) => id,
+        None => {
+            let notification = render_error_notification(None);
+            return (StatusCode::UNAUTHORIZED, headers, minify_html_response(&notification));
+        }
+    };
+
+    let form_data = match Form::<LinkShoppingListTextItemForm>::from_request(request, &state).await {
+        Ok(form_data) => form_data,
+        Err(e) => {
+            eprintln!("error in link_shopping_list_text_item {e:?}");
+            let notification = render_error_notification(None);
+            return (StatusCode::BAD_REQUEST, headers, minify_html_response(&notification));
+        }
+    };
+
+    match shopping_list::link_shopping_list_text_item(
+        &state.db_pool,
+        &authenticated_user_id,
+        &form_data.shopping_list_id,
+        &form_data.item_id,
+        form_data.product_id.as_str(),
+    ).await {
+        Ok(product_id) => {
+            let change = ShoppingListChange::ProductAdded(product_id.as_str());
+            notify_collaborators(&state, &authenticated_user_id, &form_data.shopping_list_id, change).await;
+
+            // the item moves from the free text items to the paged product list
+            headers.insert("xui-redirect", format!("/einkaufszettel/{}", form_data.shopping_list_id).parse().unwrap());
+            let notification = render_success_notification(Some("Produkt wurde zugeordnet"));
+            (StatusCode::TEMPORARY_REDIRECT, headers, minify_html_response(&notification))
+        },
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            let notification = render_error_notification(Some("Produkt ist bereits auf dem Einkaufszettel"));
+            (StatusCode::UNPROCESSABLE_ENTITY, headers, minify_html_response(&notification))
+        },
+        Err(sqlx::Error::RowNotFound) => {
+            let notification = render_error_notification(Some("Unerlaubter Zugriff"));
+            (StatusCode::UNAUTHORIZED, headers, minify_html_response(&notification))
+        },
+        Err(e) => {
+            eprintln!("undefined error in controller::shopping_list::link_shopping_list_text_item {e:?}");
+            let notification = render_error_notification(Some("Produkt konnte nicht zugeordnet werden"));
+            (StatusCode::UNPROCESSABLE_ENTITY, headers, minify_html_response(&notification))
+        },
+    }
+}
+
+async fn render_shopping_list_text_items(
+    state: &AppState,
+    authenticated_user_id: &i64,
+    shopping_list_id: &i64,
+    success_message: &str,
+    mut headers: HeaderMap,
+) -> (StatusCode, HeaderMap, Html<String>) {
+    match shopping_list::find_shopping_list_text_items(&state.db_pool, shopping_list_id, authenticated_user_id).await {
+        Ok(text_items) => {
+            let template = ShoppingListTextItemsTemplate {
+                shopping_list_id: *shopping_list_id,
+                can_edit: true,
+                text_items: &text_items,
+                notification: Some(create_notification(success_message, true)),
+            };
+            (StatusCode::OK, headers, minify_html_response(&template.render().unwrap_or_default()))
+        },
+        Err(e) => {
+            eprintln!("error in controller::shopping_list::render_shopping_list_text_items {e:?}");
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            let notification = render_error_notification(None);
+            (StatusCode::UNPROCESSABLE_ENTITY, headers, minify_html_response(&notification))
+        },
+    }
+}
+
+pub async fn save_shopping_list_member(
+    state: State<AppState>,
+    Extension(authenticated_user): Extension<Arc<Option<User>>>,
+    request: Request,
+) -> impl IntoResponse {
+    let mut headers = HeaderMap::new();
+
+    let authenticated_user_id = match authenticated_user.as_ref().as_ref().and_then(|user| *user.get_id()) {
+        Some(id) => id,
+        None => {
+            let notification = render_error_notification(None);
+            return (StatusCode::UNAUTHORIZED, headers, minify_html_response(&notification));
+        }
+    };
+
+    let form_data = match Form::<ShareShoppingListForm>::from_request(request, &state).await {
+        Ok(form_data) => form_data,
+        Err(e) => {
+            eprintln!("error in save_shopping_list_member {e:?}");
+            let notification = render_error_notification(None);
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            return (StatusCode::BAD_REQUEST, headers, minify_html_response(&notification));
+        }
+    };
+    let role = match form_data.get_role() {
+        Some(role) => role,
+        None => {
+            let notification = render_error_notification(Some("Ungültige Rolle"));
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            return (StatusCode::BAD_REQUEST, headers, minify_html_response(&notification));
+        }
+    };
+
+    match shopping_list::upsert_shopping_list_member(
+        &state.db_pool,
+        &authenticated_user_id,
+        &form_data.shopping_list_id,
+        &form_data.user_id,
+        role,
+    ).await {
+        Ok(member) => {
+            let change = ShoppingListChange::MemberAdded(member.email.as_str());
+            notify_collaborators(&state, &authenticated_user_id, &form_data.shopping_list_id, change).await;
+
+            let message = format!("Einkaufszettel mit {} geteilt", member.email);
+            render_shopping_list_members(&state, &authenticated_user_id, &form_data.shopping_list_id, &message, headers).await
+        },
+        // not the owner or not a confirmed contact
+        Err(sqlx::Error::RowNotFound) => {
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            let notification = render_error_notification(Some("Unerlaubter Zugriff"));
+            (StatusCode::UNAUTHORIZED, headers, minify_html_response(&notification))
+        },
+        Err(e) => {
+            eprintln!("undefined error in controller::shopping_list::save_shopping_list_member {e:?}");
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            let notification = render_error_notification(Some("Einkaufszettel konnte nicht geteilt werden"));
+            (StatusCode::UNPROCESSABLE_ENTITY, headers, minify_html_response(&notification))
+        },
+    }
+}
+
+// the owner removing a member or a member leaving the list
+pub async fn delete_shopping_list_member(
+    state: State<AppState>,
+    Extension(authenticated_user): Extension<Arc<Option<User>>>,
+    Path(member): Path<ShoppingListMemberForm>,
+) -> impl IntoResponse {
+    let mut headers = HeaderMap::new();
+
+    let authenticated_user_id = match authenticated_user.as_ref().as_ref().and_then(|user| *user.get_id()) {
+        Some(id) => id,
+        None => {
+            let notification = render_error_notification(None);
+            return (StatusCode::UNAUTHORIZED, headers, minify_html_response(&notification));
+        }
+    };
+
+    match shopping_list::delete_shopping_list_member(
+        &state.db_pool,
+        &authenticated_user_id,
+        &member.shopping_list_id,
+        &member.user_id,
+    ).await {
+        Ok(user_id) if user_id == authenticated_user_id => {
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            headers.insert("xui-redirect", "/einkaufszettel".parse().unwrap());
+            let notification = render_success_notification(Some("Einkaufszettel wird nicht mehr mit dir geteilt"));
+            (StatusCode::TEMPORARY_REDIRECT, headers, minify_html_response(&notification))
+        },
+        Ok(_) => {
+            render_shopping_list_members(&state, &authenticated_user_id, &member.shopping_list_id, "Wird nicht mehr geteilt", headers).await
+        },
+        Err(sqlx::Error::RowNotFound) => {
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            let notification = render_error_notification(Some("Unerlaubter Zugriff"));
+            (StatusCode::UNAUTHORIZED, headers, minify_html_response(&notification))
+        },
+        Err(e) => {
+            eprintln!("undefined error in controller::shopping_list::delete_shopping_list_member {e:?}");
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            let notification = render_error_notification(Some("Mitglied konnte nicht entfernt werden"));
+            (StatusCode::UNPROCESSABLE_ENTITY, headers, minify_html_response(&notification))
+        },
+    }
+}
+
+// the members section of the owner
+async fn render_shopping_list_members(
+    state: &AppState,
+    authenticated_user_id: &i64,
+    shopping_list_id: &i64,
+    success_message: &str,
+    mut headers: HeaderMap,
+) -> (StatusCode, HeaderMap, Html<String>) {
+    match try_join!(
+        shopping_list::find_shopping_list_members(&state.db_pool, authenticated_user_id, shopping_list_id),
+        find_contacts(&state.db_pool, authenticated_user_id),
+    ) {
+        Ok((members, contacts)) => {
+            let template = ShoppingListMembersTemplate {
+                shopping_list_id: *shopping_list_id,
+                is_owner: true,
+                members: &members,
+                contacts: &contacts,
+                notification: Some(create_notification(success_message, true)),
+            };
+            (StatusCode::OK, headers, minify_html_response(&template.render().unwrap_or_default()))
+        },
+        Err(e) => {
+            eprintln!("error in controller::shopping_list::render_shopping_list_members {e:?}");
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            let notification = render_error_notification(None);
+            (StatusCode::UNPROCESSABLE_ENTITY, headers, minify_html_response(&notification))
+        },
+    }
+}
+
+// duplicates a list, saves it as a template or creates a list from a template
+pub async fn copy_shopping_list(
+    state: State<AppState>,
+    Extension(authenticated_user): Extension<Arc<Option<User>>>,
+    request: Request,
+) -> impl IntoResponse {
+    let mut headers = HeaderMap::new();
+
+    let authenticated_user_id = match authenticated_user.as_ref().as_ref().and_then(|user| *user.get_id()) {
+        Some(id) => id,
+        None => {
+            let notification = render_error_notification(None);
+            return (StatusCode::UNAUTHORIZED, headers, minify_html_response(&notification));
+        }
+    };
+
+    let form_data = match Form::<CopyShoppingListForm>::from_request(request, &state).await {
+        Ok(form_data) => form_data,
+        Err(e) => {
+            eprintln!("error in copy_shopping_list {e:?}");
+            let notification = render_error_notification(None);
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            return (StatusCode::BAD_REQUEST, headers, minify_html_response(&notification));
+        }
+    };
+
+    let result = match shopping_list::find_shopping_list(&state.db_pool, &form_data.shopping_list_id, &authenticated_user_id).await {
+        Ok(source) => {
+            let name = source.get_copy_name(form_data.as_template, Local::now().date_naive());
+            shopping_list::copy_shopping_list(
+                &state.db_pool,
+                &authenticated_user_id,
+                &form_data.shopping_list_id,
+                name.as_str(),
+                form_data.as_template,
+            ).await.map(|id| (id, name))
+        },
+        Err(e) => Err(e),
+    };
+
+    headers.insert("hx-reswap", "none".parse().unwrap());
+    match result {
+        Ok((id, name)) => {
+            headers.insert("xui-redirect", format!("/einkaufszettel/{}", id).parse().unwrap());
+            let message = if form_data.as_template {
+                format!("Vorlage \"{}\" angelegt", name)
+            } else {
+                format!("Einkaufszettel \"{}\" angelegt", name)
+            };
+            (StatusCode::TEMPORARY_REDIRECT, headers, minify_html_response(&render_success_notification(Some(message.as_str()))))
+        },
+        Err(sqlx::Error::RowNotFound) => {
+            let notification = render_error_notification(Some("Unerlaubter Zugriff"));
+            (StatusCode::UNAUTHORIZED, headers, minify_html_response(&notification))
+        },
+        Err(e) => {
+            eprintln!("undefined error in controller::shopping_list::copy_shopping_list {e:?}");
+            let notification = render_error_notification(Some("Einkaufszettel konnte nicht kopiert werden"));
+            (StatusCode::UNPROCESSABLE_ENTITY, headers, minify_html_response(&notification))
+        },
+    }
+}
+
+pub async fn save_shopping_list_recurrence(
+    state: State<AppState>,
+    Extension(authenticated_user): Extension<Arc<Option<User>>>,
+    request: Request,
+) -> impl IntoResponse {
+    let mut headers = HeaderMap::new();
+
+    let authenticated_user_id = match authenticated_user.as_ref().as_ref().and_then(|user| *user.get_id()) {
+        Some(id) => id,
+        None => {
+            let notification = render_error_notification(None);
+            return (StatusCode::UNAUTHORIZED, headers, minify_html_response(&notification));
+        }
+    };
+
+    let form_data = match Form::<ShoppingListRecurrenceForm>::from_request(request, &state).await {
+        Ok(form_data) => form_data,
+        Err(e) => {
+            eprintln!("error in save_shopping_list_recurrence {e:?}");
+            let notification = render_error_notification(None);
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            return (StatusCode::BAD_REQUEST, headers, minify_html_response(&notification));
+        }
+    };
+    let weekday = match form_data.get_weekday() {
+        Some(weekday) => weekday,
+        None => {
+            let notification = render_error_notification(Some("Ungültiger Wochentag"));
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            return (StatusCode::BAD_REQUEST, headers, minify_html_response(&notification));
+        }
+    };
+
+    let result = match shopping_list::update_shopping_list_recurrence(
+        &state.db_pool,
+        &authenticated_user_id,
+        &form_data.shopping_list_id,
+        weekday,
+    ).await {
+        Ok(_) => shopping_list::find_shopping_list(&state.db_pool, &form_data.shopping_list_id, &authenticated_user_id).await,
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(updated_shopping_list) => {
+            let message = match updated_shopping_list.get_recurrence_label() {
+                Some(recurrence_label) => format!("Wird ab jetzt automatisch angelegt: {}", recurrence_label),
+                None => "Wird nicht mehr automatisch angelegt".to_string(),
+            };
+            let template = ShoppingListCopyTemplate {
+                shopping_list: &updated_shopping_list,
+                notification: Some(create_notification(message.as_str(), true)),
+            };
+            (StatusCode::OK, headers, minify_html_response(&template.render().unwrap_or_default()))
+        },
+        // not the owner or not a template
+        Err(sqlx::Error::RowNotFound) => {
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            let notification = render_error_notification(Some("Unerlaubter Zugriff"));
+            (StatusCode::UNAUTHORIZED, headers, minify_html_response(&notification))
+        },
+        Err(e) => {
+            eprintln!("undefined error in controller::shopping_list::save_shopping_list_recurrence {e:?}");
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            let notification = render_error_notification(Some("Wiederholung konnte nicht gespeichert werden"));
+            (StatusCode::UNPROCESSABLE_ENTITY, headers, minify_html_response(&notification))
+        },
+    }
+}
+
+// a failed notification doesn't fail the change it's about
+async fn notify_collaborators(
+    state: &AppState,
+    authenticated_user_id: &i64,
+    shopping_list_id: &i64,
+    change: ShoppingListChange<'_>,
+) {
+    if let Err(e) = shopping_list::notify_shopping_list_collaborators(&state.db_pool, authenticated_user_id, shopping_list_id, change).await {
+        eprintln!("error while notifying the collaborators of shopping list {shopping_list_id}: {e:?}");
+    }
+}
+
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/einkaufszettel/:id", get(get_shopping_list_detail_page))
         .route("/einkaufszettel/create", get(get_shopping_list_detail_page))
+        .route("/einkaufszettel/:id/einkaufen", get(get_shopping_mode_page))
+        .route("/einkaufszettel/:id/drucken", get(get_shopping_list_print_page))
         .route("/einkaufszettel", get(get_shopping_lists))
         .route("/shopping_list/delete/:id", delete(delete_shopping_list))
         // TODO move from PUT to PATCH and enable partial updates
         .route("/shopping_list/save", put(save_shopping_list))
         .route("/shopping_list/toggle-like", post(save_shopping_list_item))
+        .route("/shopping_list/item/increment", post(increment_shopping_list_item))
+        .route("/shopping_list/item/decrement", post(decrement_shopping_list_item))
+        .route("/shopping_list/item/quantity", put(set_shopping_list_item_quantity))
+        .route("/shopping_list/item/check", post(check_shopping_list_item))
+        .route("/shopping_list/item/order", put(save_shopping_list_item_order))
+        .route("/shopping_list/finish_trip/:id", post(finish_shopping_trip))
+        .route("/shopping_list/notifications/read/:id", post(read_shopping_list_notifications))
+        .route("/shopping_list/text_item/save", post(save_shopping_list_text_item))
+        .route("/shopping_list/text_item/delete/:id", delete(delete_shopping_list_text_item))
+        .route("/shopping_list/text_item/check", post(check_shopping_list_text_item))
+        .route("/shopping_list/text_item/suggestions", get(get_shopping_list_text_item_suggestions))
+        .route("/shopping_list/text_item/link", post(link_shopping_list_text_item))
+        .route("/shopping_list/member/save", post(save_shopping_list_member))
+        .route("/shopping_list/member/delete/:shopping_list_id/:user_id", delete(delete_shopping_list_member))
+        .route("/shopping_list/events/:id", get(get_shopping_list_events))
+        .route("/shopping_list/copy", post(copy_shopping_list))
+        .route("/shopping_list/recurrence", put(save_shopping_list_recurrence))
+        .route("/shopping_list/grouping", put(save_shopping_list_grouping))
+        .route("/shopping_list/export/:id", get(export_shopping_list))
+        .route("/shopping_list/import/preview", post(preview_shopping_list_import))
+        .route("/shopping_list/import/save", post(save_shopping_list_import))
+        .route("/shopping_list/pdf/:id", get(get_shopping_list_pdf))
 }
\ No newline at end of file
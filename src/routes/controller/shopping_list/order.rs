@@ -0,0 +1,150 @@
+use std::sync::Arc;
+use askama::Template;
+use axum::{
+    extract::{FromRequest, Request, State},
+    http::{HeaderMap, StatusCode},
+    response::{Html, IntoResponse},
+    Extension, Form,
+};
+use futures::try_join;
+
+use crate::{
+    model::{
+        shopping_list::{grouping::ShoppingListGroupingForm, order::ShoppingListItemOrderForm},
+        user::User,
+    },
+    routes::{create_notification, minify_html_response, render_error_notification},
+    services::{shopping_list, store::find_stores_with_aisles},
+    view::shopping_list::ShoppingListGroupingTemplate,
+    AppState,
+};
+
+// the items of a page after one was dragged to another place, see the Sortable element
+pub async fn save_shopping_list_item_order(
+    state: State<AppState>,
+    Extension(authenticated_user): Extension<Arc<Option<User>>>,
+    request: Request,
+) -> impl IntoResponse {
+    let mut headers = HeaderMap::new();
+    // the items are already where they were dropped
+    headers.insert("hx-reswap", "none".parse().unwrap());
+
+    let authenticated_user_id = match authenticated_user.as_ref().as_ref().and_then(|user| *user.get_id()) {
+        Some(id) => id,
+        None => {
+            let notification = render_error_notification(None);
+            return (StatusCode::UNAUTHORIZED, headers, minify_html_response(&notification));
+        }
+    };
+
+    // the ids repeat, see ShoppingListItemOrderForm
+    let form_data = match Form::<Vec<(String, String)>>::from_request(request, &state).await {
+        Ok(Form(pairs)) => ShoppingListItemOrderForm::from_pairs(&pairs),
+        Err(e) => {
+            eprintln!("error in save_shopping_list_item_order {e:?}");
+            None
+        }
+    };
+    let form_data = match form_data {
+        Some(form_data) if !form_data.is_empty() => form_data,
+        _ => {
+            let notification = render_error_notification(None);
+            return (StatusCode::BAD_REQUEST, headers, minify_html_response(&notification));
+        }
+    };
+
+    match shopping_list::update_shopping_list_item_order(&state.db_pool, &authenticated_user_id, &form_data).await {
+        Ok(_) => (StatusCode::OK, headers, Html("".to_string())),
+        Err(sqlx::Error::RowNotFound) => {
+            let notification = render_error_notification(Some("Unerlaubter Zugriff"));
+            (StatusCode::UNAUTHORIZED, headers, minify_html_response(&notification))
+        },
+        Err(sqlx::Error::PoolTimedOut) => {
+            let notification = render_error_notification(Some("Bitte versuche es gleich noch einmal"));
+            (StatusCode::TOO_MANY_REQUESTS, headers, minify_html_response(&notification))
+        },
+        Err(e) => {
+            eprintln!("undefined error in controller::shopping_list::save_shopping_list_item_order {e:?}");
+            let notification = render_error_notification(Some("Reihenfolge konnte nicht gespeichert werden"));
+            (StatusCode::UNPROCESSABLE_ENTITY, headers, minify_html_response(&notification))
+        },
+    }
+}
+
+// how the list is put under headings while shopping and the store whose aisles it follows
+pub async fn save_shopping_list_grouping(
+    state: State<AppState>,
+    Extension(authenticated_user): Extension<Arc<Option<User>>>,
+    request: Request,
+) -> impl IntoResponse {
+    let mut headers = HeaderMap::new();
+
+    let authenticated_user_id = match authenticated_user.as_ref().as_ref().and_then(|user| *user.get_id()) {
+        Some(id) => id,
+        None => {
+            let notification = render_error_notification(None);
+            return (StatusCode::UNAUTHORIZED, headers, minify_html_response(&notification));
+        }
+    };
+
+    let form_data = match Form::<ShoppingListGroupingForm>::from_request(request, &state).await {
+        Ok(form_data) => form_data,
+        Err(e) => {
+            eprintln!("error in save_shopping_list_grouping {e:?}");
+            let notification = render_error_notification(None);
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            return (StatusCode::BAD_REQUEST, headers, minify_html_response(&notification));
+        }
+    };
+    let (grouping, store_id) = match (form_data.get_grouping(), form_data.get_store_id()) {
+        (Some(grouping), Some(store_id)) => (grouping, store_id),
+        _ => {
+            let notification = render_error_notification(Some("Ungültige Sortierung"));
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            return (StatusCode::BAD_REQUEST, headers, minify_html_response(&notification));
+        }
+    };
+
+    let result = match shopping_list::update_shopping_list_grouping(
+        &state.db_pool,
+        &authenticated_user_id,
+        &form_data.shopping_list_id,
+        grouping,
+        store_id,
+    ).await {
+        Ok(_) => try_join!(
+            shopping_list::find_shopping_list(&state.db_pool, &form_data.shopping_list_id, &authenticated_user_id),
+            find_stores_with_aisles(&state.db_pool, &authenticated_user_id),
+        ),
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok((updated_shopping_list, stores)) => {
+            let template = ShoppingListGroupingTemplate {
+                shopping_list: &updated_shopping_list,
+                stores: &stores,
+                notification: Some(create_notification("Sortierung gespeichert", true)),
+            };
+            (StatusCode::OK, headers, minify_html_response(&template.render().unwrap_or_default()))
+        },
+        // a viewer
+        Err(sqlx::Error::RowNotFound) => {
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            let notification = render_error_notification(Some("Unerlaubter Zugriff"));
+            (StatusCode::UNAUTHORIZED, headers, minify_html_response(&notification))
+        },
+        // a store that doesn't exist
+        Err(sqlx::Error::Database(e)) if e.is_foreign_key_violation() => {
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            let notification = render_error_notification(Some("Markt nicht gefunden"));
+            (StatusCode::UNPROCESSABLE_ENTITY, headers, minify_html_response(&notification))
+        },
+        Err(e) => {
+            eprintln!("undefined error in controller::shopping_list::save_shopping_list_grouping {e:?}");
+            headers.insert("hx-reswap", "none".parse().unwrap());
+            let notification = render_error_notification(Some("Sortierung konnte nicht gespeichert werden"));
+            (StatusCode::UNPROCESSABLE_ENTITY, headers, minify_html_response(&notification))
+        },
+    }
+}
@@ -0,0 +1,105 @@
+use std::{collections::HashMap, sync::Arc};
+use askama::Template;
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{Html, IntoResponse, Response},
+    Extension,
+};
+use chrono::Local;
+use futures::try_join;
+
+use crate::{
+    model::{
+        shopping_list::print::{ShoppingListPrint, ShoppingListPrintParams},
+        user::User,
+    },
+    routes::{get_value_from_path, minify_html_response},
+    services::{category::find_category_tree, shopping_list},
+    view::shopping_list::{pdf::render_shopping_list_pdf, ShoppingListPrintTemplate},
+    AppState,
+};
+
+pub async fn get_shopping_list_print_page(
+    state: State<AppState>,
+    Extension(authenticated_user): Extension<Arc<Option<User>>>,
+    path: Path<HashMap<String, String>>,
+    Query(params): Query<ShoppingListPrintParams>,
+) -> impl IntoResponse {
+    let print = match find_shopping_list_print(&state, &authenticated_user, &path, &params).await {
+        Ok(print) => print,
+        Err(response) => return response,
+    };
+
+    let template = ShoppingListPrintTemplate { print: &print };
+    (StatusCode::OK, minify_html_response(&template.render().unwrap_or_default())).into_response()
+}
+
+// the print view as a download, for printing elsewhere or keeping it on the phone
+pub async fn get_shopping_list_pdf(
+    state: State<AppState>,
+    Extension(authenticated_user): Extension<Arc<Option<User>>>,
+    path: Path<HashMap<String, String>>,
+    Query(params): Query<ShoppingListPrintParams>,
+) -> impl IntoResponse {
+    let print = match find_shopping_list_print(&state, &authenticated_user, &path, &params).await {
+        Ok(print) => print,
+        Err(response) => return response,
+    };
+
+    let mut headers = HeaderMap::new();
+    headers.insert(header::CONTENT_TYPE, "application/pdf".parse().unwrap());
+    headers.insert(
+        header::CONTENT_DISPOSITION,
+        format!("attachment; filename=\"{}\"", print.get_pdf_file_name()).parse().unwrap(),
+    );
+    (StatusCode::OK, headers, render_shopping_list_pdf(&print)).into_response()
+}
+
+async fn find_shopping_list_print(
+    state: &AppState,
+    authenticated_user: &Arc<Option<User>>,
+    path: &Path<HashMap<String, String>>,
+    params: &ShoppingListPrintParams,
+) -> Result<ShoppingListPrint, Response> {
+    let authenticated_user_id = match authenticated_user.as_ref().as_ref().and_then(|user| *user.get_id()) {
+        Some(id) => id,
+        None => {
+            return Err((StatusCode::FORBIDDEN, Html("")).into_response());
+        }
+    };
+    let shopping_list_id = match get_value_from_path(path, "id").parse::<i64>() {
+        Ok(id) => id,
+        Err(_) => {
+            return Err((StatusCode::BAD_REQUEST, Html("")).into_response());
+        }
+    };
+    let grouping = match params.get_grouping() {
+        Some(grouping) => grouping,
+        None => {
+            return Err((StatusCode::BAD_REQUEST, Html("Unbekannte Gruppierung")).into_response());
+        }
+    };
+
+    match try_join!(
+        shopping_list::find_shopping_list(&state.db_pool, &shopping_list_id, &authenticated_user_id),
+        shopping_list::find_shopping_list_checklist(&state.db_pool, &shopping_list_id, &authenticated_user_id),
+        shopping_list::find_shopping_list_costs(&state.db_pool, &authenticated_user_id, std::slice::from_ref(&shopping_list_id)),
+        find_category_tree(&state.db_pool),
+    ) {
+        Ok((shopping_list, checklist, mut costs, categories)) => {
+            let cost = costs.remove(&shopping_list_id).unwrap_or_default();
+            Ok(ShoppingListPrint::new(&shopping_list, &checklist, &categories, grouping, cost, Local::now()))
+        },
+        Err(sqlx::Error::RowNotFound) => {
+            Err((StatusCode::TEMPORARY_REDIRECT, [("Location", "/einkaufszettel")]).into_response())
+        },
+        Err(sqlx::Error::PoolTimedOut) => {
+            Err((StatusCode::TOO_MANY_REQUESTS).into_response())
+        },
+        Err(e) => {
+            eprintln!("undefined error in controller::shopping_list::find_shopping_list_print {e:?}");
+            Err((StatusCode::UNPROCESSABLE_ENTITY, Html("")).into_response())
+        },
+    }
+}
@@ -2,29 +2,32 @@ use std::sync::Arc;
 use std::collections::HashMap;
 use askama::Template;
 use axum::{
-    extract::{Path, State, Query, Request},
+    extract::{Path, Request},
     http::StatusCode,
-    response::IntoResponse,
+    response::{Html, IntoResponse},
     routing::get,
     Extension,
-    RequestExt,
     Router,
 };
 
-use crate::{core::{context::Context, query_params::StateParams, pagination::Pagination}, AppState};
+use crate::{core::{context::Context, query_params::StateParams, pagination::{Pagination, DEFAULT_PAGE_LIMITS}}, AppState};
 use crate::routes::{minify_html_response, get_value_from_path};
 use crate::model::{user::User, social_timeline::Post};
 use crate::view::social_timeline::{PostDetailTemplate, PostListTemplate};
 
 pub async fn get_post_list(
-    State(state): State<AppState>,
+    query_params: StateParams,
     Extension(authenticated_user): Extension<Arc<Option<User>>>,
-    mut request: Request,
+    request: Request,
 ) -> impl IntoResponse {
-    let query_params: Query<StateParams> = request.extract_parts_with_state::<Query::<StateParams>, _>(&state).await.unwrap();
     
     let context = Context::new(request.uri(), request.headers());
-    let pagination = Pagination::from_query_params(&query_params).with_uri(request.uri().clone());
+    let pagination = match Pagination::from_query_params(&query_params, &DEFAULT_PAGE_LIMITS) {
+        Ok(pagination) => pagination.with_uri(request.uri().clone()),
+        Err(e) => {
+            return (StatusCode::BAD_REQUEST, Html(e.get_message())).into_response();
+        }
+    };
 
     let posts = vec![Post {id: 1}, Post {id: 545}];
 
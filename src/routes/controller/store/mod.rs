@@ -0,0 +1,180 @@
+use std::{collections::HashMap, sync::Arc};
+use askama::Template;
+use axum::{
+    extract::{Extension, FromRequest, Path, Query, Request, State},
+    http::{HeaderMap, StatusCode},
+    response::{Html, IntoResponse},
+    routing::{get, put},
+    Form, Router,
+};
+use futures::try_join;
+
+use crate::{
+    core::{context::Context, pagination::{PageLimits, Pagination}, query_params::{StateParams, StoreSearchParams}},
+    model::{store::aisle::StoreAislesForm, user::User},
+    routes::{get_value_from_path, minify_html_response, render_error_notification, render_success_notification},
+    services::{
+        category::find_category_tree,
+        store::{find_store, find_store_aisles, find_stores_near, save_store_aisles as save_aisles, DEFAULT_RADIUS_IN_METERS},
+    },
+    view::store::{StoreAislesTemplate, StoreListTemplate},
+    AppState,
+};
+
+const PAGE_LIMITS: PageLimits = PageLimits::new(10, 25);
+
+pub async fn get_stores_near_page(
+    query_params: StateParams,
+    Query(store_search_params): Query<StoreSearchParams>,
+    state: State<AppState>,
+    Extension(authenticated_user): Extension<Arc<Option<User>>>,
+    request: Request,
+) -> impl IntoResponse {
+    let user = authenticated_user.as_ref().as_ref().expect("get_stores_near_page is an auth protected route");
+    let radius_in_meters = store_search_params.radius.unwrap_or(DEFAULT_RADIUS_IN_METERS);
+    let pagination = match Pagination::from_query_params(&query_params, &PAGE_LIMITS) {
+        Ok(pagination) => pagination.with_uri(request.uri().clone()),
+        Err(e) => {
+            return (StatusCode::BAD_REQUEST, Html(e.get_message())).into_response();
+        }
+    };
+
+    let (stores, total) = match (user.address_lng, user.address_lat) {
+        (Some(lng), Some(lat)) => {
+            match find_stores_near(&state.db_pool, lng, lat, radius_in_meters, pagination.limit, pagination.get_offset()).await {
+                Ok(val) => val,
+                Err(e) => {
+                    eprintln!("unexpected error in controller::store::get_stores_near_page {e:?}");
+                    return (StatusCode::INTERNAL_SERVER_ERROR, Html("".to_string())).into_response();
+                }
+            }
+        },
+        _ => (vec![], 0),
+    };
+
+    let pagination = pagination.with_total(total);
+
+    let template = StoreListTemplate {
+        stores,
+        radius_in_meters,
+        has_location: user.address_lng.is_some() && user.address_lat.is_some(),
+        authenticated_user: &authenticated_user,
+        notification: None,
+        pagination: &pagination,
+        context: Context::from_request(&request),
+    };
+
+    (StatusCode::OK, minify_html_response(&template.render().unwrap_or_default())).into_response()
+}
+
+// the top level categories in the order the user walks through the store
+pub async fn get_store_aisles_page(
+    state: State<AppState>,
+    Extension(authenticated_user): Extension<Arc<Option<User>>>,
+    path: Path<HashMap<String, String>>,
+    request: Request,
+) -> impl IntoResponse {
+    let authenticated_user_id = match authenticated_user.as_ref().as_ref().and_then(|user| *user.get_id()) {
+        Some(id) => id,
+        None => {
+            return (StatusCode::FORBIDDEN, Html("")).into_response();
+        }
+    };
+    let store_id = match get_value_from_path(&path, "id").parse::<i64>() {
+        Ok(id) => id,
+        Err(_) => {
+            return (StatusCode::BAD_REQUEST, Html("")).into_response();
+        }
+    };
+
+    let (store, aisles, categories) = match try_join!(
+        find_store(&state.db_pool, &store_id),
+        find_store_aisles(&state.db_pool, &authenticated_user_id, &store_id),
+        find_category_tree(&state.db_pool),
+    ) {
+        Ok(val) => val,
+        Err(sqlx::Error::PoolTimedOut) => {
+            return (StatusCode::TOO_MANY_REQUESTS).into_response();
+        },
+        Err(e) => {
+            if !matches!(e, sqlx::Error::RowNotFound) {
+                eprintln!("undefined error in controller::store::get_store_aisles_page {e:?}");
+            }
+            return (StatusCode::TEMPORARY_REDIRECT, [("Location", "/maerkte-in-der-naehe")]).into_response();
+        }
+    };
+
+    let template = StoreAislesTemplate {
+        store: &store,
+        categories: aisles.sort_categories(&categories),
+        has_aisles: !aisles.is_empty(),
+        authenticated_user: &authenticated_user,
+        notification: None,
+        context: Context::from_request(&request),
+    };
+
+    (StatusCode::OK, minify_html_response(&template.render().unwrap_or_default())).into_response()
+}
+
+// the categories after one was dragged to another aisle, see the Sortable element
+pub async fn save_store_aisles(
+    state: State<AppState>,
+    Extension(authenticated_user): Extension<Arc<Option<User>>>,
+    request: Request,
+) -> impl IntoResponse {
+    let mut headers = HeaderMap::new();
+    // the categories are already where they were dropped
+    headers.insert("hx-reswap", "none".parse().unwrap());
+
+    let authenticated_user_id = match authenticated_user.as_ref().as_ref().and_then(|user| *user.get_id()) {
+        Some(id) => id,
+        None => {
+            let notification = render_error_notification(None);
+            return (StatusCode::UNAUTHORIZED, headers, minify_html_response(&notification));
+        }
+    };
+
+    // the category ids repeat, see StoreAislesForm
+    let form_data = match Form::<Vec<(String, String)>>::from_request(request, &state).await {
+        Ok(Form(pairs)) => StoreAislesForm::from_pairs(&pairs),
+        Err(e) => {
+            eprintln!("error in save_store_aisles {e:?}");
+            None
+        }
+    };
+    let form_data = match form_data {
+        Some(form_data) => form_data,
+        None => {
+            let notification = render_error_notification(None);
+            return (StatusCode::BAD_REQUEST, headers, minify_html_response(&notification));
+        }
+    };
+
+    match save_aisles(&state.db_pool, &authenticated_user_id, &form_data.store_id, &form_data.category_ids).await {
+        Ok(_) => {
+            let notification = render_success_notification(Some("Reihenfolge gespeichert"));
+            (StatusCode::OK, headers, minify_html_response(&notification))
+        },
+        // a store that doesn't exist
+        Err(sqlx::Error::Database(e)) if e.is_foreign_key_violation() => {
+            let notification = render_error_notification(Some("Markt nicht gefunden"));
+            (StatusCode::UNPROCESSABLE_ENTITY, headers, minify_html_response(&notification))
+        },
+        Err(sqlx::Error::PoolTimedOut) => {
+            let notification = render_error_notification(Some("Bitte versuche es gleich noch einmal"));
+            (StatusCode::TOO_MANY_REQUESTS, headers, minify_html_response(&notification))
+        },
+        Err(e) => {
+            eprintln!("undefined error in controller::store::save_store_aisles {e:?}");
+            let notification = render_error_notification(Some("Reihenfolge konnte nicht gespeichert werden"));
+            (StatusCode::UNPROCESSABLE_ENTITY, headers, minify_html_response(&notification))
+        },
+    }
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/maerkte-in-der-naehe", get(get_stores_near_page))
+        .route("/maerkte/:id/gaenge", get(get_store_aisles_page))
+        .route("/store/aisles", put(save_store_aisles))
+}
@@ -0,0 +1,112 @@
+use std::sync::Arc;
+use askama::Template;
+use axum::{
+    extract::{Extension, Query, Request, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+    Router,
+};
+use futures::try_join;
+
+use crate::{
+    core::{context::Context, query_params::{QueryPairs, TourParams}},
+    model::{market::Market, tour::{GeoPoint, Tour}, user::User},
+    routes::minify_html_response,
+    services::{
+        shopping_list::{find_shopping_list, find_shopping_list_cheapest_basket},
+        store::{find_nearest_store_per_market, DEFAULT_RADIUS_IN_METERS},
+    },
+    view::tour::TourDetailTemplate,
+    AppState,
+};
+
+// the product list was at /einkaufstour before the tour, its links and bookmarks carry these params
+const PRODUCT_LIST_PARAMS: [&str; 6] = ["q", "page", "limit", "sort_by", "sort_order", "is"];
+
+pub async fn get_tour_page(
+    Query(tour_params): Query<TourParams>,
+    state: State<AppState>,
+    Extension(authenticated_user): Extension<Arc<Option<User>>>,
+    request: Request,
+) -> impl IntoResponse {
+    let query_pairs = QueryPairs::parse(request.uri().query());
+    if query_pairs.iter().any(|(name, _)| PRODUCT_LIST_PARAMS.contains(&name)) {
+        let location = query_pairs.retain(&PRODUCT_LIST_PARAMS).to_href("/produkte");
+        return (StatusCode::MOVED_PERMANENTLY, [("Location", location)]).into_response();
+    }
+
+    let user = authenticated_user.as_ref().as_ref().expect("get_tour_page is an auth protected route");
+    let authenticated_user_id = user.get_id().expect("authenticated user must have an id");
+    let radius_in_meters = tour_params.radius.unwrap_or(DEFAULT_RADIUS_IN_METERS);
+    let context = Context::from_request(&request);
+
+    let render_without_tour = |context: Context| {
+        let template = TourDetailTemplate {
+            tour: None,
+            shopping_list: None,
+            radius_in_meters,
+            authenticated_user: &authenticated_user,
+            notification: None,
+            context,
+        };
+        (StatusCode::OK, minify_html_response(&template.render().unwrap_or_default())).into_response()
+    };
+
+    let (start, shopping_list_id) = match (user.address_lng, user.address_lat, tour_params.shopping_list_id.or(user.selected_shopping_list_id)) {
+        (Some(lng), Some(lat), Some(shopping_list_id)) => (GeoPoint::new(lng, lat), shopping_list_id),
+        _ => return render_without_tour(context),
+    };
+
+    // every market with a store in reach can sell the cheapest basket
+    let market_ids = Market::ALL.iter().map(|market| market.get_id()).collect::<Vec<i64>>();
+    let (shopping_list, stores) = match try_join!(
+        find_shopping_list(&state.db_pool, &shopping_list_id, &authenticated_user_id),
+        find_nearest_store_per_market(&state.db_pool, start.lng, start.lat, radius_in_meters, &market_ids),
+    ) {
+        Ok(val) => val,
+        Err(sqlx::Error::RowNotFound) => return render_without_tour(context),
+        Err(sqlx::Error::PoolTimedOut) => {
+            return (StatusCode::TOO_MANY_REQUESTS).into_response();
+        },
+        Err(e) => {
+            eprintln!("unexpected error in controller::tour::get_tour_page {e:?}");
+            return (StatusCode::TEMPORARY_REDIRECT, [("Location", "/einkaufszettel")]).into_response();
+        }
+    };
+
+    // the stops are the markets of the cheapest basket, products whose market is out of reach stay unassigned
+    let reachable_market_ids = stores.iter().map(|store| store.market_id).collect::<Vec<i64>>();
+    let products = match find_shopping_list_cheapest_basket(
+        &state.db_pool,
+        &shopping_list_id,
+        &authenticated_user_id,
+        &reachable_market_ids,
+    ).await {
+        Ok(products) => products,
+        Err(sqlx::Error::PoolTimedOut) => {
+            return (StatusCode::TOO_MANY_REQUESTS).into_response();
+        },
+        Err(e) => {
+            eprintln!("unexpected error in controller::tour::get_tour_page {e:?}");
+            return (StatusCode::TEMPORARY_REDIRECT, [("Location", "/einkaufszettel")]).into_response();
+        }
+    };
+
+    let tour = Tour::plan(start, stores, products);
+    let template = TourDetailTemplate {
+        tour: Some(&tour),
+        shopping_list: Some(&shopping_list),
+        radius_in_meters,
+        authenticated_user: &authenticated_user,
+        notification: None,
+        context,
+    };
+
+    (StatusCode::OK, minify_html_response(&template.render().unwrap_or_default())).into_response()
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/einkaufstour", get(get_tour_page))
+}
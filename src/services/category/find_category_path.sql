@@ -0,0 +1,31 @@
+WITH RECURSIVE ancestors AS (
+    SELECT
+        categories.id,
+        categories.parent_id,
+        categories.name,
+        categories.slug,
+        0 AS distance
+    FROM
+        categories
+    WHERE
+        categories.id = $1
+    UNION ALL
+    SELECT
+        categories.id,
+        categories.parent_id,
+        categories.name,
+        categories.slug,
+        ancestors.distance + 1 AS distance
+    FROM
+        categories
+        INNER JOIN ancestors ON categories.id = ancestors.parent_id
+)
+SELECT
+    ancestors.id,
+    ancestors.parent_id,
+    ancestors.name,
+    ancestors.slug
+FROM
+    ancestors
+ORDER BY
+    ancestors.distance DESC
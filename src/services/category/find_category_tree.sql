@@ -0,0 +1,56 @@
+WITH RECURSIVE tree AS (
+    SELECT
+        categories.id,
+        categories.parent_id,
+        categories.name,
+        categories.slug,
+        0 AS depth,
+        ARRAY[categories.name] AS path
+    FROM
+        categories
+    WHERE
+        categories.parent_id IS NULL
+    UNION ALL
+    SELECT
+        categories.id,
+        categories.parent_id,
+        categories.name,
+        categories.slug,
+        tree.depth + 1 AS depth,
+        tree.path || categories.name AS path
+    FROM
+        categories
+        INNER JOIN tree ON categories.parent_id = tree.id
+), descendants AS (
+    SELECT
+        categories.id AS root_id,
+        categories.id
+    FROM
+        categories
+    UNION ALL
+    SELECT
+        descendants.root_id,
+        categories.id
+    FROM
+        categories
+        INNER JOIN descendants ON categories.parent_id = descendants.id
+)
+SELECT
+    tree.id,
+    tree.parent_id,
+    tree.name,
+    tree.slug,
+    tree.depth,
+    (
+        SELECT
+            COUNT(*)
+        FROM
+            products
+            INNER JOIN descendants ON products.category_id = descendants.id
+        WHERE
+            descendants.root_id = tree.id
+    ) AS product_count
+FROM
+    tree
+ORDER BY
+    tree.path ASC
@@ -0,0 +1,59 @@
+use sqlx::{Error, Pool, Postgres};
+use crate::model::category::{Category, CategoryTree};
+
+pub async fn find_category_tree(
+    db_pool: &Pool<Postgres>,
+) -> Result<CategoryTree, Error> {
+    let categories = sqlx::query_as::<_, Category>(include_str!("./find_category_tree.sql"))
+        .fetch_all(db_pool)
+        .await?;
+    Ok(CategoryTree { categories })
+}
+
+// root first, the category itself last
+pub async fn find_category_path(
+    db_pool: &Pool<Postgres>,
+    category_id: &i64,
+) -> Result<Vec<Category>, Error> {
+    sqlx::query_as::<_, Category>(include_str!("./find_category_path.sql"))
+        .bind(category_id)
+        .fetch_all(db_pool)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // needs a postgis server, see tests/migrations.rs
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "needs a postgis database in DATABASE_URL"]
+    async fn the_tree_counts_the_products_of_the_descendants(db_pool: Pool<Postgres>) -> sqlx::Result<()> {
+        for (id, parent_id, slug) in [(1i64, None, "lebensmittel"), (2, Some(1i64), "molkerei"), (3, Some(2), "milch"), (4, Some(1), "obst")] {
+            sqlx::query("INSERT INTO categories (id, parent_id, name, slug) VALUES ($1, $2, $3, $3)")
+                .bind(id)
+                .bind(parent_id)
+                .bind(slug)
+                .execute(&db_pool)
+                .await?;
+        }
+        for (id, category_id) in [("lidl-1", 3i64), ("lidl-2", 3), ("lidl-3", 2), ("lidl-4", 1)] {
+            sqlx::query("INSERT INTO products (id, name, url, market_id, category_id) VALUES ($1, $1, '', 1, $2)")
+                .bind(id)
+                .bind(category_id)
+                .execute(&db_pool)
+                .await?;
+        }
+
+        let tree = find_category_tree(&db_pool).await?;
+        let rows = tree.categories.iter()
+            .map(|category| (category.slug.as_str(), category.depth, category.product_count))
+            .collect::<Vec<_>>();
+        // depth-first, siblings by name
+        assert_eq!(rows, vec![("lebensmittel", 0, 4), ("molkerei", 1, 3), ("milch", 2, 2), ("obst", 1, 0)]);
+
+        let path = find_category_path(&db_pool, &3).await?;
+        assert_eq!(path.iter().map(|category| category.slug.as_str()).collect::<Vec<_>>(), vec!["lebensmittel", "molkerei", "milch"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,3 @@
+SELECT images[1]
+FROM products
+WHERE id = $1
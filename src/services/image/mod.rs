@@ -0,0 +1,222 @@
+use std::io::Cursor;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, UNIX_EPOCH};
+use tokio::fs;
+use image::{codecs::jpeg::JpegEncoder, imageops::FilterType, DynamicImage, ImageReader, Rgba, RgbaImage};
+use once_cell::sync::Lazy;
+use reqwest::Client;
+use sha2::{Digest, Sha256};
+use sqlx::{Pool, Postgres};
+
+use crate::model::product::image::ImageSize;
+
+const JPEG_QUALITY: u8 = 80;
+// larger sources are not decoded at all
+const MAX_SOURCE_BYTES: usize = 10 * 1024 * 1024;
+
+// resized images by etag, the directory may be deleted at any time
+static IMAGE_CACHE_DIR: Lazy<PathBuf> = Lazy::new(|| {
+    PathBuf::from(std::env::var("IMAGE_CACHE_DIR").unwrap_or("cache/img".to_string()))
+});
+// images that aren't urls are paths below it, e.g. "lidl/100336123.jpg"
+static IMAGE_STORE_DIR: Lazy<PathBuf> = Lazy::new(|| {
+    PathBuf::from(std::env::var("IMAGE_STORE_DIR").unwrap_or("images".to_string()))
+});
+static HTTP_CLIENT: Lazy<Client> = Lazy::new(|| {
+    Client::builder()
+        .user_agent("preiskampf")
+        .timeout(Duration::from_secs(10))
+        .build()
+        .expect("Couldnt build reqwest::Client in image module")
+});
+// tells apart the temporary files of concurrent resizes
+static TMP_FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+pub async fn find_product_image_source(
+    db_pool: &Pool<Postgres>,
+    product_id: &str,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar::<_, Option<String>>(include_str!("./find_product_image_source.sql"))
+        .bind(product_id)
+        .fetch_one(db_pool)
+        .await
+}
+
+// the resized image only depends on its source and size, so neither has to be read to answer If-None-Match.
+// a stored image can be replaced under the same path, its modification time is part of the tag
+pub async fn get_image_etag(source: &str, size: &ImageSize) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(source.as_bytes());
+    hasher.update(size.get_width().to_be_bytes());
+    if let Ok(path) = find_stored_image_path(source) {
+        let modified_at = fs::metadata(path).await
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified_at| modified_at.duration_since(UNIX_EPOCH).ok())
+            .unwrap_or_default();
+        hasher.update(modified_at.as_nanos().to_be_bytes());
+    }
+    hasher.finalize().iter().take(8).map(|byte| format!("{byte:02x}")).collect()
+}
+
+// a jpeg, read from the cache or resized from the source and cached
+pub async fn load_resized_image(source: &str, size: &ImageSize, etag: &str) -> Result<Vec<u8>, String> {
+    let cache_path = IMAGE_CACHE_DIR.join(format!("{etag}.jpg"));
+    if let Ok(bytes) = fs::read(&cache_path).await {
+        return Ok(bytes);
+    }
+
+    let source_bytes = if source.starts_with("https://") || source.starts_with("http://") {
+        fetch_image(source).await?
+    } else {
+        read_stored_image(source).await?
+    };
+    let width = size.get_width();
+    // decoding and resizing would block the executor
+    let bytes = tokio::task::spawn_blocking(move || resize_image(source_bytes.as_slice(), width))
+        .await
+        .map_err(|e| e.to_string())??;
+
+    // written next to it first, a concurrent request must never read half an image
+    let tmp_path = cache_path.with_extension(format!(
+        "{}-{}.tmp",
+        std::process::id(),
+        TMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed),
+    ));
+    if let Err(e) = write_cached_image(&tmp_path, &cache_path, &bytes).await {
+        eprintln!("couldnt cache image {}: {e}", cache_path.display());
+        let _ = fs::remove_file(&tmp_path).await;
+    }
+
+    Ok(bytes)
+}
+
+async fn fetch_image(url: &str) -> Result<Vec<u8>, String> {
+    let response = HTTP_CLIENT.get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| e.to_string())?;
+    if response.content_length().is_some_and(|length| length as usize > MAX_SOURCE_BYTES) {
+        return Err(format!("{url} is larger than {MAX_SOURCE_BYTES} bytes"));
+    }
+    let bytes = response.bytes().await.map_err(|e| e.to_string())?;
+    if bytes.len() > MAX_SOURCE_BYTES {
+        return Err(format!("{url} is larger than {MAX_SOURCE_BYTES} bytes"));
+    }
+
+    Ok(bytes.to_vec())
+}
+
+async fn write_cached_image(tmp_path: &Path, cache_path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    fs::create_dir_all(IMAGE_CACHE_DIR.as_path()).await?;
+    fs::write(tmp_path, bytes).await?;
+    fs::rename(tmp_path, cache_path).await
+}
+
+fn find_stored_image_path(source: &str) -> Result<PathBuf, String> {
+    if source.starts_with("https://") || source.starts_with("http://") {
+        return Err(format!("\"{source}\" is not stored"));
+    }
+    let path = Path::new(source.trim_start_matches('/'));
+    // the images column must not be able to point anywhere outside the store
+    if path.components().any(|component| !matches!(component, Component::Normal(_))) {
+        return Err(format!("invalid image path \"{source}\""));
+    }
+
+    Ok(IMAGE_STORE_DIR.join(path))
+}
+
+async fn read_stored_image(source: &str) -> Result<Vec<u8>, String> {
+    fs::read(find_stored_image_path(source)?).await.map_err(|e| format!("{source}: {e}"))
+}
+
+fn resize_image(source_bytes: &[u8], width: u32) -> Result<Vec<u8>, String> {
+    let image = ImageReader::new(Cursor::new(source_bytes))
+        .with_guessed_format()
+        .map_err(|e| e.to_string())?
+        .decode()
+        .map_err(|e| e.to_string())?;
+    let image = if image.width() > width || image.height() > width {
+        image.resize(width, width, FilterType::Triangle)
+    } else {
+        image
+    };
+
+    // jpeg has no alpha channel, transparent product shots would turn black
+    let mut background = RgbaImage::from_pixel(image.width(), image.height(), Rgba([255, 255, 255, 255]));
+    image::imageops::overlay(&mut background, &image.to_rgba8(), 0, 0);
+
+    let mut bytes = vec![];
+    DynamicImage::ImageRgba8(background)
+        .to_rgb8()
+        .write_with_encoder(JpegEncoder::new_with_quality(&mut bytes, JPEG_QUALITY))
+        .map_err(|e| e.to_string())?;
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{ImageFormat, Rgb};
+    use super::*;
+
+    #[test]
+    fn stored_image_paths_stay_in_the_store() {
+        assert_eq!(find_stored_image_path("lidl/100336123.jpg"), Ok(IMAGE_STORE_DIR.join("lidl/100336123.jpg")));
+        // a leading slash is relative to the store, not the root
+        assert_eq!(find_stored_image_path("/etc/passwd"), Ok(IMAGE_STORE_DIR.join("etc/passwd")));
+        assert_eq!(find_stored_image_path("//etc/passwd"), Ok(IMAGE_STORE_DIR.join("etc/passwd")));
+
+        for source in ["../secret.jpg", "lidl/../../secret.jpg", "lidl/..", "./lidl/1.jpg", "/../etc/passwd"] {
+            assert!(find_stored_image_path(source).is_err(), "{}", source);
+        }
+        for source in ["https://example.com/1.jpg", "http://example.com/1.jpg"] {
+            assert!(find_stored_image_path(source).is_err(), "{}", source);
+        }
+    }
+
+    // transparent except for an opaque red square in the middle
+    fn png_with_alpha(width: u32, height: u32) -> Vec<u8> {
+        let image = RgbaImage::from_fn(width, height, |x, y| {
+            let is_center = x.abs_diff(width / 2) < width / 4 && y.abs_diff(height / 2) < height / 4;
+            if is_center { Rgba([255, 0, 0, 255]) } else { Rgba([0, 0, 0, 0]) }
+        });
+        let mut bytes = vec![];
+        DynamicImage::ImageRgba8(image).write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png).unwrap();
+        bytes
+    }
+
+    fn is_near(pixel: &Rgb<u8>, expected: [u8; 3]) -> bool {
+        pixel.0.iter().zip(expected).all(|(channel, expected)| channel.abs_diff(expected) < 24)
+    }
+
+    #[test]
+    fn images_are_fit_into_each_size_with_a_white_background() {
+        let source = png_with_alpha(200, 100);
+
+        for (size, expected) in [
+            (ImageSize::Thumbnail, (96, 48)),
+            // never upscaled
+            (ImageSize::Small, (192, 96)),
+            (ImageSize::Medium, (200, 100)),
+            (ImageSize::Large, (200, 100)),
+        ] {
+            let bytes = resize_image(&source, size.get_width()).unwrap();
+            assert_eq!(image::guess_format(&bytes).unwrap(), ImageFormat::Jpeg);
+
+            let resized = image::load_from_memory(&bytes).unwrap().to_rgb8();
+            assert_eq!(resized.dimensions(), expected, "{:?}", size);
+            assert!(is_near(resized.get_pixel(0, 0), [255, 255, 255]), "{:?} {:?}", size, resized.get_pixel(0, 0));
+            let center = resized.get_pixel(expected.0 / 2, expected.1 / 2);
+            assert!(is_near(center, [255, 0, 0]), "{:?} {:?}", size, center);
+        }
+    }
+
+    #[test]
+    fn undecodable_images_are_an_error() {
+        assert!(resize_image(b"not an image", 96).is_err());
+        assert!(resize_image(&[], 96).is_err());
+    }
+}
@@ -0,0 +1,14 @@
+SELECT
+    products.id,
+    products.name,
+    products.images,
+    products.url,
+    products.market_id,
+    products.price,
+    products.currency,
+    products.removed_at,
+    products.checked_at
+FROM
+    products
+WHERE
+    products.id = ANY($1)
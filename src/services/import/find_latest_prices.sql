@@ -0,0 +1,11 @@
+SELECT DISTINCT ON (prices.product_id)
+    prices.product_id,
+    prices.price,
+    prices.currency,
+    prices.created_at
+FROM
+    prices
+WHERE
+    prices.product_id = ANY($1)
+ORDER BY
+    prices.product_id, prices.created_at DESC
@@ -0,0 +1,4 @@
+INSERT INTO
+    prices (created_at, product_id, price, currency)
+VALUES
+    ($1, $2, $3, $4)
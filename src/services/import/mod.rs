@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, Pool, Postgres};
+
+use crate::model::import::{FeedRecord, ImportChange, ImportDiff, ImportReport};
+
+#[derive(Debug, FromRow)]
+struct ImportProduct {
+    id: String,
+    name: String,
+    images: Vec<String>,
+    url: String,
+    market_id: i64,
+    price: Option<i32>,
+    currency: Option<String>,
+    removed_at: Option<DateTime<Utc>>,
+    // the timestamp of the newest record seen for it
+    checked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, FromRow)]
+struct LatestPrice {
+    product_id: String,
+    price: Option<i32>,
+    currency: String,
+    created_at: DateTime<Utc>,
+}
+
+// upserts the products and appends to their price history only on change, so re-running a feed is a no-op.
+// with `dry_run` everything is rolled back and only the report is left.
+pub async fn import_feed_records(
+    db_pool: &Pool<Postgres>,
+    mut records: Vec<FeedRecord>,
+    dry_run: bool,
+) -> Result<ImportReport, sqlx::Error> {
+    // the history is appended in order, whatever order the feed has
+    records.sort_by_key(|record| record.timestamp);
+
+    let mut product_ids = records.iter().map(|record| record.product_id.to_owned()).collect::<Vec<String>>();
+    product_ids.sort_unstable();
+    product_ids.dedup();
+
+    let mut transaction = db_pool.begin().await?;
+    let mut products = sqlx::query_as::<_, ImportProduct>(include_str!("./find_import_products.sql"))
+        .bind(&product_ids)
+        .fetch_all(&mut *transaction)
+        .await?
+        .into_iter()
+        .map(|product| (product.id.to_owned(), product))
+        .collect::<HashMap<String, ImportProduct>>();
+    let mut latest_prices = sqlx::query_as::<_, LatestPrice>(include_str!("./find_latest_prices.sql"))
+        .bind(&product_ids)
+        .fetch_all(&mut *transaction)
+        .await?
+        .into_iter()
+        .map(|price| (price.product_id.to_owned(), price))
+        .collect::<HashMap<String, LatestPrice>>();
+
+    let mut checked_at = HashMap::<String, DateTime<Utc>>::new();
+    let mut report = ImportReport::default();
+    for record in records {
+        // sorted by timestamp, the last one is the newest
+        checked_at.insert(record.product_id.to_owned(), record.timestamp);
+
+        let existing = products.get(&record.product_id);
+        // an older record must not overwrite what a newer one saved, e.g. a feed that is re-run after a newer one
+        let is_stale = existing.and_then(|existing| existing.checked_at).is_some_and(|checked_at| record.timestamp < checked_at);
+
+        let latest_price = latest_prices.get(&record.product_id);
+        let (is_newest, is_price_changed) = match latest_price {
+            Some(latest) => (
+                latest.created_at < record.timestamp,
+                latest.price != record.price || latest.currency != record.currency,
+            ),
+            None => (true, true),
+        };
+        let is_price_added = !is_stale && is_newest && is_price_changed;
+
+        let (price, currency) = match existing {
+            Some(existing) if !is_newest => (existing.price, existing.currency.to_owned()),
+            _ => (record.price, Some(record.currency.to_owned())),
+        };
+        let change = match existing {
+            None => ImportChange::Created,
+            Some(_) if is_stale => ImportChange::Unchanged,
+            Some(existing) => {
+                let changed_fields = [
+                    ("name", existing.name != record.name),
+                    ("images", existing.images != record.images),
+                    ("url", existing.url != record.url),
+                    ("market", existing.market_id != record.market_id),
+                    ("price", existing.price != price || existing.currency != currency),
+                    // the link check removed it, but the market still has it
+                    ("restored", existing.removed_at.is_some()),
+                ].into_iter()
+                    .filter(|(_, is_changed)| *is_changed)
+                    .map(|(field, _)| field.to_string())
+                    .collect::<Vec<String>>();
+
+                if changed_fields.is_empty() {
+                    ImportChange::Unchanged
+                } else {
+                    ImportChange::Updated(changed_fields)
+                }
+            },
+        };
+
+        if change != ImportChange::Unchanged {
+            sqlx::query(include_str!("./upsert_product.sql"))
+                .bind(&record.product_id)
+                .bind(&record.name)
+                .bind(&record.images)
+                .bind(&record.url)
+                .bind(record.market_id)
+                .bind(price)
+                .bind(&currency)
+                .bind(record.timestamp)
+                .execute(&mut *transaction)
+                .await?;
+        }
+
+        if is_price_added {
+            sqlx::query(include_str!("./insert_price.sql"))
+                .bind(record.timestamp)
+                .bind(&record.product_id)
+                .bind(record.price)
+                .bind(&record.currency)
+                .execute(&mut *transaction)
+                .await?;
+            latest_prices.insert(record.product_id.to_owned(), LatestPrice {
+                product_id: record.product_id.to_owned(),
+                price: record.price,
+                currency: record.currency.to_owned(),
+                created_at: record.timestamp,
+            });
+        }
+
+        let mut is_promotion_saved = false;
+        if let Some(promotion) = &record.promotion {
+            let condition = promotion.condition.map(|condition| condition.get_columns());
+            is_promotion_saved = sqlx::query_scalar::<_, i64>(include_str!("./upsert_promotion.sql"))
+                .bind(&record.product_id)
+                .bind(promotion.starts_at)
+                .bind(promotion.ends_at)
+                .bind(promotion.price)
+                .bind(&record.currency)
+                .bind(condition.map(|(condition, _, _)| condition))
+                .bind(condition.and_then(|(_, buy_quantity, _)| buy_quantity))
+                .bind(condition.and_then(|(_, _, pay_quantity)| pay_quantity))
+                .fetch_optional(&mut *transaction)
+                .await?
+                .is_some();
+        }
+
+        if !is_stale {
+            products.insert(record.product_id.to_owned(), ImportProduct {
+                id: record.product_id.to_owned(),
+                name: record.name,
+                images: record.images,
+                url: record.url,
+                market_id: record.market_id,
+                price,
+                currency,
+                removed_at: None,
+                checked_at: Some(record.timestamp),
+            });
+        }
+        report.diffs.push(ImportDiff {
+            product_id: record.product_id,
+            change,
+            is_price_added,
+            is_promotion_saved,
+        });
+    }
+
+    let (checked_product_ids, checked_at): (Vec<String>, Vec<DateTime<Utc>>) = checked_at.into_iter().unzip();
+    sqlx::query(include_str!("./update_product_checked_at.sql"))
+        .bind(&checked_product_ids)
+        .bind(&checked_at)
+        .execute(&mut *transaction)
+        .await?;
+
+    if dry_run {
+        transaction.rollback().await?;
+    } else {
+        transaction.commit().await?;
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use super::*;
+    use crate::model::import::{parse_feed, FeedFormat};
+
+    // needs a postgis server, see tests/migrations.rs
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "needs a postgis database in DATABASE_URL"]
+    async fn importing_a_feed_twice_adds_no_second_price(db_pool: Pool<Postgres>) -> sqlx::Result<()> {
+        let content = "product_id,name,market,url,price,timestamp,promo_price,promo_end\n\
+            lidl-1,Milbona Vollmilch,lidl,https://www.lidl.de/p/1,1.09,2026-10-18T06:00:00Z,0.99,2026-10-25T00:00:00Z\n\
+            aldisued-2,Brot,aldisued,https://www.aldi-sued.de/p/2,2.49,2026-10-18T06:00:00Z,,\n";
+        let imported_at = Utc.with_ymd_and_hms(2026, 10, 19, 6, 0, 0).unwrap();
+        let records = parse_feed(content, FeedFormat::Csv, imported_at).unwrap();
+
+        let report = import_feed_records(&db_pool, records.clone(), false).await?;
+        assert_eq!(report.count(|change| *change == ImportChange::Created), 2);
+        assert_eq!((report.count_prices_added(), report.count_promotions_saved()), (2, 1));
+
+        let report = import_feed_records(&db_pool, records, false).await?;
+        assert_eq!(report.count(|change| *change == ImportChange::Unchanged), 2);
+        assert_eq!(report.get_changes().count(), 0);
+
+        let price_count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM prices").fetch_one(&db_pool).await?;
+        let promotion_count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM promotions").fetch_one(&db_pool).await?;
+        assert_eq!((price_count, promotion_count), (2, 1));
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "needs a postgis database in DATABASE_URL"]
+    async fn an_older_feed_leaves_the_product_as_the_newer_one_saved_it(db_pool: Pool<Postgres>) -> sqlx::Result<()> {
+        let imported_at = Utc.with_ymd_and_hms(2026, 10, 19, 6, 0, 0).unwrap();
+        let newer = "product_id,name,market,url,price,timestamp\n\
+            lidl-1,Milbona Vollmilch 3.5%,lidl,https://www.lidl.de/p/1-neu,1.19,2026-10-18T06:00:00Z\n";
+        // the same product twice, the feed is sorted by timestamp before it's applied
+        let older = "product_id,name,market,url,price,timestamp\n\
+            lidl-1,Milbona Vollmilch,lidl,https://www.lidl.de/p/1-alt,1.05,2026-10-12T06:00:00Z\n\
+            lidl-1,Milbona Vollmilch,lidl,https://www.lidl.de/p/1,1.09,2026-10-15T06:00:00Z\n";
+
+        import_feed_records(&db_pool, parse_feed(newer, FeedFormat::Csv, imported_at).unwrap(), false).await?;
+        let report = import_feed_records(&db_pool, parse_feed(older, FeedFormat::Csv, imported_at).unwrap(), false).await?;
+
+        assert_eq!(report.count(|change| *change == ImportChange::Unchanged), 2);
+        assert_eq!(report.get_changes().count(), 0);
+        let (name, url, price) = sqlx::query_as::<_, (String, String, Option<i32>)>("SELECT name, url, price FROM products WHERE id = 'lidl-1'")
+            .fetch_one(&db_pool)
+            .await?;
+        assert_eq!((name.as_str(), url.as_str(), price), ("Milbona Vollmilch 3.5%", "https://www.lidl.de/p/1-neu", Some(119)));
+        let price_count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM prices").fetch_one(&db_pool).await?;
+        assert_eq!(price_count, 1);
+        Ok(())
+    }
+}
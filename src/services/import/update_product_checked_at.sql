@@ -0,0 +1,9 @@
+-- every product of the feed was checked, whether or not anything changed. a feed of old records doesn't turn it back
+UPDATE
+    products
+SET
+    checked_at = GREATEST(products.checked_at, checked.checked_at)
+FROM
+    unnest($1::TEXT[], $2::TIMESTAMPTZ[]) AS checked (product_id, checked_at)
+WHERE
+    products.id = checked.product_id
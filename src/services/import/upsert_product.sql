@@ -0,0 +1,16 @@
+-- a record older than the newest one seen for the product leaves it as it is, $8 is the timestamp of the record
+INSERT INTO
+    products (id, name, images, url, market_id, price, currency)
+VALUES
+    ($1, $2, $3, $4, $5, $6, $7)
+ON CONFLICT (id) DO UPDATE SET
+    name = EXCLUDED.name,
+    images = EXCLUDED.images,
+    url = EXCLUDED.url,
+    market_id = EXCLUDED.market_id,
+    price = EXCLUDED.price,
+    currency = EXCLUDED.currency,
+    removed_at = NULL
+WHERE
+    products.checked_at IS NULL
+    OR products.checked_at <= $8
@@ -0,0 +1,18 @@
+-- returns nothing if the promotion is already saved as it is, a re-run doesn't count as a change
+INSERT INTO
+    promotions (product_id, starts_at, ends_at, price, currency, condition, buy_quantity, pay_quantity)
+VALUES
+    ($1, $2, $3, $4, $5, $6, $7, $8)
+ON CONFLICT (product_id, starts_at) DO UPDATE SET
+    ends_at = EXCLUDED.ends_at,
+    price = EXCLUDED.price,
+    currency = EXCLUDED.currency,
+    condition = EXCLUDED.condition,
+    buy_quantity = EXCLUDED.buy_quantity,
+    pay_quantity = EXCLUDED.pay_quantity
+WHERE
+    (promotions.ends_at, promotions.price, promotions.currency, promotions.condition, promotions.buy_quantity, promotions.pay_quantity)
+    IS DISTINCT FROM
+    (EXCLUDED.ends_at, EXCLUDED.price, EXCLUDED.currency, EXCLUDED.condition, EXCLUDED.buy_quantity, EXCLUDED.pay_quantity)
+RETURNING
+    id
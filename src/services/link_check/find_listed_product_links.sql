@@ -0,0 +1,10 @@
+SELECT DISTINCT
+    products.id,
+    products.url
+FROM
+    products
+    INNER JOIN shopping_lists_items ON shopping_lists_items.product_id = products.id
+WHERE
+    products.removed_at IS NULL
+ORDER BY
+    products.id
@@ -0,0 +1,12 @@
+-- flags the items of removed products and unflags those of restored ones, items already up to date are left alone
+UPDATE
+    shopping_lists_items
+SET
+    stale_at = CASE WHEN products.removed_at IS NULL THEN NULL ELSE NOW() END
+FROM
+    products
+WHERE
+    products.id = shopping_lists_items.product_id
+    AND (products.removed_at IS NULL) = (shopping_lists_items.stale_at IS NOT NULL)
+RETURNING
+    shopping_lists_items.stale_at IS NOT NULL AS is_stale
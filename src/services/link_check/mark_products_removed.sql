@@ -0,0 +1,7 @@
+UPDATE
+    products
+SET
+    removed_at = NOW()
+WHERE
+    products.id = ANY($1)
+    AND products.removed_at IS NULL
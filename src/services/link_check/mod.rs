@@ -0,0 +1,158 @@
+use std::time::Duration;
+use futures::{stream, StreamExt};
+use once_cell::sync::Lazy;
+use reqwest::{Client, StatusCode};
+use sqlx::{FromRow, Pool, Postgres};
+
+// requests to the markets at once
+const CONCURRENCY: usize = 8;
+
+static HTTP_CLIENT: Lazy<Client> = Lazy::new(|| {
+    Client::builder()
+        .user_agent("preiskampf")
+        .timeout(Duration::from_secs(10))
+        .build()
+        .expect("Couldnt build reqwest::Client in link_check module")
+});
+
+#[derive(Debug, FromRow)]
+struct ProductLink {
+    id: String,
+    url: String,
+}
+
+#[derive(Debug, Default)]
+pub struct LinkCheckReport {
+    pub checked: usize,
+    pub removed: Vec<String>,
+    // couldn't be reached at all, which says nothing about the product
+    pub unreachable: Vec<String>,
+    pub flagged_items: usize,
+    pub unflagged_items: usize,
+}
+
+impl LinkCheckReport {
+    pub fn format_summary(&self) -> String {
+        format!(
+            "{} checked, {} removed, {} unreachable, {} items flagged, {} items unflagged",
+            self.checked,
+            self.removed.len(),
+            self.unreachable.len(),
+            self.flagged_items,
+            self.unflagged_items,
+        )
+    }
+}
+
+// checks the market pages of every product on a shopping list, products whose page is gone are marked removed
+// and the items referencing them flagged as stale. with `dry_run` everything is rolled back and only the report is left.
+pub async fn check_listed_product_links(
+    db_pool: &Pool<Postgres>,
+    dry_run: bool,
+) -> Result<LinkCheckReport, sqlx::Error> {
+    let links = sqlx::query_as::<_, ProductLink>(include_str!("./find_listed_product_links.sql"))
+        .fetch_all(db_pool)
+        .await?;
+
+    let mut report = LinkCheckReport { checked: links.len(), ..Default::default() };
+    let results = stream::iter(links)
+        .map(|link| async move {
+            let is_gone = is_link_gone(link.url.as_str()).await;
+            (link, is_gone)
+        })
+        .buffer_unordered(CONCURRENCY)
+        .collect::<Vec<_>>()
+        .await;
+    for (link, is_gone) in results {
+        match is_gone {
+            Ok(true) => report.removed.push(link.id),
+            Ok(false) => {},
+            Err(e) => {
+                eprintln!("couldnt check {} of product {}: {e}", link.url, link.id);
+                report.unreachable.push(link.id);
+            },
+        }
+    }
+    report.removed.sort_unstable();
+    report.unreachable.sort_unstable();
+
+    let mut transaction = db_pool.begin().await?;
+    sqlx::query(include_str!("./mark_products_removed.sql"))
+        .bind(&report.removed)
+        .execute(&mut *transaction)
+        .await?;
+    // restored products are unflagged here as well, imports clear their removed_at
+    let flags = sqlx::query_scalar::<_, bool>(include_str!("./flag_stale_shopping_list_items.sql"))
+        .fetch_all(&mut *transaction)
+        .await?;
+    report.flagged_items = flags.iter().filter(|is_stale| **is_stale).count();
+    report.unflagged_items = flags.len() - report.flagged_items;
+
+    if dry_run {
+        transaction.rollback().await?;
+    } else {
+        transaction.commit().await?;
+    }
+
+    Ok(report)
+}
+
+// only an explicit 404 or 410 counts, a market being down must not empty the shopping lists
+async fn is_link_gone(url: &str) -> Result<bool, String> {
+    let mut status = HTTP_CLIENT.head(url).send().await.map_err(|e| e.to_string())?.status();
+    if status == StatusCode::METHOD_NOT_ALLOWED {
+        status = HTTP_CLIENT.get(url).send().await.map_err(|e| e.to_string())?.status();
+    }
+
+    Ok(status == StatusCode::NOT_FOUND || status == StatusCode::GONE)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{routing::{get, head, post}, Router};
+    use super::*;
+
+    // a market answering with the status of the path, some only to GET
+    async fn serve_market() -> String {
+        let app = Router::new()
+            .route("/ok", get(|| async { StatusCode::OK }))
+            .route("/not-found", get(|| async { StatusCode::NOT_FOUND }))
+            .route("/gone", get(|| async { StatusCode::GONE }))
+            .route("/down", get(|| async { StatusCode::SERVICE_UNAVAILABLE }))
+            .route("/head-not-allowed/ok", head(|| async { StatusCode::METHOD_NOT_ALLOWED }).get(|| async { StatusCode::OK }))
+            .route("/head-not-allowed/gone", head(|| async { StatusCode::METHOD_NOT_ALLOWED }).get(|| async { StatusCode::GONE }))
+            .route("/not-allowed", post(|| async { StatusCode::OK }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn only_not_found_and_gone_count_as_gone() {
+        let market = serve_market().await;
+
+        for (path, expected) in [
+            ("/ok", false),
+            ("/not-found", true),
+            ("/gone", true),
+            ("/down", false),
+            // markets that only answer GET are asked again
+            ("/head-not-allowed/ok", false),
+            ("/head-not-allowed/gone", true),
+            ("/not-allowed", false),
+        ] {
+            assert_eq!(is_link_gone(format!("{}{}", market, path).as_str()).await, Ok(expected), "{}", path);
+        }
+    }
+
+    #[tokio::test]
+    async fn unreachable_markets_are_an_error() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        assert!(is_link_gone(format!("http://{}/gone", addr).as_str()).await.is_err());
+        assert!(is_link_gone("not a url").await.is_err());
+    }
+}
@@ -1,4 +1,10 @@
+pub mod category;
+pub mod image;
+pub mod import;
+pub mod link_check;
 pub mod mail;
 pub mod product;
+pub mod scraper;
 pub mod shopping_list;
+pub mod store;
 pub mod user;
\ No newline at end of file
@@ -0,0 +1,32 @@
+SELECT
+  products_table.id,
+  products_table.created_at,
+  products_table.name,
+  products_table.images,
+  products_table.url,
+  products_table.market_id,
+  products_table.category_id,
+  products_table.price,
+  products_table.currency,
+  products_table.unit_price,
+  products_table.unit,
+  product_price_stats.all_time_low,
+  product_price_stats.all_time_high,
+  product_price_stats.median_price,
+  product_price_stats.discount_percent,
+  COUNT(*) OVER() AS total
+FROM
+  product_price_stats
+  JOIN products products_table ON products_table.id = product_price_stats.product_id
+WHERE
+  products_table.removed_at IS NULL
+  AND (
+    product_price_stats.discount_percent >= $1
+    OR (
+      products_table.price <= product_price_stats.all_time_low
+      AND product_price_stats.all_time_high > product_price_stats.all_time_low
+    )
+  )
+ORDER BY
+  {}, products_table.id
+LIMIT $2 OFFSET $3
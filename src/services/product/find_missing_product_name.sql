@@ -0,0 +1,4 @@
+SELECT name FROM products WHERE id = $1 AND removed_at IS NOT NULL
+UNION ALL
+SELECT name FROM deleted_products WHERE id = $1
+LIMIT 1
@@ -5,8 +5,15 @@ SELECT
   products_table.images,
   products_table.url,
   products_table.market_id,
+  products_table.category_id,
   prices_table.price,
   prices_table.currency,
+  products_table.unit_price,
+  products_table.unit,
+  product_price_stats.all_time_low,
+  product_price_stats.all_time_high,
+  product_price_stats.median_price,
+  product_price_stats.discount_percent,
   COUNT(*) OVER() AS total
 FROM
   products products_table
@@ -24,5 +31,6 @@ FROM
       prices
   ) AS prices_table ON products_table.id = prices_table.product_id
   AND prices_table.ranked_created_at = 1
+  LEFT JOIN product_price_stats ON product_price_stats.product_id = products_table.id
 WHERE
   products_table.id = $1
\ No newline at end of file
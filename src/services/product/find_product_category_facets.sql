@@ -0,0 +1,14 @@
+SELECT
+  categories.slug AS value,
+  categories.name AS label,
+  COUNT(*) AS count
+FROM
+  products products_table
+  INNER JOIN categories ON categories.id = products_table.category_id
+WHERE
+  {}
+GROUP BY
+  categories.slug, categories.name
+ORDER BY
+  count DESC
+LIMIT 10
@@ -0,0 +1,11 @@
+SELECT
+  COUNT(*) FILTER (WHERE {}) AS on_sale_count,
+  COUNT(*) FILTER (
+    WHERE EXISTS (
+      SELECT 1 FROM shopping_lists_items WHERE shopping_lists_items.shopping_list_id = $10 AND shopping_lists_items.product_id = products_table.id
+    )
+  ) AS in_list_count
+FROM
+  products products_table
+WHERE
+  {}
@@ -0,0 +1,12 @@
+SELECT
+  products_table.market_id::TEXT AS value,
+  products_table.market_id::TEXT AS label,
+  COUNT(*) AS count
+FROM
+  products products_table
+WHERE
+  {}
+GROUP BY
+  products_table.market_id
+ORDER BY
+  count DESC
@@ -0,0 +1,15 @@
+SELECT
+  promotions.starts_at,
+  promotions.ends_at,
+  promotions.price,
+  promotions.currency,
+  promotions.condition,
+  promotions.buy_quantity,
+  promotions.pay_quantity
+FROM
+  promotions
+WHERE
+  promotions.product_id = $1
+  AND promotions.ends_at > NOW()
+ORDER BY
+  promotions.starts_at
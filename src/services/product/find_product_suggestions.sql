@@ -0,0 +1,13 @@
+SELECT
+  products_table.id,
+  products_table.name,
+  {} AS headline,
+  products_table.market_id
+FROM
+  products products_table
+WHERE
+  {}
+ORDER BY
+  {}
+LIMIT
+  $10
@@ -5,13 +5,26 @@ SELECT
   products_table.images,
   products_table.url,
   products_table.market_id,
+  products_table.category_id,
   products_table.price,
-  products_table.currency
+  products_table.currency,
+  products_table.unit_price,
+  products_table.unit,
+  product_price_stats.all_time_low,
+  product_price_stats.all_time_high,
+  product_price_stats.median_price,
+  product_price_stats.discount_percent,
+  {}::TEXT AS sort_key,
+  {} AS headline
 FROM
   products products_table
+  LEFT JOIN product_price_stats ON product_price_stats.product_id = products_table.id
+WHERE
+  {}
+  AND {}
 ORDER BY
-  products_table.{} {}
+  {}
 LIMIT
-{}
+  $10
 OFFSET
-{}
\ No newline at end of file
+  $11
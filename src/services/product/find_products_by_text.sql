@@ -1,17 +1,41 @@
-SELECT 
-    products_table.id,
-    products_table.created_at,
-    products_table.name,
-    products_table.images,
-    products_table.url,
-    products_table.market_id,
-    products_table.price,
-    products_table.currency
+-- free text, e.g. "Brötchen vom Bäcker", is matched both ways: it may be a part of a name or contain one
+SELECT
+  products_table.id,
+  products_table.created_at,
+  products_table.name,
+  products_table.images,
+  products_table.url,
+  products_table.market_id,
+  products_table.category_id,
+  prices_table.price,
+  prices_table.currency,
+  products_table.unit_price,
+  products_table.unit,
+  product_price_stats.all_time_low,
+  product_price_stats.all_time_high,
+  product_price_stats.median_price,
+  product_price_stats.discount_percent
 FROM
   products products_table
+  LEFT JOIN (
+    SELECT
+      product_id,
+      RANK() OVER (
+        PARTITION BY product_id
+        ORDER BY
+          created_at DESC
+      ) as ranked_created_at,
+      price,
+      currency
+    FROM
+      prices
+  ) AS prices_table ON products_table.id = prices_table.product_id
+  AND prices_table.ranked_created_at = 1
+  LEFT JOIN product_price_stats ON product_price_stats.product_id = products_table.id
 WHERE
-    full_text_search @@ to_tsquery('{}', '{}:*')
-LIMIT 
-    {} 
-OFFSET
-    {}
\ No newline at end of file
+  products_table.removed_at IS NULL
+  AND ($1 <% products_table.name OR products_table.name <% $1)
+ORDER BY
+  GREATEST(word_similarity($1, products_table.name), word_similarity(products_table.name, $1)) DESC,
+  products_table.id
+LIMIT $2
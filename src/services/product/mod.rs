@@ -1,46 +1,263 @@
-use sqlx::{Error, FromRow, Pool, Postgres, Row};
-use crate::core::query_params::SortOrder;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use futures::try_join;
+use once_cell::sync::Lazy;
+use sqlx::{postgres::PgArguments, query::QueryAs, Error, FromRow, Pool, Postgres, Row};
+use crate::core::{
+    pagination::{Cursor, CursorDirection},
+    query_params::SortOrder,
+};
 
-use crate::model::product::{Price, Product};
+use crate::model::product::{
+    deal::{DealSortBy, GOOD_PRICE_DISCOUNT_PERCENT},
+    filter::{Facet, FlagFacets, ProductFacets, ProductFilter, ProductSortBy},
+    promotion::Promotion,
+    search::SearchLanguage,
+    suggestion::{ProductSuggestion, SearchSuggestions},
+    Price,
+    Product,
+};
 
 pub async fn find_products(
     db_pool: &Pool<Postgres>,
-    search_query: Option<String>,
-    _shopping_list_id: Option<i64>,
+    filter: &ProductFilter,
     sort_by: String,
     sort_order: String,
     limit: usize,
     offset: usize,
+    cursor: Option<&(CursorDirection, Cursor)>,
 ) -> Result<Vec<Product>, Error> {
     // https://www.reddit.com/r/rust/comments/17hoxzl/performance_on_multiple_statements_sqlx_sql/
 
-    let query_sort_order = SortOrder::from_str(sort_order.as_str()).to_string();
-    let statement = if search_query.as_deref().is_some_and(|q| q != "") {
-        format!(
-            // include_str!("./find_products_by_text.sql"),
-            // "german", // TODO localization is needed, do some proper Enum
-            include_str!("./find_products_by_similarity.sql"),
-            search_query.as_ref().unwrap(),
-            search_query.as_ref().unwrap(),
-            limit as i64,
-            offset as i64,
-        )
+    let sort_by = ProductSortBy::from_str(sort_by.as_str());
+    let sort_order = match cursor {
+        // paging backwards walks the keyset in reverse, the rows are flipped back by Pagination
+        Some((CursorDirection::Before, _)) => SortOrder::from_str(sort_order.as_str()).reverse(),
+        _ => SortOrder::from_str(sort_order.as_str()),
+    };
+    let (sort_expression, sort_type) = sort_statement(&sort_by);
+    let order_by = if filter.q.is_some() {
+        search_order_statement(&filter.language)
     } else {
-        format!(
-            include_str!("./find_products.sql"),
-            sort_by,
-            query_sort_order,
-            limit as i64,
-            offset as i64,
-        )
+        format!("{} {}, products_table.id {}", sort_expression, sort_order.to_string(), sort_order.to_string())
     };
-    let query = sqlx::query_as::<_, Product>(statement.as_str());
+    let keyset = format!(
+        "($12::TEXT IS NULL OR ({}, products_table.id) {} ($12::TEXT::{}, $13::TEXT))",
+        sort_expression,
+        match sort_order {
+            SortOrder::Asc => ">",
+            SortOrder::Desc => "<",
+        },
+        sort_type,
+    );
+    let statement = format!(
+        include_str!("./find_products.sql"),
+        sort_expression,
+        headline_statement(&filter.language),
+        product_filter_statement(&filter.language),
+        keyset,
+        order_by,
+    );
+    // a search is ranked and therefore only paginated by offset
+    let cursor = cursor.filter(|_| filter.q.is_none()).map(|(_, cursor)| cursor);
 
-    query
+    bind_product_filter(sqlx::query_as::<_, Product>(statement.as_str()), filter)
+        .bind(limit as i64)
+        .bind(offset as i64)
+        .bind(cursor.map(|cursor| cursor.sort_key.as_str()))
+        .bind(cursor.map(|cursor| cursor.id.as_str()))
         .fetch_all(db_pool)
         .await      
 }
 
+// the sort column and its type, NULL prices are sorted as -1 to keep the keyset comparable
+fn sort_statement(sort_by: &ProductSortBy) -> (&'static str, &'static str) {
+    match sort_by {
+        ProductSortBy::CreatedAt => ("products_table.created_at", "TIMESTAMPTZ"),
+        ProductSortBy::Name => ("products_table.name", "TEXT"),
+        ProductSortBy::Price => ("COALESCE(products_table.price, -1)", "INTEGER"),
+        ProductSortBy::UnitPrice => ("COALESCE(products_table.unit_price, -1)", "INTEGER"),
+    }
+}
+
+// every facet is counted as if its own filter wasn't set, so the alternatives stay visible
+pub async fn find_product_facets(
+    db_pool: &Pool<Postgres>,
+    filter: &ProductFilter,
+    selected_shopping_list_id: Option<i64>,
+) -> Result<ProductFacets, Error> {
+    let market_statement = format!(include_str!("./find_product_market_facets.sql"), product_filter_statement(&filter.language));
+    let category_statement = format!(include_str!("./find_product_category_facets.sql"), product_filter_statement(&filter.language));
+    let flag_statement = format!(
+        include_str!("./find_product_flag_facets.sql"),
+        include_str!("./product_on_sale.sql"),
+        product_filter_statement(&filter.language),
+    );
+    let market_filter = filter.without_markets();
+    let category_filter = filter.without_category();
+    let flag_filter = filter.without_flags();
+
+    let (markets, categories, flags) = try_join!(
+        bind_product_filter(sqlx::query_as::<_, Facet>(market_statement.as_str()), &market_filter)
+            .fetch_all(db_pool),
+        bind_product_filter(sqlx::query_as::<_, Facet>(category_statement.as_str()), &category_filter)
+            .fetch_all(db_pool),
+        bind_product_filter(sqlx::query_as::<_, FlagFacets>(flag_statement.as_str()), &flag_filter)
+            .bind(selected_shopping_list_id)
+            .fetch_one(db_pool),
+    )?;
+
+    Ok(ProductFacets {
+        markets,
+        categories,
+        on_sale_count: flags.on_sale_count,
+        in_list_count: flags.in_list_count,
+    })
+}
+
+const SUGGESTION_LIMIT: usize = 5;
+const SUGGESTION_CACHE_TTL: Duration = Duration::from_secs(60);
+const SUGGESTION_CACHE_CAPACITY: usize = 1000;
+
+// search-as-you-type hits the same short prefixes over and over, keyed by language and lowercased q
+static SUGGESTION_CACHE: Lazy<Mutex<HashMap<String, (Instant, SearchSuggestions)>>> = Lazy::new(|| {
+    Mutex::new(HashMap::new())
+});
+
+pub async fn find_search_suggestions(
+    db_pool: &Pool<Postgres>,
+    q: &str,
+    language: SearchLanguage,
+) -> Result<SearchSuggestions, Error> {
+    let cache_key = get_suggestion_cache_key(q, &language);
+    if let Some(suggestions) = get_cached_suggestions(&cache_key) {
+        return Ok(suggestions);
+    }
+
+    let filter = ProductFilter {
+        q: Some(q.trim().to_string()),
+        language,
+        ..Default::default()
+    };
+    let product_statement = format!(
+        include_str!("./find_product_suggestions.sql"),
+        headline_statement(&filter.language),
+        product_filter_statement(&filter.language),
+        search_order_statement(&filter.language),
+    );
+    let market_statement = format!(include_str!("./find_product_market_facets.sql"), product_filter_statement(&filter.language));
+    let category_statement = format!(include_str!("./find_product_category_facets.sql"), product_filter_statement(&filter.language));
+
+    let (products, mut categories, mut markets) = try_join!(
+        bind_product_filter(sqlx::query_as::<_, ProductSuggestion>(product_statement.as_str()), &filter)
+            .bind(SUGGESTION_LIMIT as i64)
+            .fetch_all(db_pool),
+        bind_product_filter(sqlx::query_as::<_, Facet>(category_statement.as_str()), &filter)
+            .fetch_all(db_pool),
+        bind_product_filter(sqlx::query_as::<_, Facet>(market_statement.as_str()), &filter)
+            .fetch_all(db_pool),
+    )?;
+    categories.truncate(SUGGESTION_LIMIT);
+    markets.truncate(SUGGESTION_LIMIT);
+
+    let suggestions = SearchSuggestions { products, categories, markets };
+    cache_suggestions(cache_key, &suggestions);
+
+    Ok(suggestions)
+}
+
+fn get_suggestion_cache_key(q: &str, language: &SearchLanguage) -> String {
+    format!("{}:{}", language.get_param(), q.trim().to_lowercase())
+}
+
+fn get_cached_suggestions(cache_key: &str) -> Option<SearchSuggestions> {
+    let cache = SUGGESTION_CACHE.lock().ok()?;
+    cache.get(cache_key)
+        .filter(|(cached_at, _)| cached_at.elapsed() < SUGGESTION_CACHE_TTL)
+        .map(|(_, suggestions)| suggestions.clone())
+}
+
+fn cache_suggestions(cache_key: String, suggestions: &SearchSuggestions) {
+    let Ok(mut cache) = SUGGESTION_CACHE.lock() else {
+        return;
+    };
+
+    if cache.len() >= SUGGESTION_CACHE_CAPACITY {
+        cache.retain(|_, (cached_at, _)| cached_at.elapsed() < SUGGESTION_CACHE_TTL);
+    }
+    if cache.len() >= SUGGESTION_CACHE_CAPACITY {
+        cache.clear();
+    }
+
+    cache.insert(cache_key, (Instant::now(), suggestions.clone()));
+}
+
+fn product_filter_statement(language: &SearchLanguage) -> String {
+    format!(
+        include_str!("./product_filter.sql"),
+        on_sale = include_str!("./product_on_sale.sql"),
+        tsvector = tsvector_statement(language),
+        tsquery = tsquery_statement(language),
+    )
+}
+
+// products.full_text_search is generated with the german config, every other language is computed on the fly
+fn tsvector_statement(language: &SearchLanguage) -> String {
+    match language {
+        SearchLanguage::German => "products_table.full_text_search".to_string(),
+        _ => format!("to_tsvector('{}', products_table.name)", language.get_regconfig()),
+    }
+}
+
+// every stemmed word of $9 as prefix, so "vollmi" still finds "Vollmilch"
+fn tsquery_statement(language: &SearchLanguage) -> String {
+    format!(include_str!("./product_search_query.sql"), language.get_regconfig())
+}
+
+// prefix hits first, then the full text rank, trigrams catch typos and compound words.
+// a search of only stopwords has an empty tsquery and no rank, NULL would sort first
+fn search_order_statement(language: &SearchLanguage) -> String {
+    format!(
+        "starts_with(lower(products_table.name), lower($9)) DESC, COALESCE(ts_rank({}, {}), 0) + word_similarity($9, products_table.name) DESC",
+        tsvector_statement(language),
+        tsquery_statement(language),
+    )
+}
+
+// the html escaped name with every match wrapped in <mark>, NULL without a search.
+// names come from feeds and scrapers, <mark> must be the only markup that reaches the template
+fn headline_statement(language: &SearchLanguage) -> String {
+    format!(
+        "ts_headline('{}', replace(replace(replace(products_table.name, '&', '&amp;'), '<', '&lt;'), '>', '&gt;'), {}, 'StartSel=<mark>, StopSel=</mark>, HighlightAll=true')",
+        language.get_regconfig(),
+        tsquery_statement(language),
+    )
+}
+
+// binds $1 to $9 of product_filter.sql
+fn bind_product_filter<'q, O>(
+    query: QueryAs<'q, Postgres, O, PgArguments>,
+    filter: &'q ProductFilter,
+) -> QueryAs<'q, Postgres, O, PgArguments> {
+    let market_ids = if filter.market_ids.is_empty() {
+        None
+    } else {
+        Some(filter.market_ids.as_slice())
+    };
+
+    query
+        .bind(filter.category.as_deref())
+        .bind(market_ids)
+        .bind(filter.min_price)
+        .bind(filter.max_price)
+        .bind(filter.min_unit_price)
+        .bind(filter.max_unit_price)
+        .bind(filter.on_sale)
+        .bind(filter.in_shopping_list_id)
+        .bind(filter.q.as_deref())
+}
+
 pub async fn find_product(
     db_pool: &Pool<Postgres>,
     product_id: &str,
@@ -51,6 +268,93 @@ pub async fn find_product(
         .await    
 }
 
+// products at an all-time low or a good price, see PriceStats::get_deal_badge
+pub async fn find_deals(
+    db_pool: &Pool<Postgres>,
+    sort_by: &DealSortBy,
+    limit: usize,
+    offset: usize,
+) -> Result<(Vec<Product>, u64), Error> {
+    let order_by = match sort_by {
+        DealSortBy::Discount => "COALESCE(product_price_stats.discount_percent, 0) DESC",
+        DealSortBy::Price => "products_table.price ASC",
+        DealSortBy::Name => "products_table.name ASC",
+    };
+    let rows = sqlx::query::<_>(format!(include_str!("./find_deals.sql"), order_by).as_str())
+        .bind(GOOD_PRICE_DISCOUNT_PERCENT)
+        .bind(limit as i64)
+        .bind(offset as i64)
+        .fetch_all(db_pool)
+        .await?;
+
+    let products = rows.iter()
+        .filter_map(|row| Product::from_row(row).ok())
+        .collect::<Vec<Product>>();
+    let total: u64 = match rows.first() {
+        Some(row) => row.try_get::<i64, &str>("total").unwrap_or_default() as u64,
+        None => 0
+    };
+    Ok((products, total))
+}
+
+// the stats are a snapshot, they have to be refreshed after every import
+pub async fn refresh_price_stats(db_pool: &Pool<Postgres>) -> Result<(), Error> {
+    sqlx::query("REFRESH MATERIALIZED VIEW CONCURRENTLY product_price_stats")
+        .execute(db_pool)
+        .await?;
+    Ok(())
+}
+
+// a product that was deleted or removed from its market, its name is all that's left to search with
+pub async fn find_missing_product_name(
+    db_pool: &Pool<Postgres>,
+    product_id: &str,
+) -> Result<Option<String>, Error> {
+    sqlx::query_scalar::<_, String>(include_str!("./find_missing_product_name.sql"))
+        .bind(product_id)
+        .fetch_optional(db_pool)
+        .await
+}
+
+// by trigram similarity of the names, products removed from their market are left out
+pub async fn find_similar_products(
+    db_pool: &Pool<Postgres>,
+    product_id: &str,
+    name: &str,
+    limit: i64,
+) -> Result<Vec<Product>, Error> {
+    sqlx::query_as::<_, Product>(include_str!("./find_similar_products.sql"))
+        .bind(product_id)
+        .bind(name)
+        .bind(limit)
+        .fetch_all(db_pool)
+        .await
+}
+
+// products for a free text, e.g. a shopping list item that isn't linked to one
+pub async fn find_products_by_text(
+    db_pool: &Pool<Postgres>,
+    text: &str,
+    limit: i64,
+) -> Result<Vec<Product>, Error> {
+    sqlx::query_as::<_, Product>(include_str!("./find_products_by_text.sql"))
+        .bind(text)
+        .bind(limit)
+        .fetch_all(db_pool)
+        .await
+}
+
+// the active and upcoming ones
+pub async fn find_product_promotions(
+    db_pool: &Pool<Postgres>,
+    product_id: &str,
+) -> Result<Vec<Promotion>, Error> {
+    sqlx::query_as::<_, Promotion>(include_str!("./find_product_promotions.sql"))
+        .bind(product_id)
+        .fetch_all(db_pool)
+        .await
+}
+
 pub async fn find_product_prices(
     db_pool: &Pool<Postgres>,
     product_id: &str,
@@ -59,4 +363,123 @@ pub async fn find_product_prices(
         .bind(product_id)
         .fetch_all(db_pool)
         .await
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use crate::model::product::deal::DealBadge;
+    use super::*;
+
+    #[test]
+    fn suggestions_are_cached_by_language_and_the_normalized_query() {
+        assert_eq!(get_suggestion_cache_key("  Milch ", &SearchLanguage::German), get_suggestion_cache_key("milch", &SearchLanguage::German));
+        assert_ne!(get_suggestion_cache_key("milch", &SearchLanguage::German), get_suggestion_cache_key("milch", &SearchLanguage::English));
+
+        let cache_key = get_suggestion_cache_key("cache test", &SearchLanguage::Simple);
+        assert!(get_cached_suggestions(&cache_key).is_none());
+        let suggestions = SearchSuggestions {
+            products: vec![ProductSuggestion { id: "lidl-1".to_string(), name: "Milch".to_string(), headline: None, market_id: 1 }],
+            ..Default::default()
+        };
+        cache_suggestions(cache_key.to_owned(), &suggestions);
+        assert_eq!(get_cached_suggestions(&cache_key).map(|cached| cached.products.len()), Some(1));
+    }
+
+    // needs a postgis server, see tests/migrations.rs
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "needs a postgis database in DATABASE_URL"]
+    async fn suggestions_highlight_the_escaped_names_of_matching_products(db_pool: Pool<Postgres>) -> sqlx::Result<()> {
+        for (id, name, market_id) in [
+            ("lidl-1", "Frische Vollmilch <b>3,5 %</b>", 1i64),
+            ("aldisued-1", "Vollmilch & Honig", 2),
+            ("lidl-2", "Butter", 1),
+        ] {
+            sqlx::query("INSERT INTO products (id, name, url, market_id) VALUES ($1, $2, '', $3)")
+                .bind(id)
+                .bind(name)
+                .bind(market_id)
+                .execute(&db_pool)
+                .await?;
+        }
+
+        let suggestions = find_search_suggestions(&db_pool, "Vollmilch", SearchLanguage::German).await?;
+        let mut products = suggestions.products.iter()
+            .map(|product| (product.id.as_str(), product.get_display_name()))
+            .collect::<Vec<_>>();
+        products.sort();
+        assert_eq!(products, vec![
+            ("aldisued-1", "<mark>Vollmilch</mark> &amp; Honig".to_string()),
+            ("lidl-1", "Frische <mark>Vollmilch</mark> &lt;b&gt;3,5 %&lt;/b&gt;".to_string()),
+        ]);
+        let mut markets = suggestions.markets.iter().map(|market| (market.value.as_str(), market.count)).collect::<Vec<_>>();
+        markets.sort();
+        assert_eq!(markets, vec![("1", 1), ("2", 1)]);
+        Ok(())
+    }
+
+    // needs a postgis server, see tests/migrations.rs
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "needs a postgis database in DATABASE_URL"]
+    async fn price_stats_take_the_median_over_the_daily_prices(db_pool: Pool<Postgres>) -> sqlx::Result<()> {
+        // 1,99 for 50 days, then 0,99 for the last 11, a single change must not outweigh the days it lasted
+        sqlx::query("INSERT INTO products (id, name, url, market_id, price, currency) VALUES ('lidl-1', 'Milch', '', 1, 99, 'EUR')")
+            .execute(&db_pool)
+            .await?;
+        sqlx::query("INSERT INTO prices (product_id, price, currency, created_at) VALUES ('lidl-1', 199, 'EUR', NOW() - INTERVAL '60 days'), ('lidl-1', 99, 'EUR', NOW() - INTERVAL '10 days')")
+            .execute(&db_pool)
+            .await?;
+        refresh_price_stats(&db_pool).await?;
+
+        let product = find_product(&db_pool, "lidl-1").await?;
+        let price_stats = product.price_stats.as_ref().expect("price stats");
+        assert_eq!((price_stats.all_time_low, price_stats.all_time_high), (Some(99), Some(199)));
+        assert_eq!((price_stats.median_price, price_stats.discount_percent), (Some(199), Some(50)));
+        assert_eq!(product.get_deal_badge(), Some(DealBadge::AllTimeLow));
+
+        let (deals, total) = find_deals(&db_pool, &DealSortBy::Discount, 10, 0).await?;
+        assert_eq!((deals.len(), total), (1, 1));
+        Ok(())
+    }
+
+    // needs a postgis server, see tests/migrations.rs
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "needs a postgis database in DATABASE_URL"]
+    async fn paging_visits_every_product_once_in_each_sort_order(db_pool: Pool<Postgres>) -> sqlx::Result<()> {
+        // a price without a currency has no current price, the keyset still orders by the price column
+        for (id, price, currency) in [
+            ("lidl-1", Some(100), Some("EUR")),
+            ("lidl-2", None, Some("EUR")),
+            ("lidl-3", Some(100), Some("EUR")),
+            ("lidl-4", Some(200), None),
+            ("lidl-5", Some(50), Some("CHF")),
+        ] {
+            sqlx::query("INSERT INTO products (id, name, url, market_id, price, currency) VALUES ($1, $1, '', 1, $2, $3)")
+                .bind(id)
+                .bind(price)
+                .bind(currency)
+                .execute(&db_pool)
+                .await?;
+        }
+
+        let filter = ProductFilter::default();
+        let page_through = |sort_by: &'static str, sort_order: &'static str| {
+            let (db_pool, filter) = (&db_pool, &filter);
+            async move {
+                let mut ids = vec![];
+                let mut cursor = None;
+                loop {
+                    let page = find_products(db_pool, filter, sort_by.to_string(), sort_order.to_string(), 2, 0, cursor.as_ref()).await?;
+                    let Some(last) = page.last() else { break };
+                    cursor = Some((CursorDirection::After, last.get_list_cursor()));
+                    ids.extend(page.iter().map(|product| product.id.to_owned()));
+                }
+                Ok::<Vec<String>, Error>(ids)
+            }
+        };
+
+        assert_eq!(page_through("price", "asc").await?, vec!["lidl-2", "lidl-5", "lidl-1", "lidl-3", "lidl-4"]);
+        assert_eq!(page_through("price", "desc").await?, vec!["lidl-4", "lidl-3", "lidl-1", "lidl-5", "lidl-2"]);
+        assert_eq!(page_through("created_at", "desc").await?, vec!["lidl-5", "lidl-4", "lidl-3", "lidl-2", "lidl-1"]);
+        assert_eq!(page_through("name", "asc").await?, vec!["lidl-1", "lidl-2", "lidl-3", "lidl-4", "lidl-5"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,28 @@
+(
+    $1::TEXT IS NULL
+    OR products_table.category_id IN (
+      WITH RECURSIVE category_subtree AS (
+        SELECT categories.id FROM categories WHERE categories.slug = $1
+        UNION ALL
+        SELECT categories.id FROM categories INNER JOIN category_subtree ON categories.parent_id = category_subtree.id
+      )
+      SELECT category_subtree.id FROM category_subtree
+    )
+  )
+  AND ($2::BIGINT[] IS NULL OR products_table.market_id = ANY($2))
+  AND ($3::INTEGER IS NULL OR products_table.price >= $3)
+  AND ($4::INTEGER IS NULL OR products_table.price <= $4)
+  AND ($5::INTEGER IS NULL OR products_table.unit_price >= $5)
+  AND ($6::INTEGER IS NULL OR products_table.unit_price <= $6)
+  AND ($7::BOOLEAN IS NOT TRUE OR {on_sale})
+  AND (
+    $8::BIGINT IS NULL
+    OR EXISTS (
+      SELECT 1 FROM shopping_lists_items WHERE shopping_lists_items.shopping_list_id = $8 AND shopping_lists_items.product_id = products_table.id
+    )
+  )
+  AND (
+    $9::TEXT IS NULL
+    OR {tsvector} @@ {tsquery}
+    OR word_similarity($9, products_table.name) > 0.4
+  )
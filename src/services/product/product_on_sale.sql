@@ -0,0 +1,10 @@
+EXISTS (
+    SELECT
+      1
+    FROM
+      prices
+    WHERE
+      prices.product_id = products_table.id
+      AND prices.created_at > NOW() - INTERVAL '30 days'
+      AND prices.price > products_table.price
+  )
@@ -0,0 +1,4 @@
+(
+  SELECT to_tsquery('simple', string_agg(quote_literal(search_lexemes.lexeme) || ':*', ' & '))
+  FROM unnest(to_tsvector('{}', $9)) search_lexemes
+)
@@ -0,0 +1,71 @@
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::model::{import::FeedRecord, market::Market};
+use super::{get_value_string, MarketSource};
+
+const DEFAULT_CURRENCY: &str = "EUR";
+// asset urls are templates, e.g. "https://.../{width}/{slug}.jpg"
+const IMAGE_WIDTH: &str = "500";
+
+// the json product search api, both aldis answer in the same shape
+pub struct AldiSource {
+    pub market: Market,
+    // product pages are linked below it
+    pub web_url: &'static str,
+}
+
+impl MarketSource for AldiSource {
+    fn get_market(&self) -> Market {
+        self.market
+    }
+
+    fn get_response_extension(&self) -> &'static str {
+        "json"
+    }
+
+    fn parse(&self, _url: &str, body: &str, fetched_at: DateTime<Utc>) -> Result<Vec<FeedRecord>, String> {
+        let response = serde_json::from_str::<Value>(body).map_err(|e| format!("invalid json: {e}"))?;
+        let Some(products) = response.get("data").and_then(Value::as_array) else {
+            return Err("expected a \"data\" array".to_string());
+        };
+
+        products.iter().map(|product| self.to_record(product, fetched_at)).collect()
+    }
+}
+
+impl AldiSource {
+    fn to_record(&self, product: &Value, fetched_at: DateTime<Utc>) -> Result<FeedRecord, String> {
+        let sku = product.get("sku").and_then(get_value_string).ok_or("product without \"sku\"")?;
+        let name = product.get("name").and_then(get_value_string)
+            .ok_or_else(|| format!("product {sku} without \"name\""))?;
+        let slug = product.get("urlSlugText").and_then(get_value_string).unwrap_or(sku.to_owned());
+        let price = product.get("price");
+        // already in cents
+        let cents = match price.and_then(|price| price.get("amountRelevant")) {
+            Some(Value::Null) | None => None,
+            Some(cents) => Some(cents.as_i64().and_then(|cents| i32::try_from(cents).ok())
+                .ok_or_else(|| format!("product {sku} with invalid price \"{cents}\""))?),
+        };
+        let images = product.get("assets").and_then(Value::as_array)
+            .map(|assets| assets.iter()
+                .filter_map(|asset| asset.get("url").and_then(get_value_string))
+                .map(|url| url.replace("{width}", IMAGE_WIDTH).replace("{slug}", slug.as_str()))
+                .collect())
+            .unwrap_or_default();
+
+        Ok(FeedRecord {
+            product_id: format!("{}-{}", self.market.get_slug(), sku),
+            name,
+            market_id: self.market.get_id(),
+            images,
+            url: format!("{}/product/{}-{}", self.web_url, slug, sku),
+            price: cents,
+            currency: price.and_then(|price| price.get("currencyCode")).and_then(get_value_string)
+                .unwrap_or(DEFAULT_CURRENCY.to_string())
+                .to_uppercase(),
+            timestamp: fetched_at,
+            promotion: None,
+        })
+    }
+}
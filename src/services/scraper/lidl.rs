@@ -0,0 +1,89 @@
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::model::{import::FeedRecord, market::Market, product::Price};
+use super::{get_value_string, MarketSource};
+
+const DEFAULT_CURRENCY: &str = "EUR";
+
+// product pages, the products are read from their schema.org json-ld
+pub struct LidlSource;
+
+impl MarketSource for LidlSource {
+    fn get_market(&self) -> Market {
+        Market::Lidl
+    }
+
+    fn get_response_extension(&self) -> &'static str {
+        "html"
+    }
+
+    fn parse(&self, url: &str, body: &str, fetched_at: DateTime<Utc>) -> Result<Vec<FeedRecord>, String> {
+        let mut records = vec![];
+        for script in body.split("application/ld+json").skip(1) {
+            let Some((_, script)) = script.split_once('>') else { continue };
+            let Some((json, _)) = script.split_once("</script>") else { continue };
+            let value = serde_json::from_str::<Value>(json.trim()).map_err(|e| format!("invalid json-ld: {e}"))?;
+
+            let mut products = vec![];
+            collect_products(&value, &mut products);
+            for product in products {
+                records.push(to_record(product, url, fetched_at)?);
+            }
+        }
+
+        if records.is_empty() {
+            return Err("no products found".to_string());
+        }
+        Ok(records)
+    }
+}
+
+// json-ld may be a single node, a list of nodes or a @graph
+fn collect_products<'a>(value: &'a Value, products: &mut Vec<&'a Value>) {
+    match value {
+        Value::Array(values) => values.iter().for_each(|value| collect_products(value, products)),
+        Value::Object(object) if object.get("@type").and_then(Value::as_str) == Some("Product") => products.push(value),
+        Value::Object(object) => {
+            if let Some(graph) = object.get("@graph") {
+                collect_products(graph, products);
+            }
+        },
+        _ => {},
+    }
+}
+
+fn to_record(product: &Value, url: &str, fetched_at: DateTime<Utc>) -> Result<FeedRecord, String> {
+    let sku = product.get("sku").or(product.get("productID")).and_then(get_value_string)
+        .ok_or("product without \"sku\"")?;
+    let name = product.get("name").and_then(get_value_string)
+        .ok_or_else(|| format!("product {sku} without \"name\""))?;
+    // several offers are the same product in different shops, the first one is the online shop
+    let offer = match product.get("offers") {
+        Some(Value::Array(offers)) => offers.first(),
+        offer => offer,
+    };
+    let price = match offer.and_then(|offer| offer.get("price")).and_then(get_value_string) {
+        Some(price) => Some(Price::parse_cents(price.as_str()).ok_or_else(|| format!("product {sku} with invalid price \"{price}\""))?),
+        None => None,
+    };
+    let images = match product.get("image") {
+        Some(Value::Array(images)) => images.iter().filter_map(get_value_string).collect(),
+        Some(image) => get_value_string(image).into_iter().collect(),
+        None => vec![],
+    };
+
+    Ok(FeedRecord {
+        product_id: format!("{}-{}", Market::Lidl.get_slug(), sku),
+        name,
+        market_id: Market::Lidl.get_id(),
+        images,
+        url: product.get("url").and_then(get_value_string).unwrap_or(url.to_string()),
+        price,
+        currency: offer.and_then(|offer| offer.get("priceCurrency")).and_then(get_value_string)
+            .unwrap_or(DEFAULT_CURRENCY.to_string())
+            .to_uppercase(),
+        timestamp: fetched_at,
+        promotion: None,
+    })
+}
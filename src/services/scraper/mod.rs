@@ -0,0 +1,211 @@
+use std::path::{Path, PathBuf};
+use std::fs;
+use chrono::{DateTime, Utc};
+use config::{Config, File};
+use once_cell::sync::Lazy;
+use reqwest::Client;
+use serde_json::Value;
+
+use crate::model::{import::FeedRecord, market::Market};
+
+mod aldi;
+mod lidl;
+
+use aldi::AldiSource;
+use lidl::LidlSource;
+
+static HTTP_CLIENT: Lazy<Client> = Lazy::new(|| {
+    Client::builder()
+        .user_agent("preiskampf")
+        .build()
+        .expect("Couldnt build reqwest::Client in scraper module")
+});
+
+// turns the pages of one market into feed records, fetching is left to the `Fetcher` so every source can be replayed offline
+pub trait MarketSource: Send + Sync {
+    fn get_market(&self) -> Market;
+
+    // the extension recorded responses are saved with, e.g. "html"
+    fn get_response_extension(&self) -> &'static str;
+
+    fn parse(&self, url: &str, body: &str, fetched_at: DateTime<Utc>) -> Result<Vec<FeedRecord>, String>;
+}
+
+pub fn get_source(market: Market) -> Box<dyn MarketSource> {
+    match market {
+        Market::Lidl => Box::new(LidlSource),
+        Market::AldiSued => Box::new(AldiSource { market, web_url: "https://www.aldi-sued.de/de" }),
+        Market::AldiNord => Box::new(AldiSource { market, web_url: "https://www.aldi-nord.de" }),
+    }
+}
+
+// the pages to scrape per market slug, e.g. `"lidl": ["https://www.lidl.de/p/..."]`
+pub fn get_source_urls(market: Market) -> Result<Vec<String>, String> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or(".".to_string());
+    let settings = Config::builder()
+        .add_source(File::from(PathBuf::from(format!("{}/config/scraper.json", manifest_dir))))
+        .build()
+        .map_err(|e| format!("scraper config must be provided in /config/scraper.json: {e}"))?;
+
+    Ok(settings.get::<Vec<String>>(market.get_slug()).unwrap_or_default())
+}
+
+pub enum Fetcher {
+    Live,
+    // fetches live and saves every response as a fixture below the directory
+    Record(PathBuf),
+    // reads the fixtures below the directory instead of fetching
+    Replay(PathBuf),
+}
+
+impl Fetcher {
+    async fn fetch(&self, source: &dyn MarketSource, url: &str) -> Result<String, String> {
+        match self {
+            Fetcher::Live => fetch_url(url).await,
+            Fetcher::Record(dir) => {
+                let body = fetch_url(url).await?;
+                let path = get_fixture_path(dir, source, url);
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent).map_err(|e| format!("{}: {e}", parent.display()))?;
+                }
+                fs::write(&path, &body).map_err(|e| format!("{}: {e}", path.display()))?;
+                Ok(body)
+            },
+            Fetcher::Replay(dir) => {
+                let path = get_fixture_path(dir, source, url);
+                fs::read_to_string(&path).map_err(|e| format!("no fixture at {}: {e}", path.display()))
+            },
+        }
+    }
+}
+
+async fn fetch_url(url: &str) -> Result<String, String> {
+    HTTP_CLIENT.get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| e.to_string())?
+        .text()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// e.g. `<dir>/lidl/www.lidl.de_p_milbona-vollmilch_p100336123.html`
+fn get_fixture_path(dir: &Path, source: &dyn MarketSource, url: &str) -> PathBuf {
+    let name = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url)
+        .chars()
+        .map(|char| if char.is_ascii_alphanumeric() || char == '-' || char == '.' { char } else { '_' })
+        .take(150)
+        .collect::<String>();
+
+    dir.join(source.get_market().get_slug())
+        .join(format!("{}.{}", name.trim_end_matches('_'), source.get_response_extension()))
+}
+
+#[derive(Debug, Default)]
+pub struct ScrapeResult {
+    pub records: Vec<FeedRecord>,
+    // one per page that could not be fetched or parsed, the other pages are still scraped
+    pub errors: Vec<String>,
+}
+
+pub async fn scrape_market(source: &dyn MarketSource, urls: &[String], fetcher: &Fetcher) -> ScrapeResult {
+    let mut result = ScrapeResult::default();
+    for url in urls {
+        let fetched_at = Utc::now();
+        let records = match fetcher.fetch(source, url).await {
+            Ok(body) => source.parse(url, body.as_str(), fetched_at),
+            Err(e) => Err(e),
+        };
+        match records {
+            Ok(records) => result.records.extend(records),
+            Err(e) => result.errors.push(format!("{url}: {e}")),
+        }
+    }
+
+    result
+}
+
+// strings and numbers alike, markets aren't consistent about either
+fn get_value_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(value) if !value.trim().is_empty() => Some(value.trim().to_string()),
+        Value::Number(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // replays the configured urls from the recorded responses in fixtures/scraper
+    async fn replay(market: Market) -> ScrapeResult {
+        let fetcher = Fetcher::Replay(Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/scraper"));
+        let urls = get_source_urls(market).unwrap();
+        scrape_market(get_source(market).as_ref(), &urls, &fetcher).await
+    }
+
+    #[test]
+    fn fixture_paths_are_named_after_the_url() {
+        let path = get_fixture_path(Path::new("fixtures"), &LidlSource, "https://www.lidl.de/p/milbona-frische-vollmilch-3-5/p100336123");
+        assert_eq!(path, Path::new("fixtures/lidl/www.lidl.de_p_milbona-frische-vollmilch-3-5_p100336123.html"));
+    }
+
+    #[tokio::test]
+    async fn lidl_products_are_read_from_the_json_ld() {
+        let result = replay(Market::Lidl).await;
+
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        assert_eq!(result.records.len(), 1);
+        let record = &result.records[0];
+        assert_eq!(record.product_id, "lidl-100336123");
+        assert_eq!(record.name, "Milbona Frische Vollmilch 3,5 %");
+        assert_eq!(record.market_id, Market::Lidl.get_id());
+        assert_eq!(record.images, vec![
+            "https://www.lidl.de/assets/gcp1f2a3b4c.jpeg".to_string(),
+            "https://www.lidl.de/assets/gcp5d6e7f8a.jpeg".to_string(),
+        ]);
+        assert_eq!(record.url, "https://www.lidl.de/p/milbona-frische-vollmilch-3-5/p100336123");
+        assert_eq!((record.price, record.currency.as_str()), (Some(109), "EUR"));
+        assert_eq!(record.promotion, None);
+    }
+
+    #[tokio::test]
+    async fn aldi_products_are_read_from_the_search_api() {
+        let result = replay(Market::AldiSued).await;
+
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        assert_eq!(result.records.iter().map(|record| record.product_id.as_str()).collect::<Vec<&str>>(), vec![
+            "aldisued-000000000000412345",
+            "aldisued-000000000000498765",
+        ]);
+        let record = &result.records[0];
+        assert_eq!(record.name, "Milsani Frische Vollmilch 3,5 %");
+        assert_eq!(record.market_id, Market::AldiSued.get_id());
+        assert_eq!(record.images, vec![
+            "https://dm.emea.cms.aldi.cx/is/image/aldiprodeu/product/jpg/scaled/500/milsani-frische-vollmilch-3-5.jpg".to_string(),
+        ]);
+        assert_eq!(record.url, "https://www.aldi-sued.de/de/product/milsani-frische-vollmilch-3-5-000000000000412345");
+        assert_eq!((record.price, record.currency.as_str()), (Some(105), "EUR"));
+        assert!(result.records[1].images.is_empty());
+        assert_eq!(result.records[1].price, Some(99));
+    }
+
+    #[test]
+    fn pages_without_products_are_errors() {
+        let fetched_at = Utc::now();
+        assert!(LidlSource.parse("https://www.lidl.de/", "<html></html>", fetched_at).is_err());
+        assert!(get_source(Market::AldiSued).parse("", r#"{"meta": {}}"#, fetched_at).is_err());
+        assert!(get_source(Market::AldiSued).parse("", r#"{"data": [{"name": "Milch"}]}"#, fetched_at).is_err());
+    }
+
+    #[tokio::test]
+    async fn missing_fixtures_are_reported_per_page() {
+        let fetcher = Fetcher::Replay(Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/scraper"));
+        let result = scrape_market(&LidlSource, &["https://www.lidl.de/p/unbekannt/p1".to_string()], &fetcher).await;
+
+        assert!(result.records.is_empty());
+        assert_eq!(result.errors.len(), 1);
+    }
+}
@@ -0,0 +1,7 @@
+-- any member may copy a list, the copy belongs to the user
+SELECT
+    copy_shopping_list(shopping_lists_access.shopping_list_id, $1, $3, $4) AS id
+FROM
+    shopping_lists_access
+WHERE
+    shopping_lists_access.user_id = $1 AND shopping_lists_access.shopping_list_id = $2
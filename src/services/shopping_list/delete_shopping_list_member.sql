@@ -0,0 +1,12 @@
+-- the owner removes members, members may leave on their own
+DELETE FROM
+    shopping_list_members
+USING
+    shopping_lists
+WHERE
+    shopping_lists.id = shopping_list_members.shopping_list_id
+    AND (shopping_lists.user_id = $1 OR shopping_list_members.user_id = $1)
+    AND shopping_list_members.shopping_list_id = $2
+    AND shopping_list_members.user_id = $3
+RETURNING
+    shopping_list_members.user_id
@@ -0,0 +1,12 @@
+DELETE FROM
+    shopping_lists_items
+USING
+    shopping_lists_access
+WHERE
+    shopping_lists_access.shopping_list_id = shopping_lists_items.shopping_list_id
+    AND shopping_lists_access.user_id = $1
+    AND shopping_lists_access.role IN ('owner', 'editor')
+    AND shopping_lists_items.id = $2
+    AND shopping_lists_items.product_id IS NULL
+RETURNING
+    shopping_lists_items.shopping_list_id, shopping_lists_items.name
@@ -0,0 +1,43 @@
+use std::time::Duration;
+use sqlx::{postgres::PgListener, Pool, Postgres};
+use tokio::sync::broadcast::Sender;
+
+use crate::model::shopping_list::event::ShoppingListEvent;
+
+// the channel the triggers of migrations/0011_notify_shopping_list_changes.sql notify
+const CHANNEL: &str = "shopping_list_changes";
+const RECONNECT_DELAY: Duration = Duration::from_secs(3);
+
+// forwards the changes of every list to the open event streams of this instance, runs as long as the server does
+pub async fn listen_to_shopping_list_events(db_pool: Pool<Postgres>, sender: Sender<ShoppingListEvent>) {
+    loop {
+        let mut listener = match PgListener::connect_with(&db_pool).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                eprintln!("error while connecting the shopping list event listener: {e:?}");
+                tokio::time::sleep(RECONNECT_DELAY).await;
+                continue;
+            },
+        };
+        if let Err(e) = listener.listen(CHANNEL).await {
+            eprintln!("error while listening to {CHANNEL}: {e:?}");
+            tokio::time::sleep(RECONNECT_DELAY).await;
+            continue;
+        }
+
+        // a lost connection is reconnected by the next recv, events sent in between are lost
+        loop {
+            match listener.recv().await {
+                Ok(notification) => match serde_json::from_str::<ShoppingListEvent>(notification.payload()) {
+                    // nobody listening is fine
+                    Ok(event) => _ = sender.send(event),
+                    Err(e) => eprintln!("invalid shopping list event \"{}\": {e:?}", notification.payload()),
+                },
+                Err(e) => {
+                    eprintln!("error while receiving shopping list events: {e:?}");
+                    tokio::time::sleep(RECONNECT_DELAY).await;
+                },
+            }
+        }
+    }
+}
@@ -0,0 +1,18 @@
+-- the templates recurring on the weekday of $1 that weren't copied that day yet, locked so parallel runs skip them
+SELECT
+    shopping_lists.id,
+    shopping_lists.name,
+    shopping_lists.user_id,
+    shopping_lists.emoji_presentation,
+    shopping_lists.created_at,
+    shopping_lists.is_template,
+    shopping_lists.recurring_weekday
+FROM
+    shopping_lists
+WHERE
+    shopping_lists.is_template
+    AND shopping_lists.recurring_weekday = EXTRACT(ISODOW FROM $1::DATE)
+    AND shopping_lists.recurred_on IS DISTINCT FROM $1::DATE
+ORDER BY
+    shopping_lists.id
+FOR UPDATE SKIP LOCKED
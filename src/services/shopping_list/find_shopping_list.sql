@@ -1,6 +1,19 @@
 SELECT 
-    id, name, user_id, emoji_presentation, created_at, COUNT(*) OVER() AS total
+    shopping_lists.id,
+    shopping_lists.name,
+    shopping_lists.user_id,
+    shopping_lists.emoji_presentation,
+    shopping_lists.created_at,
+    shopping_lists.is_template,
+    shopping_lists.recurring_weekday,
+    shopping_lists.item_grouping,
+    shopping_lists.store_id,
+    shopping_lists_access.role,
+    (SELECT users.email FROM users WHERE users.id = shopping_lists.user_id AND users.id <> $2) AS owner_email,
+    COUNT(*) OVER() AS total
 FROM 
     shopping_lists
+INNER JOIN
+    shopping_lists_access ON shopping_lists_access.shopping_list_id = shopping_lists.id
 WHERE
-    id = $1 AND user_id = $2
\ No newline at end of file
+    shopping_lists.id = $1 AND shopping_lists_access.user_id = $2
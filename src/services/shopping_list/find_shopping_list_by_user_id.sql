@@ -1,10 +1,32 @@
+-- the user's own lists first, then the ones shared with them, templates last
 SELECT 
-    id, name, user_id, emoji_presentation, created_at, COUNT(*) OVER() AS total
+    shopping_lists.id,
+    shopping_lists.name,
+    shopping_lists.user_id,
+    shopping_lists.emoji_presentation,
+    shopping_lists.created_at,
+    shopping_lists.is_template,
+    shopping_lists.recurring_weekday,
+    shopping_lists_access.role,
+    owners.email AS owner_email,
+    (
+        SELECT COUNT(*) FROM shopping_list_notifications
+        WHERE shopping_list_notifications.shopping_list_id = shopping_lists.id
+            AND shopping_list_notifications.user_id = $1
+            AND shopping_list_notifications.read_at IS NULL
+    ) AS unread_notification_count,
+    COUNT(*) OVER() AS total
 FROM 
     shopping_lists
+INNER JOIN
+    shopping_lists_access ON shopping_lists_access.shopping_list_id = shopping_lists.id
+LEFT JOIN
+    users AS owners ON owners.id = shopping_lists.user_id AND shopping_lists_access.role <> 'owner'
 WHERE
-    shopping_lists.user_id = $1
+    shopping_lists_access.user_id = $1
+ORDER BY
+    shopping_lists_access.role <> 'owner', shopping_lists.is_template, shopping_lists.created_at, shopping_lists.id
 LIMIT 
     $2
 OFFSET
-    $3
\ No newline at end of file
+    $3
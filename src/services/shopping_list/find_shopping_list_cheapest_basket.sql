@@ -0,0 +1,56 @@
+-- every product of the list replaced by the cheapest of itself and its similar products in the markets $3, e.g. those
+-- with a store in reach. only products sold by the same unit compete, by unit price where there is one
+SELECT
+    basket.*
+FROM (
+    SELECT DISTINCT ON (shopping_lists_items.product_id)
+        candidates.id,
+        candidates.created_at,
+        candidates.name,
+        candidates.images,
+        candidates.url,
+        candidates.market_id,
+        candidates.unit_price,
+        candidates.unit,
+        candidates.price,
+        candidates.currency,
+        shopping_lists_items.amount,
+        shopping_lists_items.amount_unit,
+        shopping_lists_items.position
+    FROM
+        shopping_lists_items
+    INNER JOIN
+        shopping_lists_access ON shopping_lists_access.shopping_list_id = shopping_lists_items.shopping_list_id
+            AND shopping_lists_access.user_id = $2
+    INNER JOIN
+        products ON products.id = shopping_lists_items.product_id
+    INNER JOIN LATERAL (
+        SELECT
+            products.id, products.created_at, products.name, products.images, products.url, products.market_id,
+            products.unit_price, products.unit, products.price, products.currency
+        UNION ALL
+        SELECT
+            similar_products.id, similar_products.created_at, similar_products.name, similar_products.images,
+            similar_products.url, similar_products.market_id, similar_products.unit_price, similar_products.unit,
+            similar_products.price, similar_products.currency
+        FROM
+            products AS similar_products
+        WHERE
+            similar_products.market_id = ANY($3)
+            AND similar_products.market_id <> products.market_id
+            AND similar_products.removed_at IS NULL
+            AND similar_products.price IS NOT NULL
+            AND similar_products.currency IS NOT DISTINCT FROM products.currency
+            AND similar_products.unit IS NOT DISTINCT FROM products.unit
+            AND similar_products.name % products.name
+    ) AS candidates ON TRUE
+    WHERE
+        shopping_lists_items.shopping_list_id = $1
+    ORDER BY
+        shopping_lists_items.product_id,
+        COALESCE(candidates.unit_price, candidates.price) ASC NULLS LAST,
+        -- the product on the list wins a tie
+        candidates.id = products.id DESC
+) AS basket
+ORDER BY
+    basket.position
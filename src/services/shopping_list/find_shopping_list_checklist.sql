@@ -0,0 +1,31 @@
+SELECT
+    shopping_lists_items.created_at AS added_at,
+    shopping_lists_items.position,
+    shopping_lists_items.stale_at,
+    shopping_lists_items.checked_at,
+    shopping_lists_items.amount,
+    shopping_lists_items.amount_unit,
+    products.id,
+    products.created_at,
+    products.name,
+    products.images,
+    products.url,
+    products.market_id,
+    products.category_id,
+    products.price,
+    products.currency,
+    products.unit_price,
+    products.unit
+FROM
+    shopping_lists_items
+INNER JOIN
+    shopping_lists_access ON shopping_lists_access.shopping_list_id = shopping_lists_items.shopping_list_id
+INNER JOIN
+    products ON products.id = shopping_lists_items.product_id
+WHERE
+    shopping_lists_items.shopping_list_id = $1 AND shopping_lists_access.user_id = $2
+ORDER BY
+    shopping_lists_items.checked_at IS NOT NULL,
+    shopping_lists_items.checked_at,
+    shopping_lists_items.position,
+    products.id
@@ -0,0 +1,25 @@
+SELECT
+    shopping_lists_items.shopping_list_id,
+    shopping_lists_items.amount,
+    shopping_lists_items.amount_unit,
+    products.id,
+    products.created_at,
+    products.name,
+    products.images,
+    products.url,
+    products.market_id,
+    products.price,
+    products.currency,
+    products.unit_price,
+    products.unit,
+    -- the age of the price is when it was last checked, the history only grows when it changes
+    products.checked_at AS price_created_at
+FROM
+    shopping_lists_items
+INNER JOIN
+    shopping_lists_access ON shopping_lists_access.shopping_list_id = shopping_lists_items.shopping_list_id
+        AND shopping_lists_access.user_id = $1
+INNER JOIN
+    products ON products.id = shopping_lists_items.product_id
+WHERE
+    shopping_lists_items.shopping_list_id = ANY($2)
@@ -0,0 +1,15 @@
+-- the aisles are the user's, everybody walks through a store their own way
+SELECT
+    shopping_lists.item_grouping,
+    ARRAY(
+        SELECT store_aisles.category_id
+        FROM store_aisles
+        WHERE store_aisles.store_id = shopping_lists.store_id AND store_aisles.user_id = $2
+        ORDER BY store_aisles.position
+    ) AS aisle_category_ids
+FROM
+    shopping_lists
+INNER JOIN
+    shopping_lists_access ON shopping_lists_access.shopping_list_id = shopping_lists.id
+WHERE
+    shopping_lists.id = $1 AND shopping_lists_access.user_id = $2
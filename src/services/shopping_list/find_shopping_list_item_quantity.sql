@@ -0,0 +1,13 @@
+SELECT
+    shopping_lists_items.amount,
+    shopping_lists_items.amount_unit
+FROM
+    shopping_lists_items
+INNER JOIN
+    shopping_lists_access ON shopping_lists_access.shopping_list_id = shopping_lists_items.shopping_list_id
+WHERE
+    shopping_lists_access.user_id = $1
+    AND shopping_lists_access.role IN ('owner', 'editor')
+    AND shopping_lists_items.shopping_list_id = $2
+    AND shopping_lists_items.product_id = $3
+FOR UPDATE OF shopping_lists_items
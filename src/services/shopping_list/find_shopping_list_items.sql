@@ -2,7 +2,9 @@ SELECT
     shopping_lists_items.product_id
 FROM 
     shopping_lists
+INNER JOIN
+    shopping_lists_access ON shopping_lists_access.shopping_list_id = shopping_lists.id
 LEFT JOIN 
     shopping_lists_items ON shopping_lists.id = shopping_lists_items.shopping_list_id
 WHERE
-    shopping_lists.id = $1 AND shopping_lists.user_id = $2
\ No newline at end of file
+    shopping_lists.id = $1 AND shopping_lists_access.user_id = $2
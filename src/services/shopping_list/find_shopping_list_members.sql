@@ -0,0 +1,15 @@
+SELECT
+    shopping_list_members.user_id,
+    shopping_list_members.role,
+    users.email
+FROM
+    shopping_list_members
+INNER JOIN
+    shopping_lists_access ON shopping_lists_access.shopping_list_id = shopping_list_members.shopping_list_id
+INNER JOIN
+    users ON users.id = shopping_list_members.user_id
+WHERE
+    shopping_lists_access.user_id = $1
+    AND shopping_list_members.shopping_list_id = $2
+ORDER BY
+    shopping_list_members.created_at, users.email
@@ -0,0 +1,16 @@
+-- the user's unread notifications of the list, they stay unread until the user marks them
+SELECT
+    shopping_list_notifications.created_at,
+    shopping_list_notifications.change,
+    shopping_list_notifications.subject,
+    users.email AS by_email
+FROM
+    shopping_list_notifications
+INNER JOIN
+    users ON users.id = shopping_list_notifications.by_user_id
+WHERE
+    shopping_list_notifications.user_id = $1
+    AND shopping_list_notifications.shopping_list_id = $2
+    AND shopping_list_notifications.read_at IS NULL
+ORDER BY
+    shopping_list_notifications.created_at DESC
@@ -1,19 +1,41 @@
 SELECT 
     shopping_lists_items.product_id,
     shopping_lists_items.created_at AS added_at,
+    shopping_lists_items.position,
+    shopping_lists_items.stale_at,
+    shopping_lists_items.amount,
+    shopping_lists_items.amount_unit,
     products.id,
     products.created_at,
     products.name,
     products.images,
     products.url,
     products.market_id,
+    products.price,
+    products.currency,
+    products.unit_price,
+    products.unit,
+    product_price_stats.all_time_low,
+    product_price_stats.all_time_high,
+    product_price_stats.median_price,
+    product_price_stats.discount_percent,
     COUNT(*) OVER() AS total
 FROM 
     shopping_lists_items
+INNER JOIN
+    shopping_lists_access ON shopping_lists_access.shopping_list_id = shopping_lists_items.shopping_list_id
+        AND shopping_lists_access.user_id = $6
 LEFT JOIN
     products ON products.id = shopping_lists_items.product_id
+LEFT JOIN
+    product_price_stats ON product_price_stats.product_id = shopping_lists_items.product_id
 WHERE
     shopping_lists_items.shopping_list_id = $1
+    AND shopping_lists_items.product_id IS NOT NULL
+    AND (
+        $4::INTEGER IS NULL
+        OR (shopping_lists_items.position, shopping_lists_items.product_id) {} ($4::INTEGER, $5::TEXT)
+    )
 ORDER BY
-    added_at DESC
-LIMIT $2 OFFSET $3
\ No newline at end of file
+    shopping_lists_items.position {}, shopping_lists_items.product_id {}
+LIMIT $2 OFFSET $3
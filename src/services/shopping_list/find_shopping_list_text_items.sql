@@ -0,0 +1,21 @@
+SELECT
+    shopping_lists_items.id,
+    shopping_lists_items.created_at AS added_at,
+    shopping_lists_items.name,
+    shopping_lists_items.note,
+    shopping_lists_items.amount,
+    shopping_lists_items.amount_unit,
+    shopping_lists_items.checked_at
+FROM
+    shopping_lists_items
+INNER JOIN
+    shopping_lists_access ON shopping_lists_access.shopping_list_id = shopping_lists_items.shopping_list_id
+WHERE
+    shopping_lists_items.shopping_list_id = $1
+    AND shopping_lists_access.user_id = $2
+    AND shopping_lists_items.product_id IS NULL
+ORDER BY
+    shopping_lists_items.checked_at IS NOT NULL,
+    shopping_lists_items.checked_at,
+    shopping_lists_items.position,
+    shopping_lists_items.id
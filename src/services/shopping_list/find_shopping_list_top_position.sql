@@ -0,0 +1,7 @@
+-- the position above the topmost item of the list
+SELECT
+    COALESCE(MIN(shopping_lists_items.position), 1) - 1
+FROM
+    shopping_lists_items
+WHERE
+    shopping_lists_items.shopping_list_id = $1
@@ -0,0 +1,69 @@
+-- records the checked items as a new trip and unchecks them, they stay on the list. no row is returned if nothing was checked
+WITH checked_items AS (
+    SELECT
+        shopping_lists_items.id,
+        shopping_lists_items.product_id,
+        shopping_lists_items.name,
+        shopping_lists_items.amount,
+        shopping_lists_items.amount_unit,
+        shopping_lists_items.checked_at
+    FROM
+        shopping_lists_items
+    INNER JOIN
+        shopping_lists_access ON shopping_lists_access.shopping_list_id = shopping_lists_items.shopping_list_id
+            AND shopping_lists_access.user_id = $1
+            AND shopping_lists_access.role IN ('owner', 'editor')
+    WHERE
+        shopping_lists_items.shopping_list_id = $2
+        AND shopping_lists_items.checked_at IS NOT NULL
+    FOR UPDATE OF
+        shopping_lists_items
+), unchecked_items AS (
+    UPDATE
+        shopping_lists_items
+    SET
+        checked_at = NULL
+    FROM
+        checked_items
+    WHERE
+        shopping_lists_items.id = checked_items.id
+), trip AS (
+    INSERT INTO
+        shopping_trips (shopping_list_id, user_id, started_at)
+    SELECT
+        $2, $1, MIN(checked_items.checked_at)
+    FROM
+        checked_items
+    HAVING
+        COUNT(*) > 0
+    RETURNING
+        id, shopping_list_id, started_at, finished_at
+), trip_items AS (
+    INSERT INTO
+        shopping_trip_items (shopping_trip_id, product_id, name, amount, amount_unit, price, currency, checked_at)
+    SELECT
+        trip.id,
+        checked_items.product_id,
+        COALESCE(products.name, checked_items.name),
+        checked_items.amount,
+        checked_items.amount_unit,
+        products.price,
+        products.currency,
+        checked_items.checked_at
+    FROM
+        trip
+    CROSS JOIN
+        checked_items
+    LEFT JOIN
+        products ON products.id = checked_items.product_id
+    RETURNING
+        shopping_trip_id
+)
+SELECT
+    trip.id,
+    trip.shopping_list_id,
+    trip.started_at,
+    trip.finished_at,
+    (SELECT COUNT(*) FROM trip_items) AS item_count
+FROM
+    trip
@@ -0,0 +1,14 @@
+-- a product that's already on the list is skipped, free text items are always added. the position keeps the order of the file
+INSERT INTO
+    shopping_lists_items (shopping_list_id, product_id, name, amount, amount_unit, note, position)
+SELECT
+    shopping_lists_access.shopping_list_id, $3, $4, $5, $6, $7, $8
+FROM
+    shopping_lists_access
+WHERE
+    shopping_lists_access.user_id = $1
+    AND shopping_lists_access.shopping_list_id = $2
+    AND shopping_lists_access.role IN ('owner', 'editor')
+ON CONFLICT (shopping_list_id, product_id) DO NOTHING
+RETURNING
+    id
@@ -1,4 +0,0 @@
-INSERT INTO 
-    shopping_lists_items (product_id, shopping_list_id, amount) 
-VALUES 
-    ($2, $3, $4)
\ No newline at end of file
@@ -0,0 +1,16 @@
+-- a notification for everybody with access except who made the change, an unread one of the same change is bumped
+INSERT INTO
+    shopping_list_notifications (shopping_list_id, user_id, by_user_id, change, subject)
+SELECT
+    shopping_lists_access.shopping_list_id,
+    shopping_lists_access.user_id,
+    $1,
+    $3,
+    COALESCE((SELECT products.name FROM products WHERE products.id = $5), $4)
+FROM
+    shopping_lists_access
+WHERE
+    shopping_lists_access.shopping_list_id = $2
+    AND shopping_lists_access.user_id <> $1
+ON CONFLICT (user_id, shopping_list_id, by_user_id, change, subject) WHERE read_at IS NULL DO UPDATE SET
+    created_at = NOW()
@@ -0,0 +1,12 @@
+INSERT INTO
+    shopping_lists_items (shopping_list_id, name, amount, amount_unit, note)
+SELECT
+    shopping_lists_access.shopping_list_id, $3, $4, $5, $6
+FROM
+    shopping_lists_access
+WHERE
+    shopping_lists_access.user_id = $1
+    AND shopping_lists_access.shopping_list_id = $2
+    AND shopping_lists_access.role IN ('owner', 'editor')
+RETURNING
+    id, created_at AS added_at, name, note, amount, amount_unit, checked_at
@@ -0,0 +1,16 @@
+-- the name is dropped for the product's, the note is kept
+UPDATE
+    shopping_lists_items
+SET
+    product_id = $4, name = NULL, amount = COALESCE(shopping_lists_items.amount, 1)
+FROM
+    shopping_lists_access
+WHERE
+    shopping_lists_access.shopping_list_id = shopping_lists_items.shopping_list_id
+    AND shopping_lists_access.user_id = $1
+    AND shopping_lists_access.role IN ('owner', 'editor')
+    AND shopping_lists_items.shopping_list_id = $2
+    AND shopping_lists_items.id = $3
+    AND shopping_lists_items.product_id IS NULL
+RETURNING
+    shopping_lists_items.product_id
@@ -1,12 +1,33 @@
-use std::collections::HashSet;
+pub mod events;
 
-use sqlx::{postgres::PgQueryResult, Error, FromRow, Pool, Postgres, Row};
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, NaiveDate, Utc, Weekday};
+use futures::try_join;
+use sqlx::{Error, FromRow, Pool, Postgres, Row};
 use crate::{
-    core::pagination::Pagination,
+    core::pagination::{CursorDirection, Pagination},
     model::{
         product::Product,
-        shopping_list::{ShoppingList, ShoppingListUpdateForm, ToggleShoppingListItemOp},
+        shopping_list::{
+            change::{ShoppingListChange, ShoppingListNotification},
+            cost::ShoppingListCost,
+            grouping::ShoppingListGrouping,
+            import::{ShoppingListImportChoice, ShoppingListImportItem, ShoppingListImportResult},
+            member::{ShoppingListMember, ShoppingListRole},
+            order::ShoppingListItemOrderForm,
+            quantity::{ItemQuantity, QuantityStep},
+            template::{get_iso_weekday, RecurringShoppingList},
+            text_item::ShoppingListTextItem,
+            trip::ShoppingTrip,
+            ShoppingChecklist,
+            ShoppingList,
+            ShoppingListUpdateForm,
+            ToggleShoppingListItemOp,
+        },
+        store::aisle::StoreAisles,
     },
+    services::category::find_category_tree,
 };
 
 
@@ -76,15 +97,33 @@ pub async fn find_shopping_list_items(
 pub async fn find_shopping_list_products(
     db_pool: &Pool<Postgres>, 
     id: &i64,
+    user_id: &i64,
     pagination: &Pagination,
 ) -> Result<(Vec<Product>, u64), Error> {
     let limit = pagination.limit;
-    let page = pagination.page;
-    let offset = page * limit;
-    match sqlx::query::<_>(include_str!("./find_shopping_list_products.sql"))
+    let offset = pagination.get_offset();
+    // paging backwards walks the keyset in reverse, the rows are flipped back by Pagination
+    let (comparator, sort_order) = match pagination.get_cursor() {
+        Some((CursorDirection::Before, _)) => ("<", "DESC"),
+        _ => (">", "ASC"),
+    };
+    let cursor = pagination.get_cursor().and_then(|(_, cursor)| {
+        cursor.sort_key.parse::<i32>().ok().map(|position| (position, cursor.id.as_str()))
+    });
+    let statement = format!(
+        include_str!("./find_shopping_list_products.sql"),
+        comparator,
+        sort_order,
+        sort_order,
+    );
+
+    match sqlx::query::<_>(statement.as_str())
         .bind(id)
-        .bind(limit as i64)
+        .bind(if pagination.is_by_cursor() { limit + 1 } else { limit } as i64)
         .bind(offset as i64)
+        .bind(cursor.map(|(position, _)| position))
+        .bind(cursor.map(|(_, product_id)| product_id))
+        .bind(user_id)
         .fetch_all(db_pool)
         .await {
             Ok(rows) => {
@@ -104,6 +143,115 @@ pub async fn find_shopping_list_products(
         }  
 }
 
+// a single entry of the product list, e.g. to push it to the other collaborators
+pub async fn find_shopping_list_product(
+    db_pool: &Pool<Postgres>,
+    user_id: &i64,
+    shopping_list_id: &i64,
+    product_id: &str,
+) -> Result<Product, Error> {
+    sqlx::query_as::<_, Product>(include_str!("./find_shopping_list_product.sql"))
+        .bind(user_id)
+        .bind(shopping_list_id)
+        .bind(product_id)
+        .fetch_one(db_pool)
+        .await
+}
+
+// the products of the list as bought where they are cheapest among `market_ids`, see the tour
+pub async fn find_shopping_list_cheapest_basket(
+    db_pool: &Pool<Postgres>,
+    id: &i64,
+    user_id: &i64,
+    market_ids: &[i64],
+) -> Result<Vec<Product>, Error> {
+    sqlx::query_as::<_, Product>(include_str!("./find_shopping_list_cheapest_basket.sql"))
+        .bind(id)
+        .bind(user_id)
+        .bind(market_ids)
+        .fetch_all(db_pool)
+        .await
+}
+
+// the estimated cost of several lists at once, e.g. of a page of the overview. lists without products are missing
+pub async fn find_shopping_list_costs(
+    db_pool: &Pool<Postgres>,
+    user_id: &i64,
+    shopping_list_ids: &[i64],
+) -> Result<HashMap<i64, ShoppingListCost>, Error> {
+    let rows = sqlx::query(include_str!("./find_shopping_list_costs.sql"))
+        .bind(user_id)
+        .bind(shopping_list_ids)
+        .fetch_all(db_pool)
+        .await?;
+
+    let mut products_by_list: HashMap<i64, Vec<Product>> = HashMap::new();
+    for row in rows.iter() {
+        products_by_list.entry(row.try_get("shopping_list_id")?).or_default().push(Product::from_row(row)?);
+    }
+
+    Ok(products_by_list.into_iter()
+        .map(|(shopping_list_id, products)| (shopping_list_id, ShoppingListCost::from_products(&products)))
+        .collect())
+}
+
+// every item of the list at once, the unchecked ones first and under the headings of the list's grouping
+pub async fn find_shopping_list_checklist(
+    db_pool: &Pool<Postgres>,
+    id: &i64,
+    user_id: &i64,
+) -> Result<ShoppingChecklist, Error> {
+    let (products, text_items, grouping) = try_join!(
+        sqlx::query_as::<_, Product>(include_str!("./find_shopping_list_checklist.sql"))
+            .bind(id)
+            .bind(user_id)
+            .fetch_all(db_pool),
+        find_shopping_list_text_items(db_pool, id, user_id),
+        sqlx::query(include_str!("./find_shopping_list_grouping.sql"))
+            .bind(id)
+            .bind(user_id)
+            .fetch_one(db_pool),
+    )?;
+
+    let mut checklist = ShoppingChecklist {
+        products,
+        text_items,
+        aisles: StoreAisles { category_ids: grouping.try_get("aisle_category_ids")? },
+        group_names: vec![],
+    };
+    let grouping = grouping.try_get::<Option<String>, _>("item_grouping")?;
+    if let Some(grouping) = grouping.as_deref().and_then(ShoppingListGrouping::from_str) {
+        checklist.group(&grouping, &find_category_tree(db_pool).await?);
+    }
+    Ok(checklist)
+}
+
+pub async fn find_shopping_list_text_items(
+    db_pool: &Pool<Postgres>,
+    id: &i64,
+    user_id: &i64,
+) -> Result<Vec<ShoppingListTextItem>, Error> {
+    sqlx::query_as::<_, ShoppingListTextItem>(include_str!("./find_shopping_list_text_items.sql"))
+        .bind(id)
+        .bind(user_id)
+        .fetch_all(db_pool)
+        .await
+}
+
+pub async fn find_shopping_list_text_item(
+    db_pool: &Pool<Postgres>,
+    user_id: &i64,
+    shopping_list_id: &i64,
+    item_id: &i64,
+) -> Result<ShoppingListTextItem, Error> {
+    sqlx::query_as::<_, ShoppingListTextItem>(include_str!("./find_shopping_list_text_item.sql"))
+        .bind(user_id)
+        .bind(shopping_list_id)
+        .bind(item_id)
+        .fetch_one(db_pool)
+        .await
+}
+
 pub async fn find_shopping_lists(
     db_pool: &Pool<Postgres>, 
     user_id: i64,
@@ -145,34 +293,509 @@ pub async fn delete_shopping_list(
         .await
 }
 
-pub async fn add_product_to_list(
+// returns the id of the copy, items are copied unchecked
+pub async fn copy_shopping_list(
     db_pool: &Pool<Postgres>,
     user_id: &i64,
     shopping_list_id: &i64,
-    product_id: &str,
-    amount: i64,
-) -> Result<PgQueryResult, Error> {
-    sqlx::query(include_str!("./insert_shopping_list_item.sql"))
+    name: &str,
+    is_template: bool,
+) -> Result<i64, Error> {
+    sqlx::query_scalar::<_, i64>(include_str!("./copy_shopping_list.sql"))
         .bind(user_id)
-        .bind(product_id)
         .bind(shopping_list_id)
-        .bind(amount)
-        .execute(db_pool)
+        .bind(name)
+        .bind(is_template)
+        .fetch_one(db_pool)
         .await
 }
 
+pub async fn update_shopping_list_recurrence(
+    db_pool: &Pool<Postgres>,
+    user_id: &i64,
+    shopping_list_id: &i64,
+    weekday: Option<Weekday>,
+) -> Result<(), Error> {
+    sqlx::query(include_str!("./update_shopping_list_recurrence.sql"))
+        .bind(user_id)
+        .bind(shopping_list_id)
+        .bind(weekday.as_ref().map(get_iso_weekday))
+        .fetch_one(db_pool)
+        .await?;
+    Ok(())
+}
+
+pub async fn update_shopping_list_grouping(
+    db_pool: &Pool<Postgres>,
+    user_id: &i64,
+    shopping_list_id: &i64,
+    grouping: Option<ShoppingListGrouping>,
+    store_id: Option<i64>,
+) -> Result<(), Error> {
+    sqlx::query(include_str!("./update_shopping_list_grouping.sql"))
+        .bind(user_id)
+        .bind(shopping_list_id)
+        .bind(grouping.as_ref().map(|grouping| grouping.get_param()))
+        .bind(store_id)
+        .fetch_one(db_pool)
+        .await?;
+    Ok(())
+}
+
+// the items keep the positions they had between them, in the order of the form
+pub async fn update_shopping_list_item_order(
+    db_pool: &Pool<Postgres>,
+    user_id: &i64,
+    form: &ShoppingListItemOrderForm,
+) -> Result<(), Error> {
+    let moved_count = sqlx::query_scalar::<_, i64>(include_str!("./update_shopping_list_item_order.sql"))
+        .bind(user_id)
+        .bind(form.shopping_list_id)
+        .bind(&form.product_ids)
+        .bind(&form.text_item_ids)
+        .fetch_one(db_pool)
+        .await?;
+    if moved_count == 0 {
+        return Err(Error::RowNotFound);
+    }
+    Ok(())
+}
+
+// copies every template recurring on the weekday of `date` into a new list of its owner, once per day.
+// with `dry_run` everything is rolled back and only the created lists are returned.
+pub async fn create_recurring_shopping_lists(
+    db_pool: &Pool<Postgres>,
+    date: NaiveDate,
+    dry_run: bool,
+) -> Result<Vec<RecurringShoppingList>, Error> {
+    let mut transaction = db_pool.begin().await?;
+    let templates = sqlx::query_as::<_, ShoppingList>(include_str!("./find_due_recurring_shopping_lists.sql"))
+        .bind(date)
+        .fetch_all(&mut *transaction)
+        .await?;
+
+    let mut created = Vec::with_capacity(templates.len());
+    for template in templates {
+        let name = template.get_copy_name(false, date);
+        let id = sqlx::query_scalar::<_, i64>(include_str!("./copy_shopping_list.sql"))
+            .bind(template.get_user_id())
+            .bind(template.get_id())
+            .bind(name.as_str())
+            .bind(false)
+            .fetch_one(&mut *transaction)
+            .await?;
+        sqlx::query(include_str!("./update_shopping_list_recurred_on.sql"))
+            .bind(template.get_id())
+            .bind(date)
+            .execute(&mut *transaction)
+            .await?;
+        created.push(RecurringShoppingList { id, template_id: template.id, name });
+    }
+
+    if dry_run {
+        transaction.rollback().await?;
+    } else {
+        transaction.commit().await?;
+    }
+    Ok(created)
+}
+
 pub async fn toggle_shopping_list_item(
     db_pool: &Pool<Postgres>,
     user_id: &i64,
     shopping_list_id: &i64,
     product_id: &str,
-    amount: i64,
+    quantity: &ItemQuantity,
 ) -> Result<ToggleShoppingListItemOp, Error> {
     sqlx::query_as::<_, ToggleShoppingListItemOp>(include_str!("./toggle_shopping_list_item.sql"))
         .bind(user_id)
         .bind(product_id)
         .bind(shopping_list_id)
-        .bind(amount)
+        .bind(quantity.amount)
+        .bind(quantity.unit.get_column())
+        .fetch_one(db_pool)
+        .await
+}
+
+pub async fn set_shopping_list_item_quantity(
+    db_pool: &Pool<Postgres>,
+    user_id: &i64,
+    shopping_list_id: &i64,
+    product_id: &str,
+    quantity: &ItemQuantity,
+) -> Result<ItemQuantity, Error> {
+    sqlx::query_as::<_, ItemQuantity>(include_str!("./update_shopping_list_item_quantity.sql"))
+        .bind(user_id)
+        .bind(shopping_list_id)
+        .bind(product_id)
+        .bind(quantity.amount)
+        .bind(quantity.unit.get_column())
+        .fetch_one(db_pool)
+        .await
+}
+
+// the step depends on the stored unit, so the row is locked until the stepped quantity is written
+pub async fn step_shopping_list_item_quantity(
+    db_pool: &Pool<Postgres>,
+    user_id: &i64,
+    shopping_list_id: &i64,
+    product_id: &str,
+    step: QuantityStep,
+) -> Result<ItemQuantity, Error> {
+    let mut transaction = db_pool.begin().await?;
+    let quantity = sqlx::query_as::<_, ItemQuantity>(include_str!("./find_shopping_list_item_quantity.sql"))
+        .bind(user_id)
+        .bind(shopping_list_id)
+        .bind(product_id)
+        .fetch_one(&mut *transaction)
+        .await?
+        .step(step);
+    let quantity = sqlx::query_as::<_, ItemQuantity>(include_str!("./update_shopping_list_item_quantity.sql"))
+        .bind(user_id)
+        .bind(shopping_list_id)
+        .bind(product_id)
+        .bind(quantity.amount)
+        .bind(quantity.unit.get_column())
+        .fetch_one(&mut *transaction)
+        .await?;
+    transaction.commit().await?;
+
+    Ok(quantity)
+}
+
+// returns when the item was checked, none if it was unchecked
+pub async fn toggle_shopping_list_item_check(
+    db_pool: &Pool<Postgres>,
+    user_id: &i64,
+    shopping_list_id: &i64,
+    product_id: &str,
+) -> Result<Option<DateTime<Utc>>, Error> {
+    sqlx::query_scalar::<_, Option<DateTime<Utc>>>(include_str!("./toggle_shopping_list_item_check.sql"))
+        .bind(user_id)
+        .bind(shopping_list_id)
+        .bind(product_id)
+        .fetch_one(db_pool)
+        .await
+}
+
+// records the checked items as a trip and unchecks them, `RowNotFound` if nothing was checked
+pub async fn finish_shopping_trip(
+    db_pool: &Pool<Postgres>,
+    user_id: &i64,
+    shopping_list_id: &i64,
+) -> Result<ShoppingTrip, Error> {
+    sqlx::query_as::<_, ShoppingTrip>(include_str!("./finish_shopping_trip.sql"))
+        .bind(user_id)
+        .bind(shopping_list_id)
+        .fetch_one(db_pool)
+        .await
+}
+
+pub async fn add_shopping_list_text_item(
+    db_pool: &Pool<Postgres>,
+    user_id: &i64,
+    shopping_list_id: &i64,
+    name: &str,
+    quantity: Option<&ItemQuantity>,
+    note: Option<&str>,
+) -> Result<ShoppingListTextItem, Error> {
+    sqlx::query_as::<_, ShoppingListTextItem>(include_str!("./insert_shopping_list_text_item.sql"))
+        .bind(user_id)
+        .bind(shopping_list_id)
+        .bind(name)
+        .bind(quantity.map(|quantity| quantity.amount))
+        .bind(quantity.and_then(|quantity| quantity.unit.get_column()))
+        .bind(note)
         .fetch_one(db_pool)
         .await
-}
\ No newline at end of file
+}
+
+// adds the reviewed lines of an import at once, `RowNotFound` if the user may not edit the list
+pub async fn import_shopping_list_items(
+    db_pool: &Pool<Postgres>,
+    user_id: &i64,
+    shopping_list_id: &i64,
+    items: &[ShoppingListImportItem],
+) -> Result<ShoppingListImportResult, Error> {
+    let mut transaction = db_pool.begin().await?;
+    let shopping_list = sqlx::query_as::<_, ShoppingList>(include_str!("./find_shopping_list.sql"))
+        .bind(shopping_list_id)
+        .bind(user_id)
+        .fetch_one(&mut *transaction)
+        .await?;
+    if !shopping_list.can_edit() {
+        return Err(Error::RowNotFound);
+    }
+
+    // the imported items go on top of the list in the order of the file, a new item would otherwise go above the last
+    let top_position = sqlx::query_scalar::<_, i32>(include_str!("./find_shopping_list_top_position.sql"))
+        .bind(shopping_list_id)
+        .fetch_one(&mut *transaction)
+        .await?;
+    let first_position = top_position - items.len() as i32 + 1;
+
+    let mut result = ShoppingListImportResult::default();
+    for (index, item) in items.iter().enumerate() {
+        let (product_id, name, quantity) = match &item.choice {
+            // products always have an amount
+            ShoppingListImportChoice::Product(product_id) => (Some(product_id.as_str()), None, Some(item.line.quantity.unwrap_or_default())),
+            ShoppingListImportChoice::Text => (None, Some(item.line.name.as_str()), item.line.quantity),
+            ShoppingListImportChoice::Skip => continue,
+        };
+        let id = sqlx::query_scalar::<_, i64>(include_str!("./insert_shopping_list_import_item.sql"))
+            .bind(user_id)
+            .bind(shopping_list_id)
+            .bind(product_id)
+            .bind(name)
+            .bind(quantity.map(|quantity| quantity.amount))
+            .bind(quantity.and_then(|quantity| quantity.unit.get_column()))
+            .bind(item.line.note.as_deref())
+            .bind(first_position + index as i32)
+            .fetch_optional(&mut *transaction)
+            .await?;
+        match id {
+            Some(_) => result.added_count += 1,
+            None => result.skipped_count += 1,
+        }
+    }
+
+    transaction.commit().await?;
+    Ok(result)
+}
+
+// returns the list the item was deleted from and the item's name
+pub async fn delete_shopping_list_text_item(
+    db_pool: &Pool<Postgres>,
+    user_id: &i64,
+    item_id: &i64,
+) -> Result<(i64, String), Error> {
+    sqlx::query_as::<_, (i64, String)>(include_str!("./delete_shopping_list_text_item.sql"))
+        .bind(user_id)
+        .bind(item_id)
+        .fetch_one(db_pool)
+        .await
+}
+
+pub async fn toggle_shopping_list_text_item_check(
+    db_pool: &Pool<Postgres>,
+    user_id: &i64,
+    shopping_list_id: &i64,
+    item_id: &i64,
+) -> Result<Option<DateTime<Utc>>, Error> {
+    sqlx::query_scalar::<_, Option<DateTime<Utc>>>(include_str!("./toggle_shopping_list_text_item_check.sql"))
+        .bind(user_id)
+        .bind(shopping_list_id)
+        .bind(item_id)
+        .fetch_one(db_pool)
+        .await
+}
+
+// turns the free text item into a product item, a unique violation if the product is already on the list
+pub async fn link_shopping_list_text_item(
+    db_pool: &Pool<Postgres>,
+    user_id: &i64,
+    shopping_list_id: &i64,
+    item_id: &i64,
+    product_id: &str,
+) -> Result<String, Error> {
+    sqlx::query_scalar::<_, String>(include_str!("./link_shopping_list_text_item.sql"))
+        .bind(user_id)
+        .bind(shopping_list_id)
+        .bind(item_id)
+        .bind(product_id)
+        .fetch_one(db_pool)
+        .await
+}
+
+// who else the list is shared with, for everybody with access to it
+pub async fn find_shopping_list_members(
+    db_pool: &Pool<Postgres>,
+    user_id: &i64,
+    shopping_list_id: &i64,
+) -> Result<Vec<ShoppingListMember>, Error> {
+    sqlx::query_as::<_, ShoppingListMember>(include_str!("./find_shopping_list_members.sql"))
+        .bind(user_id)
+        .bind(shopping_list_id)
+        .fetch_all(db_pool)
+        .await
+}
+
+// `RowNotFound` if the user doesn't own the list or the member isn't a confirmed contact
+pub async fn upsert_shopping_list_member(
+    db_pool: &Pool<Postgres>,
+    user_id: &i64,
+    shopping_list_id: &i64,
+    member_user_id: &i64,
+    role: ShoppingListRole,
+) -> Result<ShoppingListMember, Error> {
+    sqlx::query_as::<_, ShoppingListMember>(include_str!("./upsert_shopping_list_member.sql"))
+        .bind(user_id)
+        .bind(shopping_list_id)
+        .bind(member_user_id)
+        .bind(role.get_param())
+        .fetch_one(db_pool)
+        .await
+}
+
+pub async fn delete_shopping_list_member(
+    db_pool: &Pool<Postgres>,
+    user_id: &i64,
+    shopping_list_id: &i64,
+    member_user_id: &i64,
+) -> Result<i64, Error> {
+    sqlx::query_scalar::<_, i64>(include_str!("./delete_shopping_list_member.sql"))
+        .bind(user_id)
+        .bind(shopping_list_id)
+        .bind(member_user_id)
+        .fetch_one(db_pool)
+        .await
+}
+
+// lets everybody else with access to the list know about a change by `user_id`
+pub async fn notify_shopping_list_collaborators(
+    db_pool: &Pool<Postgres>,
+    user_id: &i64,
+    shopping_list_id: &i64,
+    change: ShoppingListChange<'_>,
+) -> Result<u64, Error> {
+    sqlx::query(include_str!("./insert_shopping_list_notifications.sql"))
+        .bind(user_id)
+        .bind(shopping_list_id)
+        .bind(change.get_column())
+        .bind(change.get_subject())
+        .bind(change.get_product_id())
+        .execute(db_pool)
+        .await
+        .map(|result| result.rows_affected())
+}
+
+// the unread notifications of the list
+pub async fn find_shopping_list_notifications(
+    db_pool: &Pool<Postgres>,
+    user_id: &i64,
+    shopping_list_id: &i64,
+) -> Result<Vec<ShoppingListNotification>, Error> {
+    sqlx::query_as::<_, ShoppingListNotification>(include_str!("./find_shopping_list_notifications.sql"))
+        .bind(user_id)
+        .bind(shopping_list_id)
+        .fetch_all(db_pool)
+        .await
+}
+
+// returns how many were unread
+pub async fn read_shopping_list_notifications(
+    db_pool: &Pool<Postgres>,
+    user_id: &i64,
+    shopping_list_id: &i64,
+) -> Result<u64, Error> {
+    sqlx::query(include_str!("./update_shopping_list_notifications_read.sql"))
+        .bind(user_id)
+        .bind(shopping_list_id)
+        .execute(db_pool)
+        .await
+        .map(|result| result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use super::*;
+
+    async fn insert_user(db_pool: &Pool<Postgres>, email: &str) -> sqlx::Result<i64> {
+        sqlx::query_scalar::<_, i64>("INSERT INTO users (email) VALUES ($1) RETURNING id")
+            .bind(email)
+            .fetch_one(db_pool)
+            .await
+    }
+
+    // needs a postgis server, see tests/migrations.rs
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "needs a postgis database in DATABASE_URL"]
+    async fn shared_lists_are_only_changed_by_the_owner_and_editors(db_pool: Pool<Postgres>) -> sqlx::Result<()> {
+        let owner_id = insert_user(&db_pool, "anna@example.com").await?;
+        let member_id = insert_user(&db_pool, "ben@example.com").await?;
+        let stranger_id = insert_user(&db_pool, "carla@example.com").await?;
+        sqlx::query("INSERT INTO contacts (by_user_id, to_user_id, state) VALUES ($1, $2, 'confirmed'), ($1, $3, 'pending')")
+            .bind(owner_id)
+            .bind(member_id)
+            .bind(stranger_id)
+            .execute(&db_pool)
+            .await?;
+        sqlx::query("INSERT INTO products (id, name, url, market_id) VALUES ('lidl-1', 'Butter', '', 1)")
+            .execute(&db_pool)
+            .await?;
+        let shopping_list_id = sqlx::query_scalar::<_, i64>("INSERT INTO shopping_lists (name, user_id) VALUES ('Wocheneinkauf', $1) RETURNING id")
+            .bind(owner_id)
+            .fetch_one(&db_pool)
+            .await?;
+        let quantity = ItemQuantity::default();
+
+        // only confirmed contacts, only by the owner
+        assert!(matches!(upsert_shopping_list_member(&db_pool, &owner_id, &shopping_list_id, &stranger_id, ShoppingListRole::Viewer).await, Err(Error::RowNotFound)));
+        assert!(matches!(upsert_shopping_list_member(&db_pool, &member_id, &shopping_list_id, &owner_id, ShoppingListRole::Editor).await, Err(Error::RowNotFound)));
+        let member = upsert_shopping_list_member(&db_pool, &owner_id, &shopping_list_id, &member_id, ShoppingListRole::Viewer).await?;
+        assert_eq!((member.email.as_str(), member.role), ("ben@example.com", ShoppingListRole::Viewer));
+
+        let shopping_list = find_shopping_list(&db_pool, &shopping_list_id, &member_id).await?;
+        assert_eq!((shopping_list.role, shopping_list.owner_email.as_deref()), (ShoppingListRole::Viewer, Some("anna@example.com")));
+        assert!(matches!(find_shopping_list(&db_pool, &shopping_list_id, &stranger_id).await, Err(Error::RowNotFound)));
+
+        // viewers and strangers can't change the items
+        assert!(matches!(toggle_shopping_list_item(&db_pool, &member_id, &shopping_list_id, "lidl-1", &quantity).await, Err(Error::RowNotFound)));
+        assert!(matches!(toggle_shopping_list_item(&db_pool, &stranger_id, &shopping_list_id, "lidl-1", &quantity).await, Err(Error::RowNotFound)));
+
+        // sharing again changes the role
+        upsert_shopping_list_member(&db_pool, &owner_id, &shopping_list_id, &member_id, ShoppingListRole::Editor).await?;
+        toggle_shopping_list_item(&db_pool, &member_id, &shopping_list_id, "lidl-1", &quantity).await?;
+        assert_eq!(notify_shopping_list_collaborators(&db_pool, &member_id, &shopping_list_id, ShoppingListChange::ProductAdded("lidl-1")).await?, 1);
+        // an unread notification of the same change is bumped, not repeated
+        assert_eq!(notify_shopping_list_collaborators(&db_pool, &member_id, &shopping_list_id, ShoppingListChange::ProductAdded("lidl-1")).await?, 1);
+
+        let notifications = find_shopping_list_notifications(&db_pool, &owner_id, &shopping_list_id).await?;
+        assert_eq!(notifications.iter().map(|notification| notification.format()).collect::<Vec<_>>(), vec!["ben@example.com hat „Butter“ hinzugefügt"]);
+        assert!(find_shopping_list_notifications(&db_pool, &member_id, &shopping_list_id).await?.is_empty());
+        assert_eq!(read_shopping_list_notifications(&db_pool, &owner_id, &shopping_list_id).await?, 1);
+        assert!(find_shopping_list_notifications(&db_pool, &owner_id, &shopping_list_id).await?.is_empty());
+
+        // removing the member takes the access away again
+        delete_shopping_list_member(&db_pool, &owner_id, &shopping_list_id, &member_id).await?;
+        assert!(matches!(find_shopping_list(&db_pool, &shopping_list_id, &member_id).await, Err(Error::RowNotFound)));
+        Ok(())
+    }
+
+    // needs a postgis server, see tests/migrations.rs
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "needs a postgis database in DATABASE_URL"]
+    async fn recurring_templates_are_copied_once_on_their_weekday(db_pool: Pool<Postgres>) -> sqlx::Result<()> {
+        let user_id = insert_user(&db_pool, "anna@example.com").await?;
+        sqlx::query("INSERT INTO products (id, name, url, market_id) VALUES ('lidl-1', 'Butter', '', 1)")
+            .execute(&db_pool)
+            .await?;
+        let template_id = sqlx::query_scalar::<_, i64>("INSERT INTO shopping_lists (name, user_id, is_template) VALUES ('Wocheneinkauf', $1, TRUE) RETURNING id")
+            .bind(user_id)
+            .fetch_one(&db_pool)
+            .await?;
+        toggle_shopping_list_item(&db_pool, &user_id, &template_id, "lidl-1", &ItemQuantity::default()).await?;
+        update_shopping_list_recurrence(&db_pool, &user_id, &template_id, Some(Weekday::Mon)).await?;
+
+        let monday = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+        assert!(create_recurring_shopping_lists(&db_pool, monday.succ_opt().unwrap(), false).await?.is_empty());
+        assert_eq!(create_recurring_shopping_lists(&db_pool, monday, true).await?.len(), 1);
+
+        let created = create_recurring_shopping_lists(&db_pool, monday, false).await?;
+        assert_eq!(created.len(), 1);
+        assert_eq!((created[0].template_id, created[0].name.as_str()), (template_id, "Wocheneinkauf 19.10."));
+        let copy = find_shopping_list(&db_pool, &created[0].id, &user_id).await?;
+        assert!(!copy.is_template);
+        let items = sqlx::query_scalar::<_, String>("SELECT product_id FROM shopping_lists_items WHERE shopping_list_id = $1")
+            .bind(created[0].id)
+            .fetch_all(&db_pool)
+            .await?;
+        assert_eq!(items, vec!["lidl-1"]);
+
+        // the dry run left nothing behind and a second run on the same day creates nothing
+        assert!(create_recurring_shopping_lists(&db_pool, monday, false).await?.is_empty());
+        assert_eq!(create_recurring_shopping_lists(&db_pool, monday + Duration::days(7), false).await?.len(), 1);
+        Ok(())
+    }
+}
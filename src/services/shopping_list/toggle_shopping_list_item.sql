@@ -1 +1,8 @@
-SELECT toggle_shopping_list_item($2, $3);
\ No newline at end of file
+SELECT
+    toggle_shopping_list_item($2, $3, $4, $5)
+FROM
+    shopping_lists_access
+WHERE
+    shopping_lists_access.user_id = $1
+    AND shopping_lists_access.shopping_list_id = $3
+    AND shopping_lists_access.role IN ('owner', 'editor')
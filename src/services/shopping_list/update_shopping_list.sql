@@ -1,8 +1,8 @@
 UPDATE 
     shopping_lists 
 SET 
-    user_id = $1, name = $2, emoji_presentation = $3 
+    name = $2, emoji_presentation = $3 
 WHERE 
-    id = $4 
+    id = $4 AND user_id = $1
 RETURNING 
-    id, name, user_id, emoji_presentation, created_at
\ No newline at end of file
+    id, name, user_id, emoji_presentation, created_at, is_template, recurring_weekday
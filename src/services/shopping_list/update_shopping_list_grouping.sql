@@ -0,0 +1,13 @@
+UPDATE
+    shopping_lists
+SET
+    item_grouping = $3, store_id = $4
+FROM
+    shopping_lists_access
+WHERE
+    shopping_lists_access.shopping_list_id = shopping_lists.id
+    AND shopping_lists_access.user_id = $1
+    AND shopping_lists_access.role IN ('owner', 'editor')
+    AND shopping_lists.id = $2
+RETURNING
+    shopping_lists.id
@@ -0,0 +1,48 @@
+-- the moved items swap their positions among each other, so a page of the list can be reordered on its own.
+-- either $3 or $4 is empty and neither repeats an id, see ShoppingListItemOrderForm::from_pairs
+WITH moved AS (
+    SELECT
+        shopping_lists_items.id,
+        ROW_NUMBER() OVER (ORDER BY dropped.ordinality) AS ordinality
+    FROM
+        shopping_lists_items
+    INNER JOIN
+        shopping_lists_access ON shopping_lists_access.shopping_list_id = shopping_lists_items.shopping_list_id
+    INNER JOIN (
+        SELECT product_id, NULL::BIGINT AS id, ordinality FROM unnest($3::TEXT[]) WITH ORDINALITY AS product_ids (product_id, ordinality)
+        UNION ALL
+        SELECT NULL, id, ordinality FROM unnest($4::BIGINT[]) WITH ORDINALITY AS text_item_ids (id, ordinality)
+    ) AS dropped ON dropped.product_id = shopping_lists_items.product_id
+        OR (dropped.id = shopping_lists_items.id AND shopping_lists_items.product_id IS NULL)
+    WHERE
+        shopping_lists_items.shopping_list_id = $2
+        AND shopping_lists_access.user_id = $1
+        AND shopping_lists_access.role IN ('owner', 'editor')
+),
+positions AS (
+    SELECT
+        shopping_lists_items.position,
+        ROW_NUMBER() OVER (ORDER BY shopping_lists_items.position, shopping_lists_items.id) AS ordinality
+    FROM
+        shopping_lists_items
+    INNER JOIN
+        moved ON moved.id = shopping_lists_items.id
+),
+-- the items that stay where they are aren't touched, so no change is pushed for them
+updated AS (
+    UPDATE
+        shopping_lists_items
+    SET
+        position = positions.position
+    FROM
+        moved
+    INNER JOIN
+        positions ON positions.ordinality = moved.ordinality
+    WHERE
+        shopping_lists_items.id = moved.id
+        AND shopping_lists_items.position <> positions.position
+    RETURNING
+        shopping_lists_items.id
+)
+-- none if the user can't edit the list or none of the items is on it
+SELECT COUNT(*) FROM moved
@@ -0,0 +1,14 @@
+UPDATE
+    shopping_lists_items
+SET
+    amount = $4, amount_unit = $5
+FROM
+    shopping_lists_access
+WHERE
+    shopping_lists_access.shopping_list_id = shopping_lists_items.shopping_list_id
+    AND shopping_lists_access.user_id = $1
+    AND shopping_lists_access.role IN ('owner', 'editor')
+    AND shopping_lists_items.shopping_list_id = $2
+    AND shopping_lists_items.product_id = $3
+RETURNING
+    shopping_lists_items.amount, shopping_lists_items.amount_unit
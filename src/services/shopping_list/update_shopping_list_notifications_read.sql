@@ -0,0 +1,6 @@
+UPDATE
+    shopping_list_notifications
+SET
+    read_at = NOW()
+WHERE
+    user_id = $1 AND shopping_list_id = $2 AND read_at IS NULL
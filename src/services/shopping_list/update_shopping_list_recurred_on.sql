@@ -0,0 +1,6 @@
+UPDATE
+    shopping_lists
+SET
+    recurred_on = $2
+WHERE
+    id = $1
@@ -0,0 +1,9 @@
+-- only the owner decides when a template recurs
+UPDATE
+    shopping_lists
+SET
+    recurring_weekday = $3
+WHERE
+    id = $2 AND user_id = $1 AND is_template
+RETURNING
+    id
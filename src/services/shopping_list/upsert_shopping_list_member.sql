@@ -0,0 +1,24 @@
+-- only the owner shares a list and only with confirmed contacts, sharing it again changes the role
+INSERT INTO
+    shopping_list_members (shopping_list_id, user_id, role)
+SELECT
+    shopping_lists.id, $3, $4
+FROM
+    shopping_lists
+WHERE
+    shopping_lists.user_id = $1
+    AND shopping_lists.id = $2
+    AND EXISTS (
+        SELECT 1 FROM contacts
+        WHERE contacts.state = 'confirmed'
+            AND (
+                (contacts.by_user_id = $1 AND contacts.to_user_id = $3)
+                OR (contacts.by_user_id = $3 AND contacts.to_user_id = $1)
+            )
+    )
+ON CONFLICT (shopping_list_id, user_id) DO UPDATE SET
+    role = EXCLUDED.role
+RETURNING
+    user_id,
+    role,
+    (SELECT users.email FROM users WHERE users.id = shopping_list_members.user_id) AS email
@@ -0,0 +1 @@
+DELETE FROM store_aisles WHERE user_id = $1 AND store_id = $2
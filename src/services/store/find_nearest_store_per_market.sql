@@ -0,0 +1,17 @@
+SELECT DISTINCT ON (stores.market_id)
+    stores.id,
+    stores.created_at,
+    stores.market_id,
+    stores.name,
+    stores.address,
+    stores.opening_hours,
+    st_x(stores.location::geometry) AS lng,
+    st_y(stores.location::geometry) AS lat,
+    st_distance(stores.location, st_setsrid(st_makepoint($1, $2), 4326)::geography) AS distance_in_meters
+FROM
+    stores
+WHERE
+    stores.market_id = ANY($4)
+    AND st_dwithin(stores.location, st_setsrid(st_makepoint($1, $2), 4326)::geography, $3)
+ORDER BY
+    stores.market_id, distance_in_meters ASC
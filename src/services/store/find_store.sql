@@ -0,0 +1,13 @@
+SELECT
+    stores.id,
+    stores.created_at,
+    stores.market_id,
+    stores.name,
+    stores.address,
+    stores.opening_hours,
+    st_x(stores.location::geometry) AS lng,
+    st_y(stores.location::geometry) AS lat
+FROM
+    stores
+WHERE
+    stores.id = $1
@@ -0,0 +1,8 @@
+SELECT
+    store_aisles.category_id
+FROM
+    store_aisles
+WHERE
+    store_aisles.user_id = $1 AND store_aisles.store_id = $2
+ORDER BY
+    store_aisles.position
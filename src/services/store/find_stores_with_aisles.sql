@@ -0,0 +1,15 @@
+SELECT
+    stores.id,
+    stores.created_at,
+    stores.market_id,
+    stores.name,
+    stores.address,
+    stores.opening_hours,
+    st_x(stores.location::geometry) AS lng,
+    st_y(stores.location::geometry) AS lat
+FROM
+    stores
+WHERE
+    EXISTS (SELECT 1 FROM store_aisles WHERE store_aisles.store_id = stores.id AND store_aisles.user_id = $1)
+ORDER BY
+    stores.market_id, stores.name
@@ -0,0 +1,12 @@
+-- only top level categories have an aisle
+INSERT INTO store_aisles (user_id, store_id, category_id, position)
+SELECT
+    $1,
+    $2,
+    categories.id,
+    aisles.position
+FROM
+    unnest($3::BIGINT[]) WITH ORDINALITY AS aisles (category_id, position)
+INNER JOIN
+    categories ON categories.id = aisles.category_id AND categories.parent_id IS NULL
+ON CONFLICT (user_id, store_id, category_id) DO NOTHING
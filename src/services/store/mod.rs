@@ -0,0 +1,105 @@
+use sqlx::{Error, FromRow, Pool, Postgres, Row};
+use crate::model::store::{aisle::StoreAisles, Store};
+
+pub const DEFAULT_RADIUS_IN_METERS: u32 = 5000;
+pub const MAX_RADIUS_IN_METERS: u32 = 50000;
+
+pub async fn find_stores_near(
+    db_pool: &Pool<Postgres>,
+    lng: f64,
+    lat: f64,
+    radius_in_meters: u32,
+    limit: usize,
+    offset: usize,
+) -> Result<(Vec<Store>, u64), Error> {
+    let rows = sqlx::query::<_>(include_str!("./find_stores_near.sql"))
+        .bind(lng)
+        .bind(lat)
+        .bind(radius_in_meters.min(MAX_RADIUS_IN_METERS) as f64)
+        .bind(limit as i64)
+        .bind(offset as i64)
+        .fetch_all(db_pool)
+        .await?;
+
+    let stores = rows.iter()
+        .filter_map(|row| Store::from_row(row).ok())
+        .collect::<Vec<Store>>();
+    let total: u64 = match rows.first() {
+        Some(row) => row.try_get::<i64, &str>("total").unwrap_or_default() as u64,
+        None => 0
+    };
+    Ok((stores, total))
+}
+
+pub async fn find_nearest_store_per_market(
+    db_pool: &Pool<Postgres>,
+    lng: f64,
+    lat: f64,
+    radius_in_meters: u32,
+    market_ids: &[i64],
+) -> Result<Vec<Store>, Error> {
+    sqlx::query_as::<_, Store>(include_str!("./find_nearest_store_per_market.sql"))
+        .bind(lng)
+        .bind(lat)
+        .bind(radius_in_meters.min(MAX_RADIUS_IN_METERS) as f64)
+        .bind(market_ids)
+        .fetch_all(db_pool)
+        .await
+}
+
+pub async fn find_store(
+    db_pool: &Pool<Postgres>,
+    id: &i64,
+) -> Result<Store, Error> {
+    sqlx::query_as::<_, Store>(include_str!("./find_store.sql"))
+        .bind(id)
+        .fetch_one(db_pool)
+        .await
+}
+
+// the stores the user has put the aisles of in order, a list can be shopped along them
+pub async fn find_stores_with_aisles(
+    db_pool: &Pool<Postgres>,
+    user_id: &i64,
+) -> Result<Vec<Store>, Error> {
+    sqlx::query_as::<_, Store>(include_str!("./find_stores_with_aisles.sql"))
+        .bind(user_id)
+        .fetch_all(db_pool)
+        .await
+}
+
+pub async fn find_store_aisles(
+    db_pool: &Pool<Postgres>,
+    user_id: &i64,
+    store_id: &i64,
+) -> Result<StoreAisles, Error> {
+    let category_ids = sqlx::query_scalar::<_, i64>(include_str!("./find_store_aisles.sql"))
+        .bind(user_id)
+        .bind(store_id)
+        .fetch_all(db_pool)
+        .await?;
+    Ok(StoreAisles { category_ids })
+}
+
+// replaces the user's aisles of the store, no categories forget them
+pub async fn save_store_aisles(
+    db_pool: &Pool<Postgres>,
+    user_id: &i64,
+    store_id: &i64,
+    category_ids: &[i64],
+) -> Result<(), Error> {
+    let mut transaction = db_pool.begin().await?;
+    sqlx::query(include_str!("./delete_store_aisles.sql"))
+        .bind(user_id)
+        .bind(store_id)
+        .execute(&mut *transaction)
+        .await?;
+    sqlx::query(include_str!("./insert_store_aisles.sql"))
+        .bind(user_id)
+        .bind(store_id)
+        .bind(category_ids)
+        .execute(&mut *transaction)
+        .await?;
+    transaction.commit().await?;
+    Ok(())
+}
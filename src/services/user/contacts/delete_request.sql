@@ -1,7 +1,23 @@
-DELETE FROM
-    contacts 
-WHERE 
-    id = $2
-    AND
-    (to_user_id = $1 OR by_user_id = $1)
-RETURNING *
\ No newline at end of file
+WITH deleted_contact AS (
+    DELETE FROM
+        contacts
+    WHERE
+        id = $2
+        AND
+        (to_user_id = $1 OR by_user_id = $1)
+    RETURNING *
+), revoked_memberships AS (
+    -- lists are only shared with contacts, both lose access to the lists of the other
+    DELETE FROM
+        shopping_list_members
+    USING
+        deleted_contact,
+        shopping_lists
+    WHERE
+        shopping_lists.id = shopping_list_members.shopping_list_id
+        AND (
+            (shopping_lists.user_id = deleted_contact.by_user_id AND shopping_list_members.user_id = deleted_contact.to_user_id)
+            OR (shopping_lists.user_id = deleted_contact.to_user_id AND shopping_list_members.user_id = deleted_contact.by_user_id)
+        )
+)
+SELECT * FROM deleted_contact
@@ -1,9 +1,16 @@
+-- only a list the user may add products to can be selected
 INSERT INTO 
     user_selected_shopping_list (user_id, id) 
-VALUES 
-    ($1, $2)
+SELECT
+    $1, $2
+WHERE EXISTS (
+    SELECT 1 FROM shopping_lists_access
+    WHERE shopping_lists_access.user_id = $1
+        AND shopping_lists_access.shopping_list_id = $2
+        AND shopping_lists_access.role IN ('owner', 'editor')
+)
 ON CONFLICT (user_id) DO UPDATE SET 
     user_id = EXCLUDED.user_id,
     id = EXCLUDED.id
 RETURNING
-    id
\ No newline at end of file
+    id
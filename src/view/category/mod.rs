@@ -0,0 +1,22 @@
+use askama::Template;
+use crate::core::{
+    context::Context, pagination::Pagination
+};
+use crate::core::request_extension::HttpExt;
+use crate::model::category::{Category, CategoryTree};
+use crate::model::product::ListProduct;
+use crate::model::user::User;
+
+use super::misc::NotificationTemplate;
+
+#[derive(Template)]
+#[template(path = "views/category/browse.html")]
+pub struct CategoryBrowseTemplate<'a> {
+    pub category_tree: &'a CategoryTree,
+    pub selected_category: Option<&'a Category>,
+    pub products: Vec<ListProduct<'a>>,
+    pub pagination: &'a Pagination,
+    pub authenticated_user: &'a Option<User>,
+    pub notification: Option<NotificationTemplate<'a>>,
+    pub context: Context<'a>,
+}
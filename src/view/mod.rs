@@ -1,7 +1,10 @@
 pub mod auth;
+pub mod category;
 pub mod misc;
 pub mod product;
 pub mod shopping_list;
 pub mod social_timeline;
 pub mod static_page;
+pub mod store;
+pub mod tour;
 pub mod user;
\ No newline at end of file
@@ -3,7 +3,16 @@ use crate::core::pagination::Pagination;
 use crate::core::request_extension::HttpExt;
 use crate::core::context::Context;
 use crate::view::misc::NotificationTemplate;
-use crate::model::product::{ListProduct, Price, Product};
+use crate::model::category::Category;
+use crate::model::product::{
+    deal::DealSortBy,
+    filter::{ProductFacets, ProductFilter},
+    promotion::Promotion,
+    suggestion::SearchSuggestions,
+    ListProduct,
+    Price,
+    Product,
+};
 use crate::model::user::User;
 
 pub mod price_diagram;
@@ -13,16 +22,42 @@ pub mod price_diagram;
 pub struct ProductDetailTemplate<'a> {
     pub product: &'a Product,
     pub prices: &'a Vec<Price>,
+    pub promotions: &'a Vec<Promotion>,
+    pub category_path: &'a Vec<Category>,
     pub is_liked: bool,
     pub authenticated_user: &'a Option<User>,
     pub notification: Option<NotificationTemplate<'a>>,
     pub context: Context<'a>,
 }
 
+#[derive(Template)]
+#[template(path = "views/product/deals.html")]
+pub struct ProductDealListTemplate<'a> {
+    pub products: Vec<ListProduct<'a>>,
+    pub sort_by: DealSortBy,
+    pub authenticated_user: &'a Option<User>,
+    pub pagination: &'a Pagination,
+    pub notification: Option<NotificationTemplate<'a>>,
+    pub context: Context<'a>,
+}
+
+#[derive(Template)]
+#[template(path = "views/product/not_found.html")]
+pub struct ProductNotFoundTemplate<'a> {
+    pub product_id: &'a str,
+    pub product_name: Option<&'a str>,
+    pub products: Vec<ListProduct<'a>>,
+    pub authenticated_user: &'a Option<User>,
+    pub notification: Option<NotificationTemplate<'a>>,
+    pub context: Context<'a>,
+}
+
 #[derive(Template)]
 #[template(path = "views/product/list.html")]
 pub struct ProductListTemplate<'a> {
     pub products: Vec<ListProduct<'a>>,
+    pub filter: &'a ProductFilter,
+    pub facets: &'a ProductFacets,
     pub authenticated_user: &'a Option<User>,
     pub pagination: &'a Pagination,
     pub notification: Option<NotificationTemplate<'a>>,
@@ -36,4 +71,11 @@ pub struct AddProductToggle<'a> {
     pub action_product_id: &'a String,
     pub action_is_liked: bool,
     pub notification: Option<NotificationTemplate<'a>>,
-}
\ No newline at end of file
+}
+
+#[derive(Template)]
+#[template(path = "views/product/suggestions.html")]
+pub struct SearchSuggestionsTemplate<'a> {
+    pub suggestions: &'a SearchSuggestions,
+    pub q: &'a str,
+}
@@ -1,14 +1,31 @@
+use std::collections::HashMap;
 use askama::Template;
 use crate::core::{
     context::Context, pagination::Pagination
 };
 use crate::core::request_extension::HttpExt;
 use crate::model::product::Product;
-use crate::model::shopping_list::ShoppingList;
-use crate::model::user::User;
+use crate::model::shopping_list::{
+    change::ShoppingListNotification,
+    cost::ShoppingListCost,
+    export::ShoppingListExportFormat,
+    grouping::ShoppingListGrouping,
+    import::{ShoppingListImportChoice, ShoppingListImportMatch},
+    member::ShoppingListMember,
+    print::ShoppingListPrint,
+    quantity::ItemQuantity,
+    text_item::ShoppingListTextItem,
+    ShoppingChecklist,
+    ShoppingList,
+};
+use crate::model::market::Market;
+use crate::model::store::Store;
+use crate::model::user::{User, contacts::LinkedContact};
 
 use super::misc::NotificationTemplate;
 
+pub mod pdf;
+
 const EMOJI_LIST: [u32; 35] = [
     128525, 128526, 129303, 129322, 128571, 9757, 9996, 128513,
     128020, 128022, 128025, 128035, 128048, 129424, 129445, 128106,
@@ -22,6 +39,16 @@ const EMOJI_LIST: [u32; 35] = [
 pub struct ShoppingListDetailTemplate<'a> {
     pub shopping_list: &'a ShoppingList,
     pub selected_products: Option<&'a Vec<Product>>,
+    pub text_items: Option<&'a Vec<ShoppingListTextItem>>,
+    // of all products, not just the ones of the current page
+    pub cost: Option<&'a ShoppingListCost>,
+    pub members: Option<&'a Vec<ShoppingListMember>>,
+    // the owner's confirmed contacts the list can be shared with
+    pub contacts: Option<&'a Vec<LinkedContact>>,
+    // the changes by collaborators since the last visit
+    pub notifications: Option<&'a Vec<ShoppingListNotification>>,
+    // the user's stores with aisles, the list can be shopped along them
+    pub stores: Option<&'a Vec<Store>>,
     pub pagination: Option<&'a Pagination>,
     pub authenticated_user: &'a Option<User>,
     pub notification: Option<NotificationTemplate<'a>>,
@@ -37,15 +64,294 @@ impl<'a> ShoppingListDetailTemplate<'a> {
             "/einkaufszettel/anlegen".to_string()
         }
     }
+
+    fn get_notifications(&self) -> &[ShoppingListNotification] {
+        self.notifications.map(|notifications| notifications.as_slice()).unwrap_or_default()
+    }
+
+    // a member leaving the list
+    fn get_leave_href(&self) -> String {
+        let user_id = self.authenticated_user.as_ref().and_then(|user| *user.get_id()).unwrap_or_default();
+        format!("/shopping_list/member/delete/{}/{}", self.shopping_list.id, user_id)
+    }
+
+    fn render_text_items(&self) -> String {
+        let text_items = match self.text_items {
+            Some(text_items) => text_items,
+            None => return "".to_string(),
+        };
+        ShoppingListTextItemsTemplate {
+            shopping_list_id: self.shopping_list.id,
+            can_edit: self.shopping_list.can_edit(),
+            text_items,
+            notification: None,
+        }.render().unwrap_or_default()
+    }
+
+    fn render_cost(&self) -> String {
+        match self.cost {
+            Some(cost) => ShoppingListCostTemplate { cost }.render().unwrap_or_default(),
+            None => "".to_string(),
+        }
+    }
+
+    fn render_grouping(&self) -> String {
+        let stores = match self.stores {
+            Some(stores) => stores,
+            None => return "".to_string(),
+        };
+        ShoppingListGroupingTemplate {
+            shopping_list: self.shopping_list,
+            stores,
+            notification: None,
+        }.render().unwrap_or_default()
+    }
+
+    fn render_copy(&self) -> String {
+        ShoppingListCopyTemplate {
+            shopping_list: self.shopping_list,
+            notification: None,
+        }.render().unwrap_or_default()
+    }
+
+    fn render_import_export(&self) -> String {
+        ShoppingListImportExportTemplate {
+            shopping_list_id: self.shopping_list.id,
+            can_edit: self.shopping_list.can_edit(),
+            matches: None,
+            notification: None,
+        }.render().unwrap_or_default()
+    }
+
+    fn render_members(&self) -> String {
+        let members = match self.members {
+            Some(members) => members,
+            None => return "".to_string(),
+        };
+        ShoppingListMembersTemplate {
+            shopping_list_id: self.shopping_list.id,
+            is_owner: self.shopping_list.is_owner(),
+            members,
+            contacts: self.contacts.map(|contacts| contacts.as_slice()).unwrap_or_default(),
+            notification: None,
+        }.render().unwrap_or_default()
+    }
+}
+
+// the list laid out for paper, without the navigation of the default layout, see pdf for the download
+#[derive(Template)]
+#[template(path = "views/shopping_list/print.html")]
+pub struct ShoppingListPrintTemplate<'a> {
+    pub print: &'a ShoppingListPrint,
+}
+
+impl<'a> ShoppingListPrintTemplate<'a> {
+    fn get_groupings(&self) -> [ShoppingListGrouping; 2] {
+        ShoppingListGrouping::ALL
+    }
+
+    fn get_market_label(&self, market_id: &i64) -> &'static str {
+        Market::from_id(*market_id).map(|market| market.get_label()).unwrap_or_default()
+    }
 }
 
 #[derive(Template)]
 #[template(path = "views/shopping_list/list.html")]
 pub struct ShoppingListsTemplate<'a> {
     pub shopping_lists: Vec<ShoppingList>,
+    // by list id, lists without products have none
+    pub costs: HashMap<i64, ShoppingListCost>,
     pub authenticated_user: &'a Option<User>,
     pub notification: Option<NotificationTemplate<'a>>,
     pub pagination: &'a Pagination,
     pub errors: &'a Option<Vec<String>>,
     pub context: Context<'a>,
-}
\ No newline at end of file
+}
+
+impl<'a> ShoppingListsTemplate<'a> {
+    fn get_cost(&self, shopping_list_id: &i64) -> Option<&ShoppingListCost> {
+        self.costs.get(shopping_list_id)
+    }
+}
+
+#[derive(Template)]
+#[template(path = "views/shopping_list/item_quantity.html")]
+pub struct ShoppingListItemQuantityTemplate<'a> {
+    pub shopping_list_id: i64,
+    pub product_id: &'a str,
+    pub quantity: &'a ItemQuantity,
+    pub notification: Option<NotificationTemplate<'a>>,
+}
+
+#[derive(Template)]
+#[template(path = "views/shopping_list/shopping_mode.html")]
+pub struct ShoppingModeTemplate<'a> {
+    pub shopping_list: &'a ShoppingList,
+    pub checklist: &'a ShoppingChecklist,
+    pub authenticated_user: &'a Option<User>,
+    pub notification: Option<NotificationTemplate<'a>>,
+    pub context: Context<'a>,
+}
+
+impl<'a> ShoppingModeTemplate<'a> {
+    fn render_checklist(&self) -> String {
+        ShoppingListChecklistTemplate {
+            shopping_list_id: self.shopping_list.id,
+            can_edit: self.shopping_list.can_edit(),
+            checklist: self.checklist,
+            notification: None,
+        }.render().unwrap_or_default()
+    }
+}
+
+#[derive(Template)]
+#[template(path = "views/shopping_list/checklist.html")]
+pub struct ShoppingListChecklistTemplate<'a> {
+    pub shopping_list_id: i64,
+    pub can_edit: bool,
+    pub checklist: &'a ShoppingChecklist,
+    pub notification: Option<NotificationTemplate<'a>>,
+}
+
+#[derive(Template)]
+#[template(path = "views/shopping_list/text_items.html")]
+pub struct ShoppingListTextItemsTemplate<'a> {
+    pub shopping_list_id: i64,
+    pub can_edit: bool,
+    pub text_items: &'a Vec<ShoppingListTextItem>,
+    pub notification: Option<NotificationTemplate<'a>>,
+}
+
+// the headings while shopping and the store whose aisles the categories follow
+#[derive(Template)]
+#[template(path = "views/shopping_list/grouping.html")]
+pub struct ShoppingListGroupingTemplate<'a> {
+    pub shopping_list: &'a ShoppingList,
+    pub stores: &'a [Store],
+    pub notification: Option<NotificationTemplate<'a>>,
+}
+
+impl<'a> ShoppingListGroupingTemplate<'a> {
+    fn get_groupings(&self) -> [ShoppingListGrouping; 2] {
+        ShoppingListGrouping::ALL
+    }
+
+    fn get_market_label(&self, market_id: &i64) -> &'static str {
+        Market::from_id(*market_id).map(|market| market.get_label()).unwrap_or_default()
+    }
+
+    // the store of the list, as long as the user has aisles of it
+    fn get_store(&self) -> Option<&Store> {
+        self.shopping_list.store_id.and_then(|store_id| self.stores.iter().find(|store| store.id == store_id))
+    }
+}
+
+// duplicating a list or saving it as a template, when and whether a template recurs
+#[derive(Template)]
+#[template(path = "views/shopping_list/copy.html")]
+pub struct ShoppingListCopyTemplate<'a> {
+    pub shopping_list: &'a ShoppingList,
+    pub notification: Option<NotificationTemplate<'a>>,
+}
+
+// the export links and the import, with the matched lines to review after a preview
+#[derive(Template)]
+#[template(path = "views/shopping_list/import_export.html")]
+pub struct ShoppingListImportExportTemplate<'a> {
+    pub shopping_list_id: i64,
+    pub can_edit: bool,
+    pub matches: Option<&'a [ShoppingListImportMatch]>,
+    pub notification: Option<NotificationTemplate<'a>>,
+}
+
+impl<'a> ShoppingListImportExportTemplate<'a> {
+    fn get_export_formats(&self) -> [ShoppingListExportFormat; 4] {
+        ShoppingListExportFormat::ALL
+    }
+
+    fn get_export_href(&self, format: &ShoppingListExportFormat) -> String {
+        format!("/shopping_list/export/{}?format={}", self.shopping_list_id, format.get_param())
+    }
+
+    fn get_product_choice(&self, product: &Product) -> String {
+        format!("{}{}", ShoppingListImportChoice::PRODUCT_PREFIX, product.id)
+    }
+
+    fn get_text_choice(&self) -> &'static str {
+        ShoppingListImportChoice::TEXT_PARAM
+    }
+}
+
+#[derive(Template)]
+#[template(path = "views/shopping_list/cost.html")]
+pub struct ShoppingListCostTemplate<'a> {
+    pub cost: &'a ShoppingListCost,
+}
+
+#[derive(Template)]
+#[template(path = "views/shopping_list/text_item_suggestions.html")]
+pub struct ShoppingListTextItemSuggestionsTemplate<'a> {
+    pub shopping_list_id: i64,
+    pub text_item: &'a ShoppingListTextItem,
+    pub products: &'a Vec<Product>,
+}
+
+#[derive(Template)]
+#[template(path = "views/shopping_list/members.html")]
+pub struct ShoppingListMembersTemplate<'a> {
+    pub shopping_list_id: i64,
+    // only the owner shares the list and removes members
+    pub is_owner: bool,
+    pub members: &'a Vec<ShoppingListMember>,
+    pub contacts: &'a [LinkedContact],
+    pub notification: Option<NotificationTemplate<'a>>,
+}
+
+// the fragments pushed to the collaborators that have the list open, swapped in by the LiveUpdates element
+#[derive(Template)]
+#[template(path = "views/shopping_list/live_update.html")]
+pub struct ShoppingListLiveUpdateTemplate<'a> {
+    pub shopping_list: &'a ShoppingList,
+    pub renamed: bool,
+    pub removed_product_ids: &'a [String],
+    pub added_products: &'a [Product],
+    pub updated_products: &'a [Product],
+    pub text_items: Option<&'a Vec<ShoppingListTextItem>>,
+    pub cost: Option<&'a ShoppingListCost>,
+    pub checklist: Option<&'a ShoppingChecklist>,
+}
+
+impl<'a> ShoppingListLiveUpdateTemplate<'a> {
+    fn render_text_items(&self) -> String {
+        let text_items = match self.text_items {
+            Some(text_items) => text_items,
+            None => return "".to_string(),
+        };
+        ShoppingListTextItemsTemplate {
+            shopping_list_id: self.shopping_list.id,
+            can_edit: self.shopping_list.can_edit(),
+            text_items,
+            notification: None,
+        }.render().unwrap_or_default()
+    }
+
+    fn render_cost(&self) -> String {
+        match self.cost {
+            Some(cost) => ShoppingListCostTemplate { cost }.render().unwrap_or_default(),
+            None => "".to_string(),
+        }
+    }
+
+    fn render_checklist(&self) -> String {
+        let checklist = match self.checklist {
+            Some(checklist) => checklist,
+            None => return "".to_string(),
+        };
+        ShoppingListChecklistTemplate {
+            shopping_list_id: self.shopping_list.id,
+            can_edit: self.shopping_list.can_edit(),
+            checklist,
+            notification: None,
+        }.render().unwrap_or_default()
+    }
+}
@@ -0,0 +1,312 @@
+use pdf_writer::{Content, Finish, Name, Pdf, Rect, Ref, Str, TextStr};
+use crate::model::{market::Market, shopping_list::print::{ShoppingListPrint, ShoppingListPrintItem}};
+
+// A4 in points
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 50.0;
+const LINE_HEIGHT: f32 = 18.0;
+const FONT_SIZE: f32 = 10.0;
+const TITLE_FONT_SIZE: f32 = 18.0;
+const CHECKBOX_SIZE: f32 = 9.0;
+const QUANTITY_X: f32 = MARGIN + 18.0;
+const NAME_X: f32 = MARGIN + 90.0;
+const PRICE_WIDTH: f32 = 70.0;
+const REGULAR_FONT: Name = Name(b"F1");
+const BOLD_FONT: Name = Name(b"F2");
+
+// the print view as a pdf, set in the standard Helvetica so no font has to be embedded
+pub fn render_shopping_list_pdf(print: &ShoppingListPrint) -> Vec<u8> {
+    write_pdf(print.name.as_str(), layout_pages(print))
+}
+
+fn layout_pages(print: &ShoppingListPrint) -> Vec<PdfPage> {
+    let mut pages = vec![];
+    let mut page = PdfPage::new();
+
+    let title = truncate(print.name.as_str(), TITLE_FONT_SIZE, PAGE_WIDTH - 2.0 * MARGIN);
+    page.text(BOLD_FONT, TITLE_FONT_SIZE, MARGIN, title.as_str());
+    page.y -= LINE_HEIGHT;
+    page.gray_text(9.0, MARGIN, format!("{} · {}", print.format_subtitle(), print.grouping.get_label()).as_str());
+    page.y -= LINE_HEIGHT;
+
+    for group in print.groups.iter() {
+        // a heading isn't left alone at the bottom of a page
+        if page.is_full(3.0) {
+            pages.push(std::mem::replace(&mut page, PdfPage::new()));
+        }
+        page.y -= LINE_HEIGHT / 2.0;
+        page.text(BOLD_FONT, 12.0, MARGIN, group.name.as_str());
+        page.y -= LINE_HEIGHT;
+
+        for item in group.items.iter() {
+            if page.is_full(1.0) {
+                pages.push(std::mem::replace(&mut page, PdfPage::new()));
+            }
+            page.item(item);
+            page.y -= LINE_HEIGHT;
+        }
+    }
+
+    if !print.cost.is_empty() {
+        if page.is_full(print.cost.markets.len() as f32 + 3.0) {
+            pages.push(std::mem::replace(&mut page, PdfPage::new()));
+        }
+        page.y -= LINE_HEIGHT / 2.0;
+        page.text(BOLD_FONT, 12.0, MARGIN, "Geschätzte Kosten");
+        page.y -= LINE_HEIGHT;
+        for market in print.cost.markets.iter() {
+            let label = Market::from_id(market.market_id).map(|market| market.get_label()).unwrap_or_default();
+            page.text(REGULAR_FONT, FONT_SIZE, MARGIN, label);
+            page.text_right(REGULAR_FONT, PAGE_WIDTH - MARGIN, market.total.format().as_str());
+            page.y -= LINE_HEIGHT;
+        }
+        page.text(BOLD_FONT, FONT_SIZE, MARGIN, "Gesamt");
+        page.text_right(BOLD_FONT, PAGE_WIDTH - MARGIN, print.cost.get_total().format().as_str());
+    }
+    pages.push(page);
+
+    pages
+}
+
+fn write_pdf(title: &str, pages: Vec<PdfPage>) -> Vec<u8> {
+    let catalog_id = Ref::new(1);
+    let page_tree_id = Ref::new(2);
+    let regular_font_id = Ref::new(3);
+    let bold_font_id = Ref::new(4);
+    let info_id = Ref::new(5);
+    // a page and its content stream each
+    let page_ids = (0..pages.len()).map(|index| (Ref::new(6 + 2 * index as i32), Ref::new(7 + 2 * index as i32))).collect::<Vec<(Ref, Ref)>>();
+
+    let mut pdf = Pdf::new();
+    pdf.catalog(catalog_id).pages(page_tree_id);
+    pdf.pages(page_tree_id).kids(page_ids.iter().map(|(page_id, _)| *page_id)).count(pages.len() as i32);
+    pdf.document_info(info_id).title(TextStr(title)).creator(TextStr("Preiskampf"));
+    pdf.type1_font(regular_font_id).base_font(Name(b"Helvetica")).encoding_predefined(Name(b"WinAnsiEncoding"));
+    pdf.type1_font(bold_font_id).base_font(Name(b"Helvetica-Bold")).encoding_predefined(Name(b"WinAnsiEncoding"));
+
+    for (page, (page_id, content_id)) in pages.into_iter().zip(page_ids) {
+        let mut pdf_page = pdf.page(page_id);
+        pdf_page.media_box(Rect::new(0.0, 0.0, PAGE_WIDTH, PAGE_HEIGHT));
+        pdf_page.parent(page_tree_id);
+        pdf_page.contents(content_id);
+        pdf_page.resources().fonts().pair(REGULAR_FONT, regular_font_id).pair(BOLD_FONT, bold_font_id);
+        pdf_page.finish();
+        pdf.stream(content_id, &page.content.finish());
+    }
+
+    pdf.finish()
+}
+
+// the content of a page and where the next line goes, from the top
+struct PdfPage {
+    content: Content,
+    y: f32,
+}
+
+impl PdfPage {
+    fn new() -> Self {
+        Self { content: Content::new(), y: PAGE_HEIGHT - MARGIN - LINE_HEIGHT }
+    }
+
+    fn is_full(&self, line_count: f32) -> bool {
+        self.y - line_count * LINE_HEIGHT < MARGIN
+    }
+
+    fn text(&mut self, font: Name, size: f32, x: f32, text: &str) {
+        self.content.begin_text();
+        self.content.set_font(font, size);
+        self.content.next_line(x, self.y);
+        self.content.show(Str(encode_win_ansi(text).as_slice()));
+        self.content.end_text();
+    }
+
+    fn gray_text(&mut self, size: f32, x: f32, text: &str) {
+        self.content.set_fill_gray(0.4);
+        self.text(REGULAR_FONT, size, x, text);
+        self.content.set_fill_gray(0.0);
+    }
+
+    // the widths are Helvetica's, they're close enough for the bold prices
+    fn text_right(&mut self, font: Name, right_x: f32, text: &str) {
+        self.text(font, FONT_SIZE, right_x - get_text_width(text, FONT_SIZE), text);
+    }
+
+    // a box to tick off, ticked for items already in the cart
+    fn item(&mut self, item: &ShoppingListPrintItem) {
+        self.content.set_line_width(0.8);
+        self.content.rect(MARGIN, self.y - 1.0, CHECKBOX_SIZE, CHECKBOX_SIZE);
+        self.content.stroke();
+        if item.checked {
+            self.content.move_to(MARGIN + 2.0, self.y + 3.5);
+            self.content.line_to(MARGIN + 4.0, self.y + 1.0);
+            self.content.line_to(MARGIN + 7.5, self.y + 7.0);
+            self.content.stroke();
+        }
+
+        if let Some(quantity_label) = item.quantity_label.as_ref() {
+            self.text(REGULAR_FONT, FONT_SIZE, QUANTITY_X, truncate(quantity_label, FONT_SIZE, NAME_X - QUANTITY_X - 6.0).as_str());
+        }
+        let name = match item.note.as_ref() {
+            Some(note) => format!("{} ({})", item.name, note),
+            None => item.name.to_owned(),
+        };
+        let price_x = PAGE_WIDTH - MARGIN - PRICE_WIDTH;
+        if item.checked {
+            self.content.set_fill_gray(0.5);
+        }
+        self.text(REGULAR_FONT, FONT_SIZE, NAME_X, truncate(name.as_str(), FONT_SIZE, price_x - NAME_X - 6.0).as_str());
+        self.content.set_fill_gray(0.0);
+        if let Some(price_label) = item.price_label.as_ref() {
+            self.text_right(REGULAR_FONT, PAGE_WIDTH - MARGIN, price_label);
+        }
+    }
+}
+
+// shortens the text with an ellipsis to fit `max_width` points at the font size
+fn truncate(text: &str, size: f32, max_width: f32) -> String {
+    if get_text_width(text, size) <= max_width {
+        return text.to_string();
+    }
+    let mut truncated = String::new();
+    for char in text.chars() {
+        if get_text_width(format!("{}{}…", truncated, char).as_str(), size) > max_width {
+            break;
+        }
+        truncated.push(char);
+    }
+    format!("{}…", truncated.trim_end())
+}
+
+fn get_text_width(text: &str, size: f32) -> f32 {
+    text.chars().map(get_char_width).sum::<u32>() as f32 * size / 1000.0
+}
+
+// Helvetica's advance widths in 1/1000 em, from its AFM
+fn get_char_width(char: char) -> u32 {
+    match char {
+        ' ' | '!' | ',' | '.' | '/' | ':' | ';' | 'I' | '[' | '\\' | ']' | 'f' | 't' | '·' => 278,
+        'i' | 'j' | 'l' => 222,
+        '\'' => 191,
+        '"' => 355,
+        '(' | ')' | '-' | '`' | 'r' => 333,
+        '*' => 389,
+        '+' | '<' | '=' | '>' | '~' => 584,
+        '%' => 889,
+        '&' | 'A' | 'B' | 'E' | 'K' | 'P' | 'S' | 'V' | 'X' | 'Y' | 'Ä' => 667,
+        'C' | 'D' | 'H' | 'N' | 'R' | 'U' | 'Ü' => 722,
+        'F' | 'T' | 'Z' | 'ß' => 611,
+        'G' | 'O' | 'Q' | 'Ö' => 778,
+        'J' | 'c' | 'k' | 's' | 'v' | 'x' | 'y' | 'z' => 500,
+        'L' => 556,
+        'M' | 'm' => 833,
+        'W' => 944,
+        'w' => 722,
+        '@' => 1015,
+        '^' => 469,
+        '{' | '}' => 334,
+        '|' => 260,
+        '…' => 1000,
+        _ => 556,
+    }
+}
+
+// the standard fonts only know the Windows-1252 characters, everything else becomes a "?"
+fn encode_win_ansi(text: &str) -> Vec<u8> {
+    text.chars().map(|char| match char {
+        ' '..='~' | '\u{a0}'..='\u{ff}' => char as u8,
+        '€' => 0x80,
+        '‚' => 0x82,
+        '„' => 0x84,
+        '…' => 0x85,
+        '‘' => 0x91,
+        '’' => 0x92,
+        '“' => 0x93,
+        '”' => 0x94,
+        '•' => 0x95,
+        '–' => 0x96,
+        '—' => 0x97,
+        _ => b'?',
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Local;
+    use crate::model::shopping_list::{cost::ShoppingListCost, grouping::ShoppingListGrouping, print::ShoppingListPrintGroup};
+    use super::*;
+
+    fn print_with_items(name: &str, item_names: &[&str]) -> ShoppingListPrint {
+        ShoppingListPrint {
+            shopping_list_id: 1,
+            name: name.to_string(),
+            grouping: ShoppingListGrouping::Category,
+            printed_at: Local::now(),
+            groups: vec![ShoppingListPrintGroup {
+                name: "Obst & Gemüse".to_string(),
+                items: item_names.iter().map(|name| ShoppingListPrintItem {
+                    name: name.to_string(),
+                    quantity_label: Some("2 Stück".to_string()),
+                    note: None,
+                    price_label: Some("1,98 €".to_string()),
+                    checked: false,
+                }).collect(),
+            }],
+            cost: ShoppingListCost::default(),
+        }
+    }
+
+    #[test]
+    fn truncate_keeps_short_texts_and_shortens_long_ones() {
+        assert_eq!(truncate("Äpfel", FONT_SIZE, 100.0), "Äpfel");
+
+        let truncated = truncate("Bio Vollmilch 3,8 % Fett, länger haltbar", FONT_SIZE, 80.0);
+        assert!(truncated.ends_with('…'), "{}", truncated);
+        assert!(get_text_width(truncated.as_str(), FONT_SIZE) <= 80.0, "{}", truncated);
+        // no space before the ellipsis
+        assert!(!truncated.ends_with(" …"), "{}", truncated);
+
+        // the same text is wider at a larger size
+        assert!(truncate("Wocheneinkauf", TITLE_FONT_SIZE, 80.0).len() < truncate("Wocheneinkauf", FONT_SIZE, 80.0).len());
+        assert_eq!(truncate("Milch", FONT_SIZE, 0.0), "…");
+    }
+
+    #[test]
+    fn win_ansi_encoding_degrades_unknown_characters() {
+        assert_eq!(encode_win_ansi("Käse 1,99 €"), b"K\xe4se 1,99 \x80".to_vec());
+        assert_eq!(encode_win_ansi("„Brot“ – süß…"), b"\x84Brot\x93 \x96 s\xfc\xdf\x85".to_vec());
+        assert_eq!(encode_win_ansi("牛奶 Ωmega 🍎"), b"?? ?mega ?".to_vec());
+        assert_eq!(encode_win_ansi(""), b"".to_vec());
+    }
+
+    #[test]
+    fn long_lists_break_onto_more_pages() {
+        assert_eq!(layout_pages(&print_with_items("Wocheneinkauf", &["Milch"; 10])).len(), 1);
+
+        // about 40 lines fit onto a page
+        let item_names = vec!["Milch"; 100];
+        let pages = layout_pages(&print_with_items("Wocheneinkauf", &item_names));
+        assert_eq!(pages.len(), 3);
+        assert!(pages.iter().all(|page| page.y >= MARGIN - LINE_HEIGHT), "{:?}", pages.iter().map(|page| page.y).collect::<Vec<f32>>());
+
+        let pdf = render_shopping_list_pdf(&print_with_items("Wocheneinkauf", &item_names));
+        assert!(pdf.starts_with(b"%PDF-"));
+        assert!(String::from_utf8_lossy(&pdf).contains("/Count 3"));
+    }
+
+    #[test]
+    fn non_latin_text_and_long_titles_render() {
+        let title = "Einkauf für das große Sommerfest im Garten mit der ganzen Familie und den Nachbarn 🎉";
+        let print = print_with_items(title, &["牛奶", "Ωmega-3 Kapseln", "🍎🍎🍎", ""]);
+
+        assert!(render_shopping_list_pdf(&print).starts_with(b"%PDF-"));
+
+        let content = String::from_utf8_lossy(&layout_pages(&print).remove(0).content.finish()).into_owned();
+        // strings with bytes beyond ascii are shown hex encoded
+        let title_hex = encode_win_ansi("Einkauf für das große Sommerfest im Garten mit der ganze…").iter().map(|byte| format!("{:02X}", byte)).collect::<String>();
+        assert!(content.contains(format!("<{}> Tj", title_hex).as_str()), "{}", content);
+        for shown in ["(??) Tj", "(?mega-3 Kapseln) Tj", "(???) Tj"] {
+            assert!(content.contains(shown), "{}", content);
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use askama::Template;
+use crate::core::{
+    context::Context, pagination::Pagination
+};
+use crate::core::request_extension::HttpExt;
+use crate::model::category::Category;
+use crate::model::store::Store;
+use crate::model::user::User;
+
+use super::misc::NotificationTemplate;
+
+pub const RADIUS_OPTIONS_IN_METERS: [u32; 4] = [1000, 5000, 10000, 25000];
+
+#[derive(Template)]
+#[template(path = "views/store/list.html")]
+pub struct StoreListTemplate<'a> {
+    pub stores: Vec<Store>,
+    pub radius_in_meters: u32,
+    pub has_location: bool,
+    pub authenticated_user: &'a Option<User>,
+    pub notification: Option<NotificationTemplate<'a>>,
+    pub pagination: &'a Pagination,
+    pub context: Context<'a>,
+}
+
+#[derive(Template)]
+#[template(path = "views/store/aisles.html")]
+pub struct StoreAislesTemplate<'a> {
+    pub store: &'a Store,
+    // along the aisles, the ones not placed yet at the end
+    pub categories: Vec<&'a Category>,
+    pub has_aisles: bool,
+    pub authenticated_user: &'a Option<User>,
+    pub notification: Option<NotificationTemplate<'a>>,
+    pub context: Context<'a>,
+}
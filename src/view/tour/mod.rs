@@ -0,0 +1,26 @@
+use askama::Template;
+use crate::core::context::Context;
+use crate::core::request_extension::HttpExt;
+use crate::model::shopping_list::ShoppingList;
+use crate::model::store::format_distance;
+use crate::model::tour::Tour;
+use crate::model::user::User;
+
+use super::misc::NotificationTemplate;
+
+#[derive(Template)]
+#[template(path = "views/tour/detail.html")]
+pub struct TourDetailTemplate<'a> {
+    pub tour: Option<&'a Tour>,
+    pub shopping_list: Option<&'a ShoppingList>,
+    pub radius_in_meters: u32,
+    pub authenticated_user: &'a Option<User>,
+    pub notification: Option<NotificationTemplate<'a>>,
+    pub context: Context<'a>,
+}
+
+impl<'a> TourDetailTemplate<'a> {
+    fn format_radius(&self) -> String {
+        format_distance(self.radius_in_meters as f64)
+    }
+}